@@ -16,6 +16,8 @@ criterion_group!(
     state::marks_cache,
     state::marks_rebuild,
     state::interaction,
+    state::small_menu,
+    state::small_lookup,
     adapters::indexed_tree,
     render::balanced,
     render::deep,
@@ -1,9 +1,11 @@
 use std::hint::black_box;
 
 use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+use rustc_hash::FxHashSet;
+use smallvec::SmallVec;
 use tui_treelistview::{TreeListViewState, TreeModel, TreeQuery};
 
-use super::fixture::BenchTree;
+use super::fixture::{BenchTree, expanded_state};
 
 pub fn marks_cache(c: &mut Criterion) {
     let model = BenchTree::balanced(20_000, 4);
@@ -79,3 +81,64 @@ pub fn interaction(c: &mut Criterion) {
     });
     group.finish();
 }
+
+pub fn small_menu(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small/menu");
+    for size in [8usize, 32, 64] {
+        let model = BenchTree::balanced(size, size);
+        let mut query = TreeQuery::new();
+        let mut state = expanded_state(&model, &query);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("rebuild", size), &size, |b, _| {
+            b.iter(|| {
+                query.touch_sort();
+                let rebuilt = state.ensure_projection(black_box(&model), black_box(&query));
+                black_box((rebuilt, state.visible_len()));
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("lookups", size), &size, |b, _| {
+            b.iter(|| {
+                for id in 0..size {
+                    black_box(state.visible_index_of(black_box(id)));
+                    black_box(state.node_is_expanded(black_box(id), None));
+                }
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("toggle_marks", size), &size, |b, _| {
+            b.iter(|| {
+                let _ = state.toggle_marked(black_box(size - 1));
+                state.ensure_mark_states(black_box(&model));
+                black_box(state.mark_state(0));
+            });
+        });
+    }
+    group.finish();
+}
+
+/// Compares the linear scan used by small state collections with hashing, for choosing the size
+/// at which the collections switch to hashing.
+pub fn small_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small/lookup");
+    for size in [4usize, 8, 16, 32, 64] {
+        let keys: Vec<(Option<usize>, usize)> =
+            (0..size).map(|id| (id.checked_sub(1), id)).collect();
+        let scanned: SmallVec<[(Option<usize>, usize); 8]> = keys.iter().copied().collect();
+        let hashed: FxHashSet<(Option<usize>, usize)> = keys.iter().copied().collect();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("scan", size), &size, |b, _| {
+            b.iter(|| {
+                for key in &keys {
+                    black_box(scanned.contains(black_box(key)));
+                }
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("hash", size), &size, |b, _| {
+            b.iter(|| {
+                for key in &keys {
+                    black_box(hashed.contains(black_box(key)));
+                }
+            });
+        });
+    }
+    group.finish();
+}
@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use chrono::{DateTime, Local};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyEventKind};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::Line;
 use ratatui::widgets::Cell;
@@ -14,9 +14,9 @@ use smallvec::smallvec;
 
 use tui_treelistview::{
     ColumnDef, ColumnWidth, TreeChangeSet, TreeChildren, TreeColumnSet, TreeEditCommand,
-    TreeEditRequest, TreeEditor, TreeEvent, TreeInsertPosition, TreeIntent, TreeLabelPrefix,
-    TreeLabelProvider, TreeListView, TreeListViewState, TreeListViewStyle, TreeModel, TreeQuery,
-    TreeRevision, TreeRowContext, TreeSelectionUpdate,
+    TreeEditRequest, TreeEditor, TreeEvent, TreeInsertPosition, TreeIntent, TreeKey, TreeKeyCode,
+    TreeKeyDispatch, TreeLabelPrefix, TreeLabelProvider, TreeListView, TreeListViewState,
+    TreeListViewStyle, TreeModel, TreeQuery, TreeRevision, TreeRowContext, TreeSelectionUpdate,
 };
 
 struct Node {
@@ -87,27 +87,6 @@ impl FsModel {
         self.nodes[id].parent = None;
         Some(parent)
     }
-
-    fn is_descendant(&self, root: usize, target: usize) -> bool {
-        if root == target {
-            return true;
-        }
-        let mut stack = vec![root];
-        let mut visited = vec![false; self.nodes.len()];
-        while let Some(id) = stack.pop() {
-            if id >= self.nodes.len() || visited[id] {
-                continue;
-            }
-            visited[id] = true;
-            for &child in &self.nodes[id].children {
-                if child == target {
-                    return true;
-                }
-                stack.push(child);
-            }
-        }
-        false
-    }
 }
 
 impl TreeModel for FsModel {
@@ -165,8 +144,8 @@ impl TreeEditor for FsModel {
                     return Err("invalid destination parent");
                 }
                 for node in nodes.iter().copied() {
-                    if self.root == Some(node) || self.is_descendant(node, parent) {
-                        return Err("move would violate tree invariants");
+                    if self.root == Some(node) {
+                        return Err("cannot move root");
                     }
                 }
                 for node in nodes.iter().copied() {
@@ -469,20 +448,30 @@ fn edit_command(
     clipboard: &mut Option<usize>,
 ) -> Option<TreeEditCommand<usize>> {
     match request {
-        TreeEditRequest::ReorderUp { node, parent } => {
+        TreeEditRequest::ReorderUp {
+            node,
+            parent,
+            count,
+        } => {
             let siblings = &model.nodes[parent].children;
             let index = siblings.iter().position(|id| *id == node)?;
-            let previous = index.checked_sub(1).and_then(|index| siblings.get(index))?;
+            let target = index.saturating_sub(count.max(1));
+            let previous = siblings.get(target).filter(|previous| **previous != node)?;
             Some(TreeEditCommand::Move {
                 nodes: smallvec![node],
                 parent,
                 position: TreeInsertPosition::Before(*previous),
             })
         }
-        TreeEditRequest::ReorderDown { node, parent } => {
+        TreeEditRequest::ReorderDown {
+            node,
+            parent,
+            count,
+        } => {
             let siblings = &model.nodes[parent].children;
             let index = siblings.iter().position(|id| *id == node)?;
-            let next = siblings.get(index + 1)?;
+            let target = (index + count.max(1)).min(siblings.len() - 1);
+            let next = siblings.get(target).filter(|next| **next != node)?;
             Some(TreeEditCommand::Move {
                 nodes: smallvec![node],
                 parent,
@@ -534,18 +523,22 @@ fn run_app(
 
         if event::poll(Duration::from_millis(200))? {
             match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => break,
-                    _ => {
-                        let event = state.handle_key(&model, &query, columns, key);
-                        if let TreeEvent::Intent(TreeIntent::Edit(request)) = event
-                            && let Some(command) = edit_command(&model, request, &mut clipboard)
-                            && let Err(error) = state.apply_edit(&mut model, &query, command)
-                        {
-                            eprintln!("Edit failed: {error}");
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    match state.dispatch_key(&model, &query, columns, key) {
+                        TreeKeyDispatch::Unhandled(TreeKey {
+                            code: TreeKeyCode::Char('q') | TreeKeyCode::Esc,
+                            ..
+                        }) => break,
+                        TreeKeyDispatch::Handled(TreeEvent::Intent(TreeIntent::Edit(request))) => {
+                            if let Some(command) = edit_command(&model, request, &mut clipboard)
+                                && let Err(error) = state.apply_edit(&mut model, &query, command)
+                            {
+                                eprintln!("Edit failed: {error}");
+                            }
                         }
+                        _ => {}
                     }
-                },
+                }
                 _ => {}
             }
         }
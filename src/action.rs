@@ -1,9 +1,18 @@
+#[cfg(feature = "keys")]
+use crate::key::TreeKey;
+
 /// Actions that only change view state.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeViewAction {
     SelectPrev,
     SelectNext,
+    /// Moves the selection and extends the multi-selection from its anchor to the new row, see
+    /// [`TreeListViewState::select_range`](crate::TreeListViewState::select_range).
+    ExtendSelectionUp,
+    ExtendSelectionDown,
     SelectParent,
+    /// Selects the child last left through `SelectParent`, or else the first visible child,
+    /// without skipping leaves.
     SelectFirstChild,
     Expand,
     Collapse,
@@ -13,18 +22,44 @@ pub enum TreeViewAction {
     ToggleRecursive,
     ExpandAll,
     CollapseAll,
+    /// Collapses everything except the selection's ancestors and centers the selection.
+    CollapseToSelection,
     ToggleGuides,
     ToggleMark,
+    /// Opens a popup with the selected node's [`TreeDetailProvider`](crate::TreeDetailProvider)
+    /// lines; the next key dismisses it.
+    ShowDetails,
+    /// Pins the selected node as a comparison baseline, or unpins it when it already is one,
+    /// see [`TreeListViewState::anchor_distance`](crate::TreeListViewState::anchor_distance).
+    SetAnchor,
+    /// Turns review mode on or off, see
+    /// [`TreeListViewState::set_review`](crate::TreeListViewState::set_review).
+    ToggleReview,
     SelectFirst,
     SelectLast,
     SelectColumnLeft,
     SelectColumnRight,
     SelectFirstColumn,
     SelectLastColumn,
+    /// Moves the selection and the viewport up by the height of the last rendered viewport.
+    PageUp,
+    PageDown,
     ScrollViewUp,
     ScrollViewDown,
     ScrollLeft,
     ScrollRight,
+    /// Scrolls horizontally by one whole column.
+    ScrollColumnsLeft,
+    ScrollColumnsRight,
+    /// Widens the selected column by one cell, overriding its computed width.
+    GrowColumn,
+    ShrinkColumn,
+    /// Focuses the selected column's header, or returns to the rows when it is focused, so the
+    /// footer can show the column's [description](crate::ColumnDef::with_description).
+    ///
+    /// Column selection, resizing, and horizontal scrolling keep the header focused; other view
+    /// actions return to the rows.
+    SelectHeaderRow,
 }
 
 /// High-level editing actions for the selected node.
@@ -40,6 +75,134 @@ pub enum TreeEditAction {
     Paste,
 }
 
+impl TreeViewAction {
+    /// Every view action, in declaration order.
+    pub const ALL: [Self; 37] = [
+        Self::SelectPrev,
+        Self::SelectNext,
+        Self::ExtendSelectionUp,
+        Self::ExtendSelectionDown,
+        Self::SelectParent,
+        Self::SelectFirstChild,
+        Self::Expand,
+        Self::Collapse,
+        Self::ExpandOrSelectFirstChild,
+        Self::CollapseOrSelectParent,
+        Self::ToggleNode,
+        Self::ToggleRecursive,
+        Self::ExpandAll,
+        Self::CollapseAll,
+        Self::CollapseToSelection,
+        Self::ToggleGuides,
+        Self::ToggleMark,
+        Self::ShowDetails,
+        Self::SetAnchor,
+        Self::ToggleReview,
+        Self::SelectFirst,
+        Self::SelectLast,
+        Self::SelectColumnLeft,
+        Self::SelectColumnRight,
+        Self::SelectFirstColumn,
+        Self::SelectLastColumn,
+        Self::PageUp,
+        Self::PageDown,
+        Self::ScrollViewUp,
+        Self::ScrollViewDown,
+        Self::ScrollLeft,
+        Self::ScrollRight,
+        Self::ScrollColumnsLeft,
+        Self::ScrollColumnsRight,
+        Self::GrowColumn,
+        Self::ShrinkColumn,
+        Self::SelectHeaderRow,
+    ];
+
+    /// Returns the `snake_case` name used for the action in key binding configs.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::SelectPrev => "select_prev",
+            Self::SelectNext => "select_next",
+            Self::ExtendSelectionUp => "extend_selection_up",
+            Self::ExtendSelectionDown => "extend_selection_down",
+            Self::SelectParent => "select_parent",
+            Self::SelectFirstChild => "select_first_child",
+            Self::Expand => "expand",
+            Self::Collapse => "collapse",
+            Self::ExpandOrSelectFirstChild => "expand_or_select_first_child",
+            Self::CollapseOrSelectParent => "collapse_or_select_parent",
+            Self::ToggleNode => "toggle_node",
+            Self::ToggleRecursive => "toggle_recursive",
+            Self::ExpandAll => "expand_all",
+            Self::CollapseAll => "collapse_all",
+            Self::CollapseToSelection => "collapse_to_selection",
+            Self::ToggleGuides => "toggle_guides",
+            Self::ToggleMark => "toggle_mark",
+            Self::ShowDetails => "show_details",
+            Self::SetAnchor => "set_anchor",
+            Self::ToggleReview => "toggle_review",
+            Self::SelectFirst => "select_first",
+            Self::SelectLast => "select_last",
+            Self::SelectColumnLeft => "select_column_left",
+            Self::SelectColumnRight => "select_column_right",
+            Self::SelectFirstColumn => "select_first_column",
+            Self::SelectLastColumn => "select_last_column",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::ScrollViewUp => "scroll_view_up",
+            Self::ScrollViewDown => "scroll_view_down",
+            Self::ScrollLeft => "scroll_left",
+            Self::ScrollRight => "scroll_right",
+            Self::ScrollColumnsLeft => "scroll_columns_left",
+            Self::ScrollColumnsRight => "scroll_columns_right",
+            Self::GrowColumn => "grow_column",
+            Self::ShrinkColumn => "shrink_column",
+            Self::SelectHeaderRow => "select_header_row",
+        }
+    }
+
+    /// Looks an action up by its [name](Self::name).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+impl TreeEditAction {
+    /// Every edit action, in declaration order.
+    pub const ALL: [Self; 8] = [
+        Self::ReorderUp,
+        Self::ReorderDown,
+        Self::AddChild,
+        Self::Rename,
+        Self::Detach,
+        Self::Delete,
+        Self::Yank,
+        Self::Paste,
+    ];
+
+    /// Returns the `snake_case` name used for the action in key binding configs.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ReorderUp => "reorder_up",
+            Self::ReorderDown => "reorder_down",
+            Self::AddChild => "add_child",
+            Self::Rename => "rename",
+            Self::Detach => "detach",
+            Self::Delete => "delete",
+            Self::Yank => "yank",
+            Self::Paste => "paste",
+        }
+    }
+
+    /// Looks an action up by its [name](Self::name).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
 /// An action produced by the application or user.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeAction<Custom = ()> {
@@ -48,6 +211,16 @@ pub enum TreeAction<Custom = ()> {
     Custom(Custom),
 }
 
+impl<C> TreeAction<C> {
+    /// Looks a view or edit action up by its name, such as `select_next` or `delete`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        TreeViewAction::from_name(name)
+            .map(Self::View)
+            .or_else(|| TreeEditAction::from_name(name).map(Self::Edit))
+    }
+}
+
 impl<C> From<TreeViewAction> for TreeAction<C> {
     fn from(action: TreeViewAction) -> Self {
         Self::View(action)
@@ -61,10 +234,13 @@ impl<C> From<TreeEditAction> for TreeAction<C> {
 }
 
 /// A typed edit request enriched with the current selection.
+///
+/// Reorder requests carry the `count` typed in a prefix such as `3`, at least one, as the
+/// number of siblings to move `node` by.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeEditRequest<Id> {
-    ReorderUp { node: Id, parent: Id },
-    ReorderDown { node: Id, parent: Id },
+    ReorderUp { node: Id, parent: Id, count: usize },
+    ReorderDown { node: Id, parent: Id, count: usize },
     AddChild { parent: Id },
     Rename { node: Id },
     Detach { node: Id, parent: Id },
@@ -76,11 +252,20 @@ pub enum TreeEditRequest<Id> {
 /// An intent that must be handled by the application.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeIntent<Id, Custom = ()> {
+    /// An unloaded branch was expanded; it opens once the model reports its children loaded.
     LoadChildren(Id),
     Edit(TreeEditRequest<Id>),
     Custom(Custom),
 }
 
+/// The mouse gesture that produced a cell click.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeClickKind {
+    Single,
+    Double,
+    Right,
+}
+
 /// The result of handling an action.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeEvent<Id, Custom = ()> {
@@ -90,4 +275,28 @@ pub enum TreeEvent<Id, Custom = ()> {
     Unchanged,
     /// The application or model must perform an operation.
     Intent(TreeIntent<Id, Custom>),
+    /// A cell was clicked; its row is now selected.
+    CellClicked {
+        id: Id,
+        column: usize,
+        kind: TreeClickKind,
+    },
+    /// A column header was clicked, for example to cycle [`TreeSortKeys`](crate::TreeSortKeys).
+    HeaderClicked { column: usize, kind: TreeClickKind },
+    /// The header of `column` was focused with
+    /// [`TreeViewAction::SelectHeaderRow`], or the selected column changed while it was.
+    HeaderSelected { column: usize },
+    /// A rendered row referred to an id that the model no longer contains, found under
+    /// [`TreeModelCheck::Verify`](crate::TreeModelCheck::Verify).
+    ModelInconsistent(Id),
+}
+
+/// The result of [`dispatch_key`](crate::TreeListViewState::dispatch_key): the event of a key
+/// the view consumed, or the key itself for fallthrough to the application or another widget.
+#[cfg(feature = "keys")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeKeyDispatch<Id, Custom = ()> {
+    Handled(TreeEvent<Id, Custom>),
+    /// No binding matched the key.
+    Unhandled(TreeKey),
 }
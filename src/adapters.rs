@@ -1,10 +1,17 @@
+use std::cell::OnceCell;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 use std::hash::Hash;
 
+use std::collections::hash_map::Entry;
+use std::hash::BuildHasher;
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
 use smallvec::SmallVec;
 
-use crate::model::{TreeChildren, TreeModel, TreeRevision};
+use crate::model::{TreeChildren, TreeModel, TreeModelOwned, TreeRevision, TreeSort};
+use crate::state::TreeListViewState;
+use crate::traversal::TreeWalk;
 
 /// An error produced while parsing an indexed tree.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -91,6 +98,80 @@ where
     }
 }
 
+/// A model defined inline by a roots closure and a children closure, for examples, tests, and
+/// prototypes that do not warrant a struct and a [`TreeModel`] impl.
+///
+/// The closures run once in [`Self::new`], which walks every node reachable from the roots and
+/// keeps the returned child lists as the model's storage. Build a new model with a new revision
+/// after the source changes.
+pub struct FnTreeModel<Id> {
+    roots: SmallVec<[Id; 8]>,
+    children: FxHashMap<Id, SmallVec<[Id; 8]>>,
+    revision: TreeRevision,
+}
+
+impl<Id: Copy + Eq + Hash> FnTreeModel<Id> {
+    /// Collects the tree from `root_fn` and `children_fn`. A node reached twice keeps its first
+    /// child list, so cyclic sources terminate.
+    #[must_use]
+    pub fn new<R, C>(root_fn: impl FnOnce() -> R, mut children_fn: C) -> Self
+    where
+        R: IntoIterator<Item = Id>,
+        C: FnMut(Id) -> SmallVec<[Id; 8]>,
+    {
+        let roots: SmallVec<[Id; 8]> = root_fn().into_iter().collect();
+        let mut children = FxHashMap::default();
+        let mut pending: Vec<Id> = roots.iter().rev().copied().collect();
+        while let Some(id) = pending.pop() {
+            if children.contains_key(&id) {
+                continue;
+            }
+            let loaded = children_fn(id);
+            pending.extend(loaded.iter().rev().copied());
+            children.insert(id, loaded);
+        }
+        Self {
+            roots,
+            children,
+            revision: TreeRevision::INITIAL,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_revision(mut self, revision: TreeRevision) -> Self {
+        self.revision = revision;
+        self
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeModel for FnTreeModel<Id> {
+    type Id = Id;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        self.roots.iter().copied()
+    }
+
+    fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id> {
+        self.children
+            .get(&id)
+            .map_or(TreeChildren::Leaf, |children| {
+                TreeChildren::loaded(children)
+            })
+    }
+
+    fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+
+    fn size_hint(&self) -> usize {
+        self.children.len()
+    }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        self.children.contains_key(&id)
+    }
+}
+
 /// A validated zero-copy adapter over an indexed adjacency list.
 pub struct IndexedTree<'a, C = Vec<usize>>
 where
@@ -199,10 +280,497 @@ where
     fn size_hint(&self) -> usize {
         self.children.len()
     }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        id < self.children.len()
+    }
+}
+
+/// A node of a [`GroupedTreeModel`]: either a node of the wrapped model or a synthetic group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GroupedId<Id> {
+    Node(Id),
+    /// A group owned by the adapter; resolve its key with [`GroupedTreeModel::group_key`].
+    ///
+    /// Identified by its parent, `None` among the roots, and a hash of its key, so selection,
+    /// expansion, and marks stay on the group while other groups come and go.
+    Group {
+        parent: Option<Id>,
+        key: u64,
+    },
+}
+
+type GroupedChildren<Id> = Vec<GroupedId<Id>>;
+type Groups<Id, K> = FxHashMap<(Option<Id>, u64), (K, GroupedChildren<Id>)>;
+
+/// An adapter that gathers children sharing a key under synthetic group nodes, such as files by
+/// extension, without modifying the wrapped model.
+///
+/// Groups appear at the position of their first member and children without a key stay in place.
+/// The grouping is computed once by [`Self::new`], so rebuild the adapter whenever the wrapped
+/// model changes. The wrapped revision is passed through; use [`Self::with_revision`] when only
+/// the key changes.
+pub struct GroupedTreeModel<'a, T: TreeModel, K> {
+    model: &'a T,
+    roots: GroupedChildren<T::Id>,
+    children: FxHashMap<T::Id, GroupedChildren<T::Id>>,
+    groups: Groups<T::Id, K>,
+    revision: TreeRevision,
+}
+
+impl<'a, T: TreeModel, K: Hash + Eq> GroupedTreeModel<'a, T, K> {
+    /// Groups the loaded children of every reachable node, and the roots, by `key`.
+    ///
+    /// `key` receives the parent, or `None` for roots, and the child; `None` leaves the child
+    /// ungrouped.
+    pub fn new<G>(model: &'a T, mut key: G) -> Self
+    where
+        G: FnMut(&T, Option<T::Id>, T::Id) -> Option<K>,
+    {
+        let mut groups = FxHashMap::default();
+        let roots = group_children(model, None, model.roots(), &mut key, &mut groups);
+        let mut children = FxHashMap::default();
+        for node in TreeWalk::forest(model) {
+            let TreeChildren::Loaded(loaded) = node.children else {
+                continue;
+            };
+            if children.contains_key(&node.id) {
+                continue;
+            }
+            let grouped = group_children(
+                model,
+                Some(node.id),
+                loaded.iter().copied(),
+                &mut key,
+                &mut groups,
+            );
+            children.insert(node.id, grouped);
+        }
+        Self {
+            model,
+            roots,
+            children,
+            groups,
+            revision: model.revision(),
+        }
+    }
+}
+
+impl<T: TreeModel, K> GroupedTreeModel<'_, T, K> {
+    /// Overrides the revision reported to the view, for example after switching the key.
+    #[must_use]
+    pub const fn with_revision(mut self, revision: TreeRevision) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Returns the wrapped model.
+    #[must_use]
+    pub const fn inner(&self) -> &T {
+        self.model
+    }
+
+    /// Returns the key of a synthetic group, or `None` for real nodes.
+    #[must_use]
+    pub fn group_key(&self, id: GroupedId<T::Id>) -> Option<&K> {
+        match id {
+            GroupedId::Node(_) => None,
+            GroupedId::Group { parent, key } => self.groups.get(&(parent, key)).map(|(key, _)| key),
+        }
+    }
+}
+
+fn group_children<T, K, G>(
+    model: &T,
+    parent: Option<T::Id>,
+    children: impl Iterator<Item = T::Id>,
+    key: &mut G,
+    groups: &mut Groups<T::Id, K>,
+) -> GroupedChildren<T::Id>
+where
+    T: TreeModel,
+    K: Hash + Eq,
+    G: FnMut(&T, Option<T::Id>, T::Id) -> Option<K>,
+{
+    let mut grouped = Vec::new();
+    for child in children {
+        let Some(child_key) = key(model, parent, child) else {
+            grouped.push(GroupedId::Node(child));
+            continue;
+        };
+        // Probes past the rare hash collision between different keys of one parent.
+        let mut hash = FxBuildHasher.hash_one(&child_key);
+        while groups
+            .get(&(parent, hash))
+            .is_some_and(|(group_key, _)| *group_key != child_key)
+        {
+            hash = hash.wrapping_add(1);
+        }
+        match groups.entry((parent, hash)) {
+            Entry::Occupied(mut group) => group.get_mut().1.push(GroupedId::Node(child)),
+            Entry::Vacant(group) => {
+                grouped.push(GroupedId::Group { parent, key: hash });
+                group.insert((child_key, vec![GroupedId::Node(child)]));
+            }
+        }
+    }
+    grouped
+}
+
+impl<T: TreeModel, K> TreeModel for GroupedTreeModel<'_, T, K> {
+    type Id = GroupedId<T::Id>;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        self.roots.iter().copied()
+    }
+
+    fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id> {
+        match id {
+            GroupedId::Node(node) => match self.model.children(node) {
+                TreeChildren::Leaf => TreeChildren::Leaf,
+                TreeChildren::Unloaded => TreeChildren::Unloaded,
+                TreeChildren::Loading => TreeChildren::Loading,
+                TreeChildren::Loaded(_) => self
+                    .children
+                    .get(&node)
+                    .map_or(TreeChildren::Leaf, |children| {
+                        TreeChildren::loaded(children)
+                    }),
+            },
+            GroupedId::Group { parent, key } => self
+                .groups
+                .get(&(parent, key))
+                .map_or(TreeChildren::Leaf, |(_, members)| {
+                    TreeChildren::loaded(members)
+                }),
+        }
+    }
+
+    fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+
+    fn size_hint(&self) -> usize {
+        self.model.size_hint().saturating_add(self.groups.len())
+    }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        match id {
+            GroupedId::Node(node) => self.model.contains(node),
+            GroupedId::Group { parent, key } => self.groups.contains_key(&(parent, key)),
+        }
+    }
+
+    fn draws_guides(&self, id: Self::Id) -> bool {
+        match id {
+            GroupedId::Node(node) => self.model.draws_guides(node),
+            GroupedId::Group { .. } => true,
+        }
+    }
+
+    fn fill_fraction(&self, id: Self::Id) -> Option<f32> {
+        match id {
+            GroupedId::Node(node) => self.model.fill_fraction(node),
+            GroupedId::Group { .. } => None,
+        }
+    }
+
+    fn overlay_rows(&self, id: Self::Id) -> usize {
+        match id {
+            GroupedId::Node(node) => self.model.overlay_rows(node),
+            GroupedId::Group { .. } => 0,
+        }
+    }
+}
+
+/// Sorted child lists kept by the application between frames for [`SortedTreeModel`].
+///
+/// A revision change drops every sorted list, and each one is sorted again the first time its
+/// parent's children are read, so collapsed branches cost nothing until they are shown. Call
+/// [`Self::invalidate`] after changing the comparator.
+#[derive(Clone, Debug)]
+pub struct SortedChildrenCache<Id> {
+    revision: Option<TreeRevision>,
+    roots: Vec<Id>,
+    children: FxHashMap<Id, OnceCell<Vec<Id>>>,
+}
+
+impl<Id: Copy + Eq + Hash> SortedChildrenCache<Id> {
+    /// Creates an empty cache; the first [`SortedTreeModel`] built with it sorts the roots.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            revision: None,
+            roots: Vec::new(),
+            children: FxHashMap::default(),
+        }
+    }
+
+    /// Forces the next [`SortedTreeModel`] to sort again.
+    pub const fn invalidate(&mut self) {
+        self.revision = None;
+    }
+
+    fn refresh<T, S>(&mut self, model: &T, sort: &S)
+    where
+        T: TreeModel<Id = Id>,
+        S: TreeSort<T>,
+    {
+        let revision = model.revision();
+        if self.revision == Some(revision) {
+            return;
+        }
+        self.roots.clear();
+        self.roots.extend(model.roots());
+        self.roots
+            .sort_by(|left, right| sort.compare(model, *left, *right));
+        self.children.clear();
+        for node in TreeWalk::forest(model) {
+            if let TreeChildren::Loaded(loaded) = node.children
+                && loaded.len() > 1
+            {
+                self.children.entry(node.id).or_default();
+            }
+        }
+        self.revision = Some(revision);
+    }
+}
+
+impl<Id: Copy + Eq + Hash> Default for SortedChildrenCache<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An adapter that presents the wrapped model's roots and children in comparator order, without
+/// touching the application's data or the query's sort.
+///
+/// [`Self::new`] sorts the roots when the cache is stale; child lists are sorted on first read.
+/// Disabled sorts pass the wrapped order through.
+pub struct SortedTreeModel<'a, T: TreeModel> {
+    model: &'a T,
+    sort: &'a dyn TreeSort<T>,
+    cache: Option<&'a SortedChildrenCache<T::Id>>,
+}
+
+impl<'a, T: TreeModel> SortedTreeModel<'a, T> {
+    pub fn new<S: TreeSort<T>>(
+        model: &'a T,
+        sort: &'a S,
+        cache: &'a mut SortedChildrenCache<T::Id>,
+    ) -> Self {
+        if !sort.is_enabled() {
+            return Self {
+                model,
+                sort,
+                cache: None,
+            };
+        }
+        cache.refresh(model, sort);
+        Self {
+            model,
+            sort,
+            cache: Some(cache),
+        }
+    }
+
+    /// Returns the wrapped model.
+    #[must_use]
+    pub const fn inner(&self) -> &T {
+        self.model
+    }
+}
+
+impl<T: TreeModel> TreeModel for SortedTreeModel<'_, T> {
+    type Id = T::Id;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        let sorted = self.cache.map(|cache| cache.roots.iter().copied());
+        let wrapped = self.cache.is_none().then(|| self.model.roots());
+        sorted
+            .into_iter()
+            .flatten()
+            .chain(wrapped.into_iter().flatten())
+    }
+
+    fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id> {
+        let children = self.model.children(id);
+        let (TreeChildren::Loaded(loaded), Some(cache)) = (children, self.cache) else {
+            return children;
+        };
+        let Some(sorted) = cache.children.get(&id) else {
+            return children;
+        };
+        TreeChildren::loaded(sorted.get_or_init(|| {
+            let mut sorted = loaded.to_vec();
+            sorted.sort_by(|left, right| self.sort.compare(self.model, *left, *right));
+            sorted
+        }))
+    }
+
+    fn revision(&self) -> TreeRevision {
+        self.model.revision()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.model.size_hint()
+    }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        self.model.contains(id)
+    }
+
+    fn draws_guides(&self, id: Self::Id) -> bool {
+        self.model.draws_guides(id)
+    }
+
+    fn fill_fraction(&self, id: Self::Id) -> Option<f32> {
+        self.model.fill_fraction(id)
+    }
+
+    fn overlay_rows(&self, id: Self::Id) -> usize {
+        self.model.overlay_rows(id)
+    }
+}
+
+/// Child lists fetched from a [`TreeModelOwned`], kept by the application between frames for
+/// [`OwnedTreeModel`].
+///
+/// The lists are dropped when the model's revision changes. Call [`Self::invalidate`] to fetch
+/// them again without a revision change.
+#[derive(Clone, Debug)]
+pub struct OwnedChildrenCache<Id> {
+    revision: Option<TreeRevision>,
+    children: FxHashMap<Id, SmallVec<[Id; 8]>>,
+}
+
+impl<Id: Copy + Eq + Hash> OwnedChildrenCache<Id> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            revision: None,
+            children: FxHashMap::default(),
+        }
+    }
+
+    /// Forces the next [`OwnedTreeModel`] to fetch every child list again.
+    pub const fn invalidate(&mut self) {
+        self.revision = None;
+    }
+
+    fn refresh<T>(&mut self, model: &T, state: &TreeListViewState<Id>)
+    where
+        T: TreeModelOwned<Id = Id>,
+    {
+        let revision = model.revision();
+        if self.revision != Some(revision) {
+            self.children.clear();
+            self.revision = Some(revision);
+        }
+        let mut pending: Vec<Id> = model.roots().collect();
+        for root in &pending {
+            self.fetch(model, *root);
+        }
+        while let Some(parent) = pending.pop() {
+            let Some(children) = self.children.get(&parent).cloned() else {
+                continue;
+            };
+            for child in children {
+                self.fetch(model, child);
+                if state.node_is_expanded(child, Some(parent)) {
+                    pending.push(child);
+                }
+            }
+        }
+    }
+
+    fn fetch<T: TreeModelOwned<Id = Id>>(&mut self, model: &T, id: Id) {
+        self.children
+            .entry(id)
+            .or_insert_with(|| model.children(id));
+    }
+}
+
+impl<Id: Copy + Eq + Hash> Default for OwnedChildrenCache<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An adapter that presents a [`TreeModelOwned`] as a [`TreeModel`].
+///
+/// [`Self::new`] fetches the children of the roots, of the roots' children, which hidden roots
+/// display directly, and of every node under an expanded parent, so each visible row knows
+/// whether it is a branch. Deeper nodes report [`TreeChildren::Unloaded`] until their parent is
+/// expanded and the adapter is created again, so filters and expand-all only reach nodes fetched
+/// so far.
+pub struct OwnedTreeModel<'a, T: TreeModelOwned> {
+    model: &'a T,
+    cache: &'a OwnedChildrenCache<T::Id>,
+}
+
+impl<'a, T: TreeModelOwned> OwnedTreeModel<'a, T> {
+    pub fn new(
+        model: &'a T,
+        cache: &'a mut OwnedChildrenCache<T::Id>,
+        state: &TreeListViewState<T::Id>,
+    ) -> Self {
+        cache.refresh(model, state);
+        Self { model, cache }
+    }
+
+    /// Returns the wrapped model.
+    #[must_use]
+    pub const fn inner(&self) -> &T {
+        self.model
+    }
+}
+
+impl<T: TreeModelOwned> TreeModel for OwnedTreeModel<'_, T> {
+    type Id = T::Id;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        self.model.roots()
+    }
+
+    fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id> {
+        self.cache
+            .children
+            .get(&id)
+            .map_or(TreeChildren::Unloaded, |children| {
+                TreeChildren::loaded(children)
+            })
+    }
+
+    fn revision(&self) -> TreeRevision {
+        self.model.revision()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.model.size_hint()
+    }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        self.model.contains(id)
+    }
+
+    fn draws_guides(&self, id: Self::Id) -> bool {
+        self.model.draws_guides(id)
+    }
+
+    fn fill_fraction(&self, id: Self::Id) -> Option<f32> {
+        self.model.fill_fraction(id)
+    }
+
+    fn overlay_rows(&self, id: Self::Id) -> usize {
+        self.model.overlay_rows(id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use super::*;
 
     #[test]
@@ -224,4 +792,108 @@ mod tests {
             .expect("slice-backed adjacency list is valid");
         assert_eq!(tree.children(0).loaded_slice(), &[1]);
     }
+
+    #[test]
+    fn grouped_model_gathers_keyed_children_under_synthetic_nodes() {
+        let children = vec![vec![1, 2, 3, 4], vec![], vec![], vec![], vec![]];
+        let tree =
+            IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("indexed tree is valid");
+        let grouped = GroupedTreeModel::new(&tree, |_, parent, child| {
+            (parent.is_some() && child != 2).then_some(child % 2)
+        });
+
+        assert_eq!(grouped.roots().collect::<Vec<_>>(), [GroupedId::Node(0)]);
+        let top = grouped.children(GroupedId::Node(0)).loaded_slice();
+        assert!(matches!(
+            top[0],
+            GroupedId::Group {
+                parent: Some(0),
+                ..
+            }
+        ));
+        assert_eq!(top[1], GroupedId::Node(2));
+        assert!(matches!(
+            top[2],
+            GroupedId::Group {
+                parent: Some(0),
+                ..
+            }
+        ));
+        assert_eq!(grouped.group_key(top[0]), Some(&1));
+        assert_eq!(grouped.group_key(top[1]), None);
+        assert_eq!(
+            grouped.children(top[0]).loaded_slice(),
+            [GroupedId::Node(1), GroupedId::Node(3)]
+        );
+        assert_eq!(
+            grouped.children(top[2]).loaded_slice(),
+            [GroupedId::Node(4)]
+        );
+        assert!(!grouped.children(GroupedId::Node(1)).is_branch());
+    }
+
+    #[test]
+    fn grouped_ids_survive_rebuilds_that_add_groups() {
+        let children = vec![vec![1, 2, 3], vec![], vec![], vec![]];
+        let tree =
+            IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("indexed tree is valid");
+        let before = GroupedTreeModel::new(&tree, |_, parent, child| {
+            (parent.is_some() && child != 1).then(|| format!("key {}", child % 2))
+        });
+        let after = GroupedTreeModel::new(&tree, |_, parent, child| {
+            parent.is_some().then(|| format!("key {}", child % 2))
+        });
+
+        let group = before.children(GroupedId::Node(0)).loaded_slice()[1];
+        assert_eq!(before.group_key(group).map(String::as_str), Some("key 0"));
+        let top = after.children(GroupedId::Node(0)).loaded_slice();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[1], group);
+        assert_eq!(after.group_key(group).map(String::as_str), Some("key 0"));
+        assert!(!after.contains(GroupedId::Group {
+            parent: None,
+            key: 0
+        }));
+    }
+
+    #[test]
+    fn sorted_model_reorders_children_and_reuses_its_cache() {
+        let children = vec![vec![3, 1, 2], vec![], vec![], vec![]];
+        let descending = |_: &IndexedTree<'_>, left: usize, right: usize| right.cmp(&left);
+        let mut cache = SortedChildrenCache::new();
+        let tree =
+            IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("indexed tree is valid");
+        let sorted = SortedTreeModel::new(&tree, &descending, &mut cache);
+        assert_eq!(sorted.children(0).loaded_slice(), &[3, 2, 1]);
+        assert_eq!(sorted.roots().collect::<Vec<_>>(), [0]);
+
+        let ascending = |_: &IndexedTree<'_>, left: usize, right: usize| left.cmp(&right);
+        let stale = SortedTreeModel::new(&tree, &ascending, &mut cache);
+        assert_eq!(stale.children(0).loaded_slice(), &[3, 2, 1]);
+        cache.invalidate();
+        let sorted = SortedTreeModel::new(&tree, &ascending, &mut cache);
+        assert_eq!(sorted.children(0).loaded_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn sorted_model_sorts_child_lists_on_first_read() {
+        let children = vec![vec![2, 1], vec![4, 3], vec![], vec![], vec![]];
+        let calls = Cell::new(0);
+        let ascending = |_: &IndexedTree<'_>, left: usize, right: usize| {
+            calls.set(calls.get() + 1);
+            left.cmp(&right)
+        };
+        let mut cache = SortedChildrenCache::new();
+        let tree =
+            IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("indexed tree is valid");
+        let sorted = SortedTreeModel::new(&tree, &ascending, &mut cache);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(sorted.children(0).loaded_slice(), &[1, 2]);
+        let after_first = calls.get();
+        assert!(after_first > 0);
+        assert_eq!(sorted.children(0).loaded_slice(), &[1, 2]);
+        assert_eq!(calls.get(), after_first);
+        assert_eq!(sorted.children(1).loaded_slice(), &[3, 4]);
+        assert!(calls.get() > after_first);
+    }
 }
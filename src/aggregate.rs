@@ -0,0 +1,78 @@
+use std::hash::Hash;
+use std::ops::Add;
+
+use rustc_hash::FxHashMap;
+
+use crate::model::{TreeModel, TreeRevision};
+use crate::traversal::TreePostorder;
+
+/// Per-node aggregates over loaded subtrees, such as "files inside" or "total bytes" columns.
+///
+/// Values are computed in one postorder pass in [`Self::refresh`] and kept until the model's
+/// revision changes, so columns read them each frame without walking subtrees again. Call
+/// [`Self::invalidate`] after changing the value closure.
+#[derive(Clone, Debug)]
+pub struct SubtreeAgg<Id, V> {
+    revision: Option<TreeRevision>,
+    nodes: FxHashMap<Id, (usize, V)>,
+}
+
+impl<Id: Copy + Eq + Hash, V: Copy + Add<Output = V>> SubtreeAgg<Id, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            revision: None,
+            nodes: FxHashMap::default(),
+        }
+    }
+
+    /// Forces the next [`Self::refresh`] to compute every aggregate again.
+    pub const fn invalidate(&mut self) {
+        self.revision = None;
+    }
+
+    /// Recomputes the aggregates when the model's revision changed since the last call, summing
+    /// `value` over each node and its loaded descendants. Returns `true` when it recomputed.
+    pub fn refresh<T>(&mut self, model: &T, mut value: impl FnMut(&T, Id) -> V) -> bool
+    where
+        T: TreeModel<Id = Id>,
+    {
+        let revision = model.revision();
+        if self.revision == Some(revision) {
+            return false;
+        }
+        self.nodes.clear();
+        self.nodes.reserve(model.size_hint());
+        for node in TreePostorder::forest(model) {
+            let mut descendants = 0_usize;
+            let mut total = value(model, node.id);
+            for child in node.children {
+                if let Some((child_descendants, child_total)) = self.nodes.get(child) {
+                    descendants = descendants.saturating_add(child_descendants + 1);
+                    total = total + *child_total;
+                }
+            }
+            self.nodes.insert(node.id, (descendants, total));
+        }
+        self.revision = Some(revision);
+        true
+    }
+
+    /// Returns the number of loaded descendants, or `None` for a node outside the last refresh.
+    #[must_use]
+    pub fn descendants(&self, id: Id) -> Option<usize> {
+        self.nodes.get(&id).map(|(descendants, _)| *descendants)
+    }
+
+    /// Returns the sum of the node's value and its loaded descendants' values.
+    #[must_use]
+    pub fn total(&self, id: Id) -> Option<V> {
+        self.nodes.get(&id).map(|(_, total)| *total)
+    }
+}
+
+impl<Id: Copy + Eq + Hash, V: Copy + Add<Output = V>> Default for SubtreeAgg<Id, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,29 @@
+use ratatui::buffer::Buffer;
+use ratatui::text::Span;
+
+/// Returns the symbols of a rendered buffer as text, one line per row, without styles.
+///
+/// Cells covered by a wide character are skipped and trailing spaces are trimmed, so the text
+/// matches what a terminal shows and stays stable in golden files.
+#[must_use]
+pub fn tree_buffer_text(buffer: &Buffer) -> String {
+    let width = usize::from(buffer.area.width);
+    let mut text = String::with_capacity(buffer.content.len() + usize::from(buffer.area.height));
+    for (index, row) in buffer.content.chunks(width.max(1)).enumerate() {
+        if index > 0 {
+            text.push('\n');
+        }
+        let start = text.len();
+        let mut covered = 0;
+        for cell in row {
+            if covered == 0 {
+                text.push_str(cell.symbol());
+                covered = Span::raw(cell.symbol()).width().max(1);
+            }
+            covered -= 1;
+        }
+        let end = start + text[start..].trim_end().len();
+        text.truncate(end);
+    }
+    text
+}
@@ -0,0 +1,322 @@
+use std::collections::hash_set;
+use std::hash::Hash;
+use std::slice;
+
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
+use smallvec::SmallVec;
+
+/// Collections up to this size use linear scans instead of hashing.
+///
+/// Trees used as simple menus rarely exceed it, and for them a scan over a few contiguous
+/// elements is cheaper than hashing every lookup. The `small/lookup` benchmark measures the
+/// crossover: scans win below eight elements and fall well behind hashing past it.
+pub const SMALL_LIMIT: usize = 8;
+
+/// A set backed by a linear-scan vector that switches to a hash set once it grows large.
+#[derive(Clone, Debug)]
+pub enum HybridSet<T> {
+    Small(SmallVec<[T; SMALL_LIMIT]>),
+    Large(FxHashSet<T>),
+}
+
+impl<T: Eq + Hash> HybridSet<T> {
+    /// Chooses the representation from the expected number of elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > SMALL_LIMIT {
+            Self::Large(FxHashSet::with_capacity_and_hasher(capacity, FxBuildHasher))
+        } else {
+            Self::Small(SmallVec::new())
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Small(values) => values.len(),
+            Self::Large(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::Small(values) => values.contains(value),
+            Self::Large(values) => values.contains(value),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> bool {
+        match self {
+            Self::Small(values) => {
+                if values.contains(&value) {
+                    return false;
+                }
+                if values.len() < SMALL_LIMIT {
+                    values.push(value);
+                    return true;
+                }
+                self.promote(SMALL_LIMIT.saturating_add(1));
+                self.insert(value)
+            }
+            Self::Large(values) => values.insert(value),
+        }
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        match self {
+            Self::Small(values) => values
+                .iter()
+                .position(|candidate| candidate == value)
+                .map(|index| values.swap_remove(index))
+                .is_some(),
+            Self::Large(values) => values.remove(value),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match self {
+            Self::Small(values) => values.clear(),
+            Self::Large(values) => values.clear(),
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        match self {
+            Self::Small(values) => values.retain(|value| keep(value)),
+            Self::Large(values) => values.retain(keep),
+        }
+    }
+
+    /// Switches to hashing when the expected size exceeds [`SMALL_LIMIT`].
+    pub fn reserve(&mut self, capacity: usize) {
+        match self {
+            Self::Small(_) if capacity > SMALL_LIMIT => self.promote(capacity),
+            Self::Small(_) => {}
+            Self::Large(values) => values.reserve(capacity.saturating_sub(values.len())),
+        }
+    }
+
+    pub fn iter(&self) -> HybridSetIter<'_, T> {
+        match self {
+            Self::Small(values) => HybridSetIter::Small(values.iter()),
+            Self::Large(values) => HybridSetIter::Large(values.iter()),
+        }
+    }
+
+    fn promote(&mut self, capacity: usize) {
+        if let Self::Small(values) = self {
+            let mut large = FxHashSet::with_capacity_and_hasher(capacity, FxBuildHasher);
+            large.extend(values.drain(..));
+            *self = Self::Large(large);
+        }
+    }
+}
+
+impl<T: Eq + Hash> Default for HybridSet<T> {
+    fn default() -> Self {
+        Self::Small(SmallVec::new())
+    }
+}
+
+impl<T: Eq + Hash> PartialEq for HybridSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|value| other.contains(value))
+    }
+}
+
+impl<T: Eq + Hash> Eq for HybridSet<T> {}
+
+impl<T: Eq + Hash> FromIterator<T> for HybridSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut set = Self::with_capacity(iter.size_hint().0);
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for HybridSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T: Eq + Hash> IntoIterator for &'a HybridSet<T> {
+    type Item = &'a T;
+    type IntoIter = HybridSetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub enum HybridSetIter<'a, T> {
+    Small(slice::Iter<'a, T>),
+    Large(hash_set::Iter<'a, T>),
+}
+
+impl<'a, T> Iterator for HybridSetIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Small(iter) => iter.next(),
+            Self::Large(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Small(iter) => iter.size_hint(),
+            Self::Large(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// A map backed by a linear-scan vector that switches to a hash map once it grows large.
+#[derive(Clone, Debug)]
+pub enum HybridMap<K, V> {
+    Small(SmallVec<[(K, V); SMALL_LIMIT]>),
+    Large(FxHashMap<K, V>),
+}
+
+impl<K: Eq + Hash, V> HybridMap<K, V> {
+    /// Chooses the representation from the expected number of entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > SMALL_LIMIT {
+            Self::Large(FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher))
+        } else {
+            Self::Small(SmallVec::new())
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match self {
+            Self::Small(entries) => entries
+                .iter()
+                .find_map(|(candidate, value)| (candidate == key).then_some(value)),
+            Self::Large(entries) => entries.get(key),
+        }
+    }
+
+    /// Inserts a value and returns the previous one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self {
+            Self::Small(entries) => {
+                if let Some((_, current)) =
+                    entries.iter_mut().find(|(candidate, _)| *candidate == key)
+                {
+                    return Some(std::mem::replace(current, value));
+                }
+                if entries.len() < SMALL_LIMIT {
+                    entries.push((key, value));
+                    return None;
+                }
+                self.promote(SMALL_LIMIT.saturating_add(1));
+                self.insert(key, value)
+            }
+            Self::Large(entries) => entries.insert(key, value),
+        }
+    }
+
+    /// Inserts a value only when the key is absent.
+    pub fn insert_if_absent(&mut self, key: K, value: V) {
+        match self {
+            Self::Small(entries) => {
+                if !entries.iter().any(|(candidate, _)| *candidate == key) {
+                    self.insert(key, value);
+                }
+            }
+            Self::Large(entries) => {
+                entries.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match self {
+            Self::Small(entries) => entries.clear(),
+            Self::Large(entries) => entries.clear(),
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        match self {
+            Self::Small(entries) => entries.retain(|(key, value)| keep(key, value)),
+            Self::Large(entries) => entries.retain(|key, value| keep(key, value)),
+        }
+    }
+
+    /// Switches to hashing when the expected size exceeds [`SMALL_LIMIT`].
+    pub fn reserve(&mut self, capacity: usize) {
+        match self {
+            Self::Small(_) if capacity > SMALL_LIMIT => self.promote(capacity),
+            Self::Small(_) => {}
+            Self::Large(entries) => entries.reserve(capacity.saturating_sub(entries.len())),
+        }
+    }
+
+    fn promote(&mut self, capacity: usize) {
+        if let Self::Small(entries) = self {
+            let mut large = FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher);
+            large.extend(entries.drain(..));
+            *self = Self::Large(large);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> Default for HybridMap<K, V> {
+    fn default() -> Self {
+        Self::Small(SmallVec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hybrid_set_promotes_past_the_small_limit() {
+        let mut set = HybridSet::with_capacity(0);
+        for value in 0..SMALL_LIMIT {
+            assert!(set.insert(value));
+        }
+        assert!(matches!(set, HybridSet::Small(_)));
+        assert!(!set.insert(0));
+        assert!(set.insert(SMALL_LIMIT));
+        assert!(matches!(set, HybridSet::Large(_)));
+        assert_eq!(set.len(), SMALL_LIMIT + 1);
+        assert!(set.remove(&3));
+        assert!(!set.contains(&3));
+
+        let small: HybridSet<_> = [1, 2, 3].into_iter().collect();
+        let large: HybridSet<_> = {
+            let mut large = HybridSet::with_capacity(SMALL_LIMIT + 1);
+            large.extend([3, 2, 1]);
+            large
+        };
+        assert_eq!(small, large);
+    }
+
+    #[test]
+    fn hybrid_map_keeps_the_first_value_when_inserting_if_absent() {
+        let mut map = HybridMap::with_capacity(0);
+        map.insert_if_absent(7, 'a');
+        map.insert_if_absent(7, 'b');
+        assert_eq!(map.get(&7), Some(&'a'));
+        assert_eq!(map.insert(7, 'c'), Some('a'));
+
+        map.reserve(SMALL_LIMIT + 1);
+        assert!(matches!(map, HybridMap::Large(_)));
+        assert_eq!(map.get(&7), Some(&'c'));
+        map.clear();
+        assert_eq!(map.get(&7), None);
+    }
+}
@@ -1,13 +1,18 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
 
-use ratatui::style::Style;
-use ratatui::text::Line;
-use ratatui::widgets::{Cell, Row};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Style, Styled};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Cell, Row, Table, Widget};
 use smallvec::SmallVec;
 
-use crate::context::TreeRowContext;
-use crate::model::TreeModel;
+use crate::context::{TreeExpansionState, TreeRowContext};
+use crate::model::{TreeFilter, TreeModel, TreeRevision, TreeSort};
 
 /// An error produced while constructing a valid column width.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -123,11 +128,49 @@ where
     }
 }
 
+type CellText<'a, T> = Box<dyn for<'m> Fn(&'m T, <T as TreeModel>::Id) -> Cow<'m, str> + 'a>;
+/// How [`TreeColumnSet`] makes room when [never-truncated](ColumnDef::never_truncate) columns
+/// do not fit at their minimum widths.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeColumnSqueeze {
+    /// Hides never-truncated columns, rightmost first, until the rest fit.
+    #[default]
+    DropColumn,
+    /// Narrows the tree column down to one cell first, then hides never-truncated columns.
+    StealFromLabel,
+}
+
+type CellPredicate<'a, T> = Box<dyn Fn(&T, <T as TreeModel>::Id) -> bool + 'a>;
+type CellStyleRule<'a, T> = Box<dyn Fn(&T, <T as TreeModel>::Id) -> Option<Style> + 'a>;
+type CellOrder<'a, T> =
+    Box<dyn Fn(&T, <T as TreeModel>::Id, <T as TreeModel>::Id) -> Ordering + 'a>;
+
+/// How a data column fits text wider than the column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeCellOverflow {
+    /// Text is cut at the column edge.
+    #[default]
+    Clip,
+    /// Text is shortened to end in `…`.
+    Ellipsis,
+    /// Text breaks at the last word boundary that fits instead of mid-word. Rows are one line
+    /// tall, so the wrapped lines after the first are not shown.
+    Wrap,
+}
+
 /// A column definition. Exactly one column in a set must have the tree role.
 pub struct ColumnDef<'a, T: TreeModel> {
     header: Line<'a>,
     width: ColumnWidth,
     kind: TreeColumnKind<'a, T>,
+    text: Option<CellText<'a, T>>,
+    aggregate: Option<Box<dyn TreeCellRenderer<T> + 'a>>,
+    absorbs_label: Option<CellPredicate<'a, T>>,
+    sort_key: Option<CellOrder<'a, T>>,
+    style_rule: Option<CellStyleRule<'a, T>>,
+    overflow: TreeCellOverflow,
+    never_truncate: bool,
+    description: Option<Cow<'a, str>>,
 }
 
 impl<'a, T: TreeModel> ColumnDef<'a, T> {
@@ -138,6 +181,14 @@ impl<'a, T: TreeModel> ColumnDef<'a, T> {
             header: header.into(),
             width,
             kind: TreeColumnKind::Tree,
+            text: None,
+            aggregate: None,
+            absorbs_label: None,
+            sort_key: None,
+            style_rule: None,
+            overflow: TreeCellOverflow::Clip,
+            never_truncate: false,
+            description: None,
         }
     }
 
@@ -154,6 +205,14 @@ impl<'a, T: TreeModel> ColumnDef<'a, T> {
             header: header.into(),
             width,
             kind: TreeColumnKind::Data(Box::new(renderer)),
+            text: None,
+            aggregate: None,
+            absorbs_label: None,
+            sort_key: None,
+            style_rule: None,
+            overflow: TreeCellOverflow::Clip,
+            never_truncate: false,
+            description: None,
         }
     }
 
@@ -168,6 +227,125 @@ impl<'a, T: TreeModel> ColumnDef<'a, T> {
     {
         Self::data(header, width, OwnedCellRenderer(renderer))
     }
+
+    /// Provides the column's plain-text value, used by [`ColumnFilter`].
+    #[must_use]
+    pub fn with_text<X>(mut self, text: X) -> Self
+    where
+        X: for<'m> Fn(&'m T, T::Id) -> Cow<'m, str> + 'a,
+    {
+        self.text = Some(Box::new(text));
+        self
+    }
+
+    /// Orders nodes by this column with `compare` in a [`ColumnSort`] instead of by
+    /// [text](Self::with_text), for example to sort sizes numerically.
+    #[must_use]
+    pub fn with_sort_key<O>(mut self, compare: O) -> Self
+    where
+        O: Fn(&T, T::Id, T::Id) -> Ordering + 'a,
+    {
+        self.sort_key = Some(Box::new(compare));
+        self
+    }
+
+    /// Patches the style returned by `rule` over the column's cells, so cells can be colored by
+    /// value, such as red above 90% usage, without styling spans in every renderer.
+    ///
+    /// `None` keeps the renderer's style. Applies to aggregate cells too; ignored for the tree
+    /// column.
+    #[must_use]
+    pub fn with_style_rule<X>(mut self, rule: X) -> Self
+    where
+        X: Fn(&T, T::Id) -> Option<Style> + 'a,
+    {
+        self.style_rule = Some(Box::new(rule));
+        self
+    }
+
+    /// Renders collapsed branches with `renderer` instead of the column's own renderer.
+    ///
+    /// Lets a column summarize a hidden subtree, such as the total size of a collapsed directory.
+    /// Ignored for the tree column.
+    #[must_use]
+    pub fn with_aggregate<R>(mut self, renderer: R) -> Self
+    where
+        R: TreeCellRenderer<T> + 'a,
+    {
+        self.aggregate = Some(Box::new(renderer));
+        self
+    }
+
+    /// Lets the tree label spill into this column for nodes where `is_empty` holds, such as
+    /// directories without a size, instead of truncating it.
+    ///
+    /// Only columns directly right of the tree column, or of another absorbing column, take part.
+    #[must_use]
+    pub fn absorb_label_overflow<E>(mut self, is_empty: E) -> Self
+    where
+        E: Fn(&T, T::Id) -> bool + 'a,
+    {
+        self.absorbs_label = Some(Box::new(is_empty));
+        self
+    }
+
+    /// Sets how text wider than the column is fitted.
+    ///
+    /// The decision is made from the cell as its renderer drew it, and the fitted cell keeps the
+    /// drawn styles. Cells whose content is not left-aligned, and the tree column, are clipped.
+    #[must_use]
+    pub const fn with_overflow(mut self, overflow: TreeCellOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Keeps the column at least at its minimum width, so numbers never lose digits.
+    ///
+    /// When the columns do not fit, the column is hidden rather than narrowed, as chosen by
+    /// [`TreeColumnSet::squeeze`]. Horizontal scrolling lays columns out at their ideal widths,
+    /// so it never needs to.
+    #[must_use]
+    pub const fn never_truncate(mut self) -> Self {
+        self.never_truncate = true;
+        self
+    }
+
+    /// Explains the column in the footer while its header is focused with
+    /// [`TreeViewAction::SelectHeaderRow`], so short headers such as `Sz` need no separate docs.
+    ///
+    /// [`TreeViewAction::SelectHeaderRow`]: crate::TreeViewAction::SelectHeaderRow
+    #[must_use]
+    pub fn with_description(mut self, description: impl Into<Cow<'a, str>>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    fn apply_style_rule<'m>(&self, cell: Cell<'m>, model: &T, id: T::Id) -> Cell<'m> {
+        match self.style_rule.as_ref().and_then(|rule| rule(model, id)) {
+            Some(style) => {
+                let patched = Styled::style(&cell).patch(style);
+                cell.style(patched)
+            }
+            None => cell,
+        }
+    }
+
+    /// Fits a rendered cell into `width` columns as chosen by [`Self::with_overflow`].
+    fn fit<'m>(&self, cell: Cell<'m>, width: Option<u16>) -> Cell<'m> {
+        match (self.overflow, width) {
+            (TreeCellOverflow::Clip, _) | (_, None | Some(0)) => cell,
+            (overflow, Some(width)) => fit_drawn(cell, width, overflow),
+        }
+    }
+
+    /// Like [`Self::with_aggregate`] for a capturing closure that returns an owned cell.
+    #[must_use]
+    pub fn with_aggregate_owned<R>(self, renderer: R) -> Self
+    where
+        R: Fn(&T, T::Id, &TreeRowContext<'_>) -> Cell<'static> + 'a,
+    {
+        self.with_aggregate(OwnedCellRenderer(renderer))
+    }
 }
 
 /// A dynamic column set parsed and validated once at construction.
@@ -176,6 +354,7 @@ pub struct TreeColumnSet<'a, T: TreeModel> {
     tree_column: usize,
     header_style: Style,
     show_header: bool,
+    squeeze: TreeColumnSqueeze,
 }
 
 impl<'a, T: TreeModel> TreeColumnSet<'a, T> {
@@ -208,6 +387,7 @@ impl<'a, T: TreeModel> TreeColumnSet<'a, T> {
             tree_column,
             header_style: Style::default(),
             show_header: true,
+            squeeze: TreeColumnSqueeze::DropColumn,
         })
     }
 
@@ -225,6 +405,51 @@ impl<'a, T: TreeModel> TreeColumnSet<'a, T> {
         self
     }
 
+    /// Sets how never-truncated columns make room when the columns do not fit.
+    #[must_use]
+    pub const fn squeeze(mut self, squeeze: TreeColumnSqueeze) -> Self {
+        self.squeeze = squeeze;
+        self
+    }
+
+    /// Narrows or hides columns until `widths` fit in `available`, if any column must never be
+    /// truncated.
+    fn keep_minimums(&self, widths: &mut [u16], available: u16) {
+        let total = widths.iter().copied().fold(0_u16, u16::saturating_add);
+        let mut overflow = total.saturating_sub(available);
+        if overflow == 0 || !self.columns.iter().any(|column| column.never_truncate) {
+            return;
+        }
+        if self.squeeze == TreeColumnSqueeze::StealFromLabel {
+            let label = &mut widths[self.tree_column];
+            let stolen = overflow.min(label.saturating_sub(1));
+            *label -= stolen;
+            overflow -= stolen;
+        }
+        for (width, column) in widths.iter_mut().zip(&self.columns).rev() {
+            if overflow == 0 {
+                break;
+            }
+            if column.never_truncate {
+                overflow = overflow.saturating_sub(*width);
+                *width = 0;
+            }
+        }
+    }
+
+    /// Counts the columns right of the tree column that absorb the label for `id`.
+    fn absorbed_columns(&self, model: &T, id: T::Id) -> usize {
+        self.columns[self.tree_column.saturating_add(1)..]
+            .iter()
+            .take_while(|column| {
+                column
+                    .absorbs_label
+                    .as_ref()
+                    .is_some_and(|is_empty| is_empty(model, id))
+            })
+            .count()
+    }
+
     fn total_width(&self, width: impl Fn(ColumnWidth) -> u16) -> u16 {
         self.columns
             .iter()
@@ -250,7 +475,10 @@ impl<T: TreeModel> TreeColumns<T> for TreeColumnSet<'_, T> {
     }
 
     fn widths(&self, available: u16) -> SmallVec<[u16; 8]> {
-        distribute_widths(available, self.columns.iter().map(|column| column.width))
+        let mut widths =
+            distribute_widths(available, self.columns.iter().map(|column| column.width));
+        self.keep_minimums(&mut widths, available);
+        widths
     }
 
     fn header(&self) -> Option<Row<'_>> {
@@ -271,15 +499,176 @@ impl<T: TreeModel> TreeColumns<T> for TreeColumnSet<'_, T> {
         context: &TreeRowContext<'_>,
         tree_cell: Cell<'a>,
     ) -> SmallVec<[Cell<'a>; 8]> {
-        let mut tree_cell = Some(tree_cell);
+        self.visible_cells(model, id, context, tree_cell, 0..self.columns.len())
+    }
+
+    fn visible_cells<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        tree_cell: Cell<'a>,
+        visible: Range<usize>,
+    ) -> SmallVec<[Cell<'a>; 8]> {
+        let absorbed = self.absorbed_columns(model, id);
+        let span = u16::try_from(absorbed.saturating_add(1)).unwrap_or(u16::MAX);
+        let first_absorbed = self.tree_column.saturating_add(1);
+        let absorbed = first_absorbed..first_absorbed.saturating_add(absorbed);
+        let mut tree_cell = Some(tree_cell.column_span(span));
         self.columns
             .iter()
-            .map(|column| match &column.kind {
+            .enumerate()
+            .filter(|(index, _)| !absorbed.contains(index))
+            .map(|(index, column)| match &column.kind {
                 TreeColumnKind::Tree => tree_cell.take().unwrap_or_default(),
-                TreeColumnKind::Data(renderer) => renderer.cell(model, id, context),
+                TreeColumnKind::Data(renderer) if visible.contains(&index) => {
+                    let cell = self
+                        .aggregate_cell(model, id, context, index)
+                        .unwrap_or_else(|| renderer.cell(model, id, context));
+                    let cell = column.fit(cell, context.column_widths().get(index).copied());
+                    column.apply_style_rule(cell, model, id)
+                }
+                TreeColumnKind::Data(_) => Cell::default(),
             })
             .collect()
     }
+
+    fn aggregate_cell<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        column: usize,
+    ) -> Option<Cell<'a>> {
+        if context.node.expansion != TreeExpansionState::Collapsed {
+            return None;
+        }
+        let column = self.columns.get(column)?;
+        if matches!(column.kind, TreeColumnKind::Tree) {
+            return None;
+        }
+        let aggregate = column.aggregate.as_ref()?;
+        Some(aggregate.cell(model, id, context))
+    }
+
+    fn cell_text<'a>(&'a self, model: &'a T, id: T::Id, column: usize) -> Option<Cow<'a, str>> {
+        let text = self.columns.get(column)?.text.as_ref()?;
+        Some(text(model, id))
+    }
+
+    fn compare_cells(&self, model: &T, left: T::Id, right: T::Id, column: usize) -> Ordering {
+        self.columns
+            .get(column)
+            .and_then(|column| column.sort_key.as_ref())
+            .map_or_else(
+                || {
+                    self.cell_text(model, left, column)
+                        .cmp(&self.cell_text(model, right, column))
+                },
+                |compare| compare(model, left, right),
+            )
+    }
+
+    fn column_help(&self, column: usize) -> Option<Line<'_>> {
+        let column = self.columns.get(column)?;
+        let description = column.description.as_deref()?;
+        Some(Line::from(format!(" {}: {description} ", column.header)))
+    }
+}
+
+/// A grapheme drawn into a scratch line, with its start column and the style it was drawn with.
+struct DrawnGrapheme {
+    x: u16,
+    width: u16,
+    symbol: String,
+    style: Style,
+}
+
+impl DrawnGrapheme {
+    fn is_blank(&self) -> bool {
+        self.symbol.trim().is_empty()
+    }
+}
+
+/// Draws `cell` into a line of `width` columns and returns its graphemes from the left.
+fn draw_cell(cell: Cell<'_>, width: u16) -> Vec<DrawnGrapheme> {
+    let area = Rect::new(0, 0, width, 1);
+    let mut buffer = Buffer::empty(area);
+    Table::new([Row::new([cell])], [Constraint::Length(width)]).render(area, &mut buffer);
+    let mut graphemes = Vec::with_capacity(usize::from(width));
+    let mut x = 0;
+    while x < width {
+        let drawn = &buffer[(x, 0)];
+        let symbol = drawn.symbol();
+        let grapheme_width = u16::try_from(Span::raw(symbol).width()).unwrap_or(1).max(1);
+        let mut style = Style::new().add_modifier(drawn.modifier);
+        if drawn.fg != Color::Reset {
+            style = style.fg(drawn.fg);
+        }
+        if drawn.bg != Color::Reset {
+            style = style.bg(drawn.bg);
+        }
+        graphemes.push(DrawnGrapheme {
+            x,
+            width: grapheme_width,
+            symbol: symbol.to_owned(),
+            style,
+        });
+        x = x.saturating_add(grapheme_width);
+    }
+    graphemes
+}
+
+/// Rebuilds the drawn content of `cell` fitted into `width` columns, or returns it unchanged when
+/// it fits, is not left-aligned, or cannot be fitted.
+///
+/// The content is drawn without the cell's own style one and two columns wider than `width`:
+/// left-aligned content draws the same in both, and overflows when a visible grapheme reaches
+/// past `width`. The fitted cell keeps the original cell style.
+fn fit_drawn(cell: Cell<'_>, width: u16, overflow: TreeCellOverflow) -> Cell<'_> {
+    let content = cell.clone().style(Style::default());
+    let drawn = draw_cell(content.clone(), width.saturating_add(1));
+    let wider = draw_cell(content, width.saturating_add(2));
+    let left_aligned = drawn.iter().zip(&wider).all(|(narrow, wide)| {
+        narrow.x == wide.x && narrow.symbol == wide.symbol && narrow.style == wide.style
+    });
+    let overflows = drawn
+        .iter()
+        .any(|grapheme| grapheme.x + grapheme.width > width && !grapheme.is_blank());
+    if !left_aligned || !overflows {
+        return cell;
+    }
+    let kept = match overflow {
+        TreeCellOverflow::Clip => return cell,
+        TreeCellOverflow::Ellipsis => width - 1,
+        TreeCellOverflow::Wrap => {
+            let boundary = drawn
+                .iter()
+                .rev()
+                .filter(|grapheme| grapheme.x < width)
+                .find(|grapheme| grapheme.is_blank());
+            match boundary {
+                Some(boundary) if boundary.x > 0 => boundary.x,
+                _ => return cell,
+            }
+        }
+    };
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |symbol: &str, style: Style| match spans.last_mut() {
+        Some(span) if span.style == style => span.content.to_mut().push_str(symbol),
+        _ => spans.push(Span::styled(symbol.to_owned(), style)),
+    };
+    for grapheme in drawn.iter().filter(|grapheme| grapheme.x < width) {
+        if grapheme.x + grapheme.width <= kept {
+            push(&grapheme.symbol, grapheme.style);
+        } else if overflow == TreeCellOverflow::Wrap {
+            push(&" ".repeat(usize::from(grapheme.width)), grapheme.style);
+        } else if grapheme.x <= kept {
+            push("…", grapheme.style);
+        }
+    }
+    let style = Styled::style(&cell);
+    Cell::new(Line::from(spans)).style(style)
 }
 
 /// Renders an additional cell with full row context.
@@ -315,6 +704,390 @@ pub trait TreeColumns<T: TreeModel> {
         context: &TreeRowContext<'_>,
         tree_cell: Cell<'a>,
     ) -> SmallVec<[Cell<'a>; 8]>;
+
+    /// Builds a row whose cells outside `visible` may be left empty.
+    ///
+    /// The widget passes the columns intersecting the horizontal viewport, so wide tables only
+    /// pay for the cells on screen. The default builds every cell.
+    fn visible_cells<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        tree_cell: Cell<'a>,
+        visible: Range<usize>,
+    ) -> SmallVec<[Cell<'a>; 8]> {
+        let _ = visible;
+        self.cells(model, id, context, tree_cell)
+    }
+
+    /// Returns a cell summarizing a collapsed branch's subtree, or `None` to render the node's own
+    /// value.
+    fn aggregate_cell<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        column: usize,
+    ) -> Option<Cell<'a>> {
+        let _ = (model, id, context, column);
+        None
+    }
+
+    /// Returns the plain-text value of a column, or `None` when it has none.
+    fn cell_text<'a>(&'a self, model: &'a T, id: T::Id, column: usize) -> Option<Cow<'a, str>> {
+        let _ = (model, id, column);
+        None
+    }
+
+    /// Orders two nodes by one column, used by [`ColumnSort`].
+    ///
+    /// The default compares [`Self::cell_text`], placing nodes without text first.
+    fn compare_cells(&self, model: &T, left: T::Id, right: T::Id, column: usize) -> Ordering {
+        self.cell_text(model, left, column)
+            .cmp(&self.cell_text(model, right, column))
+    }
+
+    /// Returns the footer line shown while a column's header is focused, or `None` for no help.
+    fn column_help(&self, column: usize) -> Option<Line<'_>> {
+        let _ = column;
+        None
+    }
+}
+
+impl<T: TreeModel, C: TreeColumns<T> + ?Sized> TreeColumns<T> for &C {
+    fn column_count(&self) -> usize {
+        (**self).column_count()
+    }
+
+    fn tree_column_index(&self) -> usize {
+        (**self).tree_column_index()
+    }
+
+    fn minimum_width(&self) -> u16 {
+        (**self).minimum_width()
+    }
+
+    fn ideal_width(&self) -> u16 {
+        (**self).ideal_width()
+    }
+
+    fn widths(&self, available: u16) -> SmallVec<[u16; 8]> {
+        (**self).widths(available)
+    }
+
+    fn header(&self) -> Option<Row<'_>> {
+        (**self).header()
+    }
+
+    fn header_height(&self) -> u16 {
+        (**self).header_height()
+    }
+
+    fn cells<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        tree_cell: Cell<'a>,
+    ) -> SmallVec<[Cell<'a>; 8]> {
+        (**self).cells(model, id, context, tree_cell)
+    }
+
+    fn visible_cells<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        tree_cell: Cell<'a>,
+        visible: Range<usize>,
+    ) -> SmallVec<[Cell<'a>; 8]> {
+        (**self).visible_cells(model, id, context, tree_cell, visible)
+    }
+
+    fn aggregate_cell<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        column: usize,
+    ) -> Option<Cell<'a>> {
+        (**self).aggregate_cell(model, id, context, column)
+    }
+
+    fn cell_text<'a>(&'a self, model: &'a T, id: T::Id, column: usize) -> Option<Cow<'a, str>> {
+        (**self).cell_text(model, id, column)
+    }
+
+    fn compare_cells(&self, model: &T, left: T::Id, right: T::Id, column: usize) -> Ordering {
+        (**self).compare_cells(model, left, right, column)
+    }
+
+    fn column_help(&self, column: usize) -> Option<Line<'_>> {
+        (**self).column_help(column)
+    }
+}
+
+/// Runtime column widths that replace the ones computed by [`TreeColumns::widths`].
+///
+/// Adjusted by [`TreeViewAction::GrowColumn`] and [`TreeViewAction::ShrinkColumn`] and saved in
+/// [`TreeListViewSnapshot::column_widths`].
+///
+/// [`TreeViewAction::GrowColumn`]: crate::TreeViewAction::GrowColumn
+/// [`TreeViewAction::ShrinkColumn`]: crate::TreeViewAction::ShrinkColumn
+/// [`TreeListViewSnapshot::column_widths`]: crate::TreeListViewSnapshot::column_widths
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveColumns {
+    widths: SmallVec<[Option<u16>; 8]>,
+}
+
+impl AdaptiveColumns {
+    /// Creates overrides for the given columns; `None` keeps the computed width.
+    #[must_use]
+    pub fn from_widths(widths: impl IntoIterator<Item = Option<u16>>) -> Self {
+        let mut columns = Self {
+            widths: widths.into_iter().collect(),
+        };
+        columns.trim();
+        columns
+    }
+
+    /// Returns the override of one column.
+    #[must_use]
+    pub fn width(&self, column: usize) -> Option<u16> {
+        self.widths.get(column).copied().flatten()
+    }
+
+    /// Returns the overrides by column index, without trailing `None` entries.
+    #[must_use]
+    pub fn widths(&self) -> &[Option<u16>] {
+        &self.widths
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.widths.is_empty()
+    }
+
+    /// Overrides the width of one column, or restores its computed width with `None`.
+    pub fn set_width(&mut self, column: usize, width: Option<u16>) -> bool {
+        if self.width(column) == width {
+            return false;
+        }
+        if self.widths.len() <= column {
+            self.widths.resize(column + 1, None);
+        }
+        self.widths[column] = width;
+        self.trim();
+        true
+    }
+
+    /// Restores the computed width of every column.
+    pub fn clear(&mut self) -> bool {
+        let changed = !self.widths.is_empty();
+        self.widths.clear();
+        changed
+    }
+
+    pub(crate) fn apply(&self, widths: &mut [u16]) {
+        for (width, column) in widths.iter_mut().zip(&self.widths) {
+            if let Some(column) = column {
+                *width = *column;
+            }
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.widths.last() == Some(&None) {
+            self.widths.pop();
+        }
+    }
+}
+
+/// A filter that matches nodes by the text of one column.
+///
+/// Nodes whose column has no text never match.
+#[derive(Clone, Copy, Debug)]
+pub struct ColumnFilter<C, P> {
+    columns: C,
+    column: usize,
+    predicate: P,
+}
+
+impl<C, P> ColumnFilter<C, P> {
+    /// Creates a filter that tests the text of `column` with `predicate`.
+    #[must_use]
+    pub const fn new(columns: C, column: usize, predicate: P) -> Self {
+        Self {
+            columns,
+            column,
+            predicate,
+        }
+    }
+
+    /// Returns the filtered column index.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl<T, C, P> TreeFilter<T> for ColumnFilter<C, P>
+where
+    T: TreeModel,
+    C: TreeColumns<T>,
+    P: Fn(&str) -> bool,
+{
+    fn is_match(&self, model: &T, id: T::Id) -> bool {
+        self.columns
+            .cell_text(model, id, self.column)
+            .is_some_and(|text| (self.predicate)(&text))
+    }
+}
+
+/// The direction of one [`TreeSortKeys`] entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TreeSortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// An ordered list of sort columns, from primary to least significant.
+///
+/// Header activations cycle a column through ascending, descending, and unsorted with
+/// [`Self::cycle`] or [`Self::cycle_secondary`]. The revision advances with every change, so it
+/// can be passed to [`TreeQuery::with_sort`](crate::TreeQuery::with_sort) with a [`ColumnSort`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeSortKeys {
+    keys: SmallVec<[(usize, TreeSortDirection); 2]>,
+    revision: TreeRevision,
+}
+
+impl TreeSortKeys {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the sort columns, from primary to least significant.
+    #[must_use]
+    pub fn keys(&self) -> &[(usize, TreeSortDirection)] {
+        &self.keys
+    }
+
+    #[must_use]
+    pub const fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+
+    /// Returns the direction of `column` and its zero-based priority, for header indicators.
+    #[must_use]
+    pub fn direction(&self, column: usize) -> Option<(TreeSortDirection, usize)> {
+        self.keys
+            .iter()
+            .enumerate()
+            .find_map(|(priority, (key, direction))| {
+                (*key == column).then_some((*direction, priority))
+            })
+    }
+
+    /// Replaces all keys. A later duplicate of a column is ignored.
+    pub fn set(&mut self, keys: impl IntoIterator<Item = (usize, TreeSortDirection)>) -> bool {
+        let mut next: SmallVec<[(usize, TreeSortDirection); 2]> = SmallVec::new();
+        for (column, direction) in keys {
+            if next.iter().all(|(key, _)| *key != column) {
+                next.push((column, direction));
+            }
+        }
+        self.replace(next)
+    }
+
+    /// Makes `column` the only key, cycling it from ascending to descending to unsorted on
+    /// repeated calls. A column that is not the primary key restarts at ascending.
+    pub fn cycle(&mut self, column: usize) -> bool {
+        let next = match self.keys.first() {
+            Some(&(key, TreeSortDirection::Ascending)) if key == column => {
+                Some(TreeSortDirection::Descending)
+            }
+            Some(&(key, TreeSortDirection::Descending)) if key == column => None,
+            _ => Some(TreeSortDirection::Ascending),
+        };
+        self.set(next.map(|direction| (column, direction)))
+    }
+
+    /// Cycles `column` like [`Self::cycle`] while keeping the other keys, appending it as the
+    /// least significant key when it is not sorted yet.
+    pub fn cycle_secondary(&mut self, column: usize) -> bool {
+        let mut next = self.keys.clone();
+        match next.iter().position(|(key, _)| *key == column) {
+            Some(index) if next[index].1 == TreeSortDirection::Ascending => {
+                next[index].1 = TreeSortDirection::Descending;
+            }
+            Some(index) => {
+                next.remove(index);
+            }
+            None => next.push((column, TreeSortDirection::Ascending)),
+        }
+        self.replace(next)
+    }
+
+    pub fn clear(&mut self) -> bool {
+        self.replace(SmallVec::new())
+    }
+
+    fn replace(&mut self, keys: SmallVec<[(usize, TreeSortDirection); 2]>) -> bool {
+        if self.keys == keys {
+            return false;
+        }
+        self.keys = keys;
+        self.revision.advance();
+        true
+    }
+}
+
+/// A sort that orders siblings by several columns through [`TreeColumns::compare_cells`].
+///
+/// Siblings equal in every key keep their model order.
+#[derive(Clone, Debug)]
+pub struct ColumnSort<C> {
+    columns: C,
+    keys: SmallVec<[(usize, TreeSortDirection); 2]>,
+}
+
+impl<C> ColumnSort<C> {
+    /// Creates a sort over the current `keys`.
+    #[must_use]
+    pub fn new(columns: C, keys: &TreeSortKeys) -> Self {
+        Self {
+            columns,
+            keys: keys.keys.clone(),
+        }
+    }
+}
+
+impl<T, C> TreeSort<T> for ColumnSort<C>
+where
+    T: TreeModel,
+    C: TreeColumns<T>,
+{
+    fn compare(&self, model: &T, left: T::Id, right: T::Id) -> Ordering {
+        self.keys
+            .iter()
+            .map(|(column, direction)| {
+                let order = self.columns.compare_cells(model, left, right, *column);
+                match direction {
+                    TreeSortDirection::Ascending => order,
+                    TreeSortDirection::Descending => order.reverse(),
+                }
+            })
+            .find(|order| order.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
 }
 
 /// Distributes width as evenly as possible between `min`, `ideal`, and `max`.
@@ -374,6 +1147,8 @@ fn grow_towards(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adapters::IndexedTree;
+    use crate::model::TreeRevision;
 
     #[test]
     fn column_width_rejects_invalid_ranges() {
@@ -414,4 +1189,90 @@ mod tests {
             assert_eq!(actual, total.clamp(6, 28));
         }
     }
+
+    #[test]
+    fn column_filter_tests_the_column_text() {
+        const SIZES: [u64; 3] = [512, 2_000_000, 3_000_000];
+        let children = vec![vec![1, 2], vec![], vec![]];
+        let tree = IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("valid tree");
+        let columns = TreeColumnSet::new([
+            ColumnDef::tree("Name", ColumnWidth::fixed(8)),
+            ColumnDef::data_owned("Size", ColumnWidth::fixed(8), |_, _, _| Cell::default())
+                .with_text(|_: &IndexedTree<'_>, id| Cow::Owned(SIZES[id].to_string())),
+        ])
+        .expect("one tree column");
+
+        let large = ColumnFilter::new(&columns, 1, |text: &str| {
+            text.parse::<u64>().is_ok_and(|size| size > 1_000_000)
+        });
+        assert!(!large.is_match(&tree, 0));
+        assert!(large.is_match(&tree, 1));
+        assert_eq!(columns.cell_text(&tree, 2, 1).as_deref(), Some("3000000"));
+
+        let untexted = ColumnFilter::new(&columns, 0, |_: &str| true);
+        assert!(!untexted.is_match(&tree, 0));
+    }
+
+    #[test]
+    fn column_sort_orders_by_several_keys_and_cycles_directions() {
+        const KINDS: [&str; 4] = ["", "file", "dir", "file"];
+        const NAMES: [&str; 4] = ["", "b", "c", "a"];
+        let children = vec![vec![1, 2, 3], vec![], vec![], vec![]];
+        let tree = IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("valid tree");
+        let columns = TreeColumnSet::new([
+            ColumnDef::tree("Name", ColumnWidth::fixed(8))
+                .with_text(|_: &IndexedTree<'_>, id| Cow::Borrowed(NAMES[id])),
+            ColumnDef::data_owned("Kind", ColumnWidth::fixed(8), |_, _, _| Cell::default())
+                .with_sort_key(|_: &IndexedTree<'_>, left, right| KINDS[left].cmp(KINDS[right])),
+        ])
+        .expect("one tree column");
+        let sorted = |keys: &TreeSortKeys| {
+            let sort = ColumnSort::new(&columns, keys);
+            let mut ids = vec![1, 2, 3];
+            ids.sort_by(|left, right| sort.compare(&tree, *left, *right));
+            ids
+        };
+
+        let mut keys = TreeSortKeys::new();
+        assert!(keys.cycle(1));
+        assert!(keys.cycle_secondary(0));
+        assert_eq!(sorted(&keys), [2, 3, 1]);
+        assert_eq!(keys.direction(0), Some((TreeSortDirection::Ascending, 1)));
+
+        let revision = keys.revision();
+        assert!(keys.cycle_secondary(0));
+        assert_eq!(sorted(&keys), [2, 1, 3]);
+        assert!(keys.revision() > revision);
+
+        assert!(keys.cycle(1), "cycling replaces the secondary key");
+        assert_eq!(keys.keys(), [(1, TreeSortDirection::Descending)]);
+        assert_eq!(sorted(&keys), [1, 3, 2], "ties keep model order");
+        assert!(keys.cycle(1));
+        assert!(keys.keys().is_empty());
+        assert!(!ColumnSort::new(&columns, &keys).is_enabled());
+    }
+
+    #[test]
+    fn never_truncated_columns_are_hidden_or_take_room_from_the_label() {
+        let data =
+            |min| ColumnDef::data_owned("", ColumnWidth::fixed(min), |_, _, _| Cell::default());
+        let columns = || {
+            [
+                ColumnDef::tree("Name", ColumnWidth::flexible(10, 20).expect("valid width")),
+                data(6).never_truncate(),
+                data(4),
+                data(8).never_truncate(),
+            ]
+        };
+        let dropping = TreeColumnSet::<IndexedTree<'_>>::new(columns()).expect("one tree column");
+        assert_eq!(dropping.widths(40).as_slice(), &[22, 6, 4, 8]);
+        assert_eq!(dropping.widths(26).as_slice(), &[10, 6, 4, 0]);
+        assert_eq!(dropping.widths(16).as_slice(), &[10, 0, 4, 0]);
+
+        let stealing = TreeColumnSet::<IndexedTree<'_>>::new(columns())
+            .expect("one tree column")
+            .squeeze(TreeColumnSqueeze::StealFromLabel);
+        assert_eq!(stealing.widths(26).as_slice(), &[8, 6, 4, 8]);
+        assert_eq!(stealing.widths(12).as_slice(), &[1, 6, 4, 0]);
+    }
 }
@@ -0,0 +1,236 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget};
+use smallvec::SmallVec;
+
+use crate::context::{TreeExpansionState, TreeMarkState};
+use crate::glyphs::{TreeGlyphs, TreeLabelProvider};
+use crate::model::{TreeFilter, TreeFilterConfig, TreeModel, TreeQuery, TreeSort};
+use crate::projection::ProjectedNode;
+use crate::state::TreeListViewState;
+use crate::state::hit::{PaneHitBox, TreeHitMap};
+use crate::style::TreeListViewStyle;
+
+/// A Miller-columns presentation of the same state: the parent's siblings, the selection's
+/// siblings, and a preview of the selected node's children side by side.
+///
+/// Navigation reuses [`TreeListViewState`] actions and key bindings, where
+/// `CollapseOrSelectParent` and `ExpandOrSelectFirstChild` move between columns. Filtering,
+/// sorting, and marks come from the shared projection. Collapsed branches preview their loaded
+/// children only while filtering is disabled. Clicks on a listed projected row select it through
+/// [`TreeListViewState::handle_click`].
+pub struct TreeColumnsView<'a, T, F, S, L> {
+    model: &'a T,
+    query: &'a TreeQuery<F, S>,
+    label: &'a L,
+    style: TreeListViewStyle<'a>,
+    glyphs: TreeGlyphs<'a>,
+}
+
+impl<'a, T, F, S, L> TreeColumnsView<'a, T, F, S, L>
+where
+    T: TreeModel,
+    F: TreeFilter<T>,
+    S: TreeSort<T>,
+    L: TreeLabelProvider<T>,
+{
+    #[must_use]
+    pub const fn new(
+        model: &'a T,
+        query: &'a TreeQuery<F, S>,
+        label: &'a L,
+        style: TreeListViewStyle<'a>,
+    ) -> Self {
+        Self {
+            model,
+            query,
+            label,
+            style,
+            glyphs: TreeGlyphs::unicode(),
+        }
+    }
+
+    /// Sets the glyph collection.
+    #[must_use]
+    pub const fn glyphs(mut self, glyphs: TreeGlyphs<'a>) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    fn item(
+        &self,
+        state: &TreeListViewState<T::Id>,
+        id: T::Id,
+        expansion: TreeExpansionState,
+    ) -> ListItem<'a> {
+        let parts = self.label.label_parts(self.model, id);
+        let mut spans = Vec::with_capacity(4);
+        if let Some(prefix) = parts.prefix.filter(|prefix| !prefix.is_empty()) {
+            spans.push(Span::raw(prefix));
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::raw(parts.name));
+        let glyph = match expansion {
+            TreeExpansionState::Leaf => None,
+            TreeExpansionState::Collapsed
+            | TreeExpansionState::Expanded
+            | TreeExpansionState::ForcedByFilter => Some(self.glyphs.collapsed),
+            TreeExpansionState::Unloaded => Some(self.glyphs.unloaded),
+            TreeExpansionState::Loading => Some(self.glyphs.loading),
+        };
+        if let Some(glyph) = glyph.filter(|glyph| !glyph.is_empty()) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(glyph, self.style.line_style));
+        }
+        let style = match state.mark_state_cached(id) {
+            TreeMarkState::Unmarked => Style::default(),
+            TreeMarkState::Partial => self.style.partial_mark_style,
+            TreeMarkState::Marked => self.style.marked_style,
+        };
+        ListItem::new(Line::from(spans)).style(style)
+    }
+
+    /// Lists the projected rows under `parent_index` and the position of `highlighted` among them.
+    fn siblings(
+        &self,
+        state: &TreeListViewState<T::Id>,
+        parent_index: Option<usize>,
+        highlighted: Option<usize>,
+    ) -> (Vec<ListItem<'a>>, Option<usize>) {
+        let rows = state.column_rows(parent_index);
+        let nodes = state.projection().nodes();
+        let position = highlighted.and_then(|highlighted| rows.binary_search(&highlighted).ok());
+        let items = rows
+            .iter()
+            .filter_map(|&index| nodes.get(index))
+            .map(|node| self.item(state, node.id(), node.expansion()))
+            .collect();
+        (items, position)
+    }
+
+    /// Lists the children of the row at `index`, which are projected rows when it is expanded.
+    fn preview(&self, state: &TreeListViewState<T::Id>, index: usize) -> Vec<ListItem<'a>> {
+        let Some(node) = state.projection().nodes().get(index).copied() else {
+            return Vec::new();
+        };
+        if node.expansion().is_expanded() {
+            return self.siblings(state, Some(index), None).0;
+        }
+        if !matches!(self.query.filter_config(), TreeFilterConfig::Disabled) {
+            return Vec::new();
+        }
+        let mut children = self.model.children(node.id()).loaded_slice().to_vec();
+        let sort = self.query.sort();
+        if sort.is_enabled() {
+            children.sort_by(|left, right| sort.compare(self.model, *left, *right));
+        }
+        children
+            .into_iter()
+            .map(|child| {
+                let expansion = if self.model.children(child).is_branch() {
+                    TreeExpansionState::Collapsed
+                } else {
+                    TreeExpansionState::Leaf
+                };
+                self.item(state, child, expansion)
+            })
+            .collect()
+    }
+
+    /// Renders one list and returns the index of its first visible item.
+    fn render_pane(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        items: Vec<ListItem<'a>>,
+        selected: Option<usize>,
+        symbol: &'a str,
+    ) -> usize {
+        let list = List::new(items)
+            .highlight_style(self.style.highlight_style)
+            .highlight_symbol(Line::from(symbol))
+            .highlight_spacing(HighlightSpacing::Always);
+        let mut list_state = ListState::default().with_selected(selected);
+        StatefulWidget::render(list, area, buffer, &mut list_state);
+        list_state.offset()
+    }
+}
+
+impl<T, F, S, L> StatefulWidget for TreeColumnsView<'_, T, F, S, L>
+where
+    T: TreeModel,
+    F: TreeFilter<T>,
+    S: TreeSort<T>,
+    L: TreeLabelProvider<T>,
+{
+    type State = TreeListViewState<T::Id>;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        state.hit_map = TreeHitMap::default();
+        let block = self.style.block();
+        let inner = block.inner(area);
+        block.render(area, buffer);
+        if inner.is_empty() {
+            return;
+        }
+        state.ensure_projection(self.model, self.query);
+        state.ensure_mark_states(self.model);
+
+        let [parents, current, preview] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+        ])
+        .spacing(self.style.column_spacing)
+        .areas(inner);
+
+        state.ensure_column_rows();
+
+        let mut panes = SmallVec::new();
+        let selected = state.selected_index();
+        let node = state.selected_node();
+        let parent_index = node.and_then(ProjectedNode::parent_index);
+        if let Some(parent_index) = parent_index {
+            let grandparent = state
+                .projection()
+                .nodes()
+                .get(parent_index)
+                .and_then(|parent| parent.parent_index());
+            let (items, position) = self.siblings(state, grandparent, Some(parent_index));
+            let offset = self.render_pane(parents, buffer, items, position, "");
+            panes.push(PaneHitBox {
+                area: parents,
+                parent_index: grandparent,
+                offset,
+            });
+        }
+        let (items, position) = self.siblings(state, parent_index, selected);
+        let offset = self.render_pane(
+            current,
+            buffer,
+            items,
+            position,
+            self.style.highlight_symbol,
+        );
+        panes.push(PaneHitBox {
+            area: current,
+            parent_index,
+            offset,
+        });
+        if let Some(selected) = selected {
+            let items = self.preview(state, selected);
+            let offset = self.render_pane(preview, buffer, items, None, "");
+            if node.is_some_and(|node| node.expansion().is_expanded()) {
+                panes.push(PaneHitBox {
+                    area: preview,
+                    parent_index: Some(selected),
+                    offset,
+                });
+            }
+        }
+        state.hit_map.panes = panes;
+        state.mark_rendered();
+    }
+}
@@ -1,5 +1,7 @@
 use ratatui::style::Style;
 
+use crate::glyphs::TreeLeafAlignment;
+
 /// A node's effective expansion state in the current projection.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TreeExpansionState {
@@ -42,12 +44,64 @@ pub enum TreeMarkState {
     Marked,
 }
 
+/// Why a node is marked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeMarkSource {
+    /// The node itself is marked.
+    Manual,
+    /// The node is marked only because of marks below it.
+    Inherited,
+}
+
 /// Node state available to row renderers.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TreeRowNodeState {
     pub expansion: TreeExpansionState,
     pub mark: TreeMarkState,
     pub match_state: TreeMatchState,
+    mark_source: Option<TreeMarkSource>,
+    matched_descendants: usize,
+}
+
+impl TreeRowNodeState {
+    #[must_use]
+    pub const fn new(
+        expansion: TreeExpansionState,
+        mark: TreeMarkState,
+        match_state: TreeMatchState,
+    ) -> Self {
+        Self {
+            expansion,
+            mark,
+            match_state,
+            mark_source: None,
+            matched_descendants: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_mark_source(mut self, source: Option<TreeMarkSource>) -> Self {
+        self.mark_source = source;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_matched_descendants(mut self, count: usize) -> Self {
+        self.matched_descendants = count;
+        self
+    }
+
+    /// Returns why the node is marked, or `None` when it is [`TreeMarkState::Unmarked`].
+    #[must_use]
+    pub const fn mark_source(&self) -> Option<TreeMarkSource> {
+        self.mark_source
+    }
+
+    /// Returns the direct filter matches below the node, for badges on collapsed ancestors.
+    #[must_use]
+    pub const fn matched_descendants(&self) -> usize {
+        self.matched_descendants
+    }
 }
 
 /// View state available to row renderers.
@@ -68,4 +122,94 @@ pub struct TreeRowContext<'a> {
     pub node: TreeRowNodeState,
     pub render: TreeRowRenderState,
     pub line_style: Style,
+    /// The bits of the fill fraction, so the context stays `Eq`.
+    fill_fraction: Option<u32>,
+    column_widths: &'a [u16],
+    flagged_columns: &'a [usize],
+    leaf_alignment: TreeLeafAlignment,
+}
+
+impl<'a> TreeRowContext<'a> {
+    #[must_use]
+    pub const fn new(
+        level: usize,
+        is_tail_stack: &'a [bool],
+        node: TreeRowNodeState,
+        render: TreeRowRenderState,
+        line_style: Style,
+    ) -> Self {
+        Self {
+            level,
+            is_tail_stack,
+            node,
+            render,
+            line_style,
+            fill_fraction: None,
+            column_widths: &[],
+            flagged_columns: &[],
+            leaf_alignment: TreeLeafAlignment::Compact,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_fill_fraction(mut self, fraction: Option<f32>) -> Self {
+        self.fill_fraction = match fraction {
+            Some(fraction) => Some(fraction.to_bits()),
+            None => None,
+        };
+        self
+    }
+
+    #[must_use]
+    pub const fn with_column_widths(mut self, widths: &'a [u16]) -> Self {
+        self.column_widths = widths;
+        self
+    }
+
+    /// Sets the flagged columns, which must be in ascending order.
+    #[must_use]
+    pub const fn with_flagged_columns(mut self, columns: &'a [usize]) -> Self {
+        self.flagged_columns = columns;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_leaf_alignment(mut self, alignment: TreeLeafAlignment) -> Self {
+        self.leaf_alignment = alignment;
+        self
+    }
+
+    /// Returns the share of the row painted with the background fill, from
+    /// [`TreeModel::fill_fraction`](crate::TreeModel::fill_fraction).
+    #[must_use]
+    pub const fn fill_fraction(&self) -> Option<f32> {
+        match self.fill_fraction {
+            Some(bits) => Some(f32::from_bits(bits)),
+            None => None,
+        }
+    }
+
+    /// Returns the width of every column in this frame, by column index.
+    #[must_use]
+    pub const fn column_widths(&self) -> &'a [u16] {
+        self.column_widths
+    }
+
+    /// Returns the columns whose cell is flagged for this node, in ascending order.
+    #[must_use]
+    pub const fn flagged_columns(&self) -> &'a [usize] {
+        self.flagged_columns
+    }
+
+    /// Returns how leaf labels line up with the labels of expandable siblings.
+    #[must_use]
+    pub const fn leaf_alignment(&self) -> TreeLeafAlignment {
+        self.leaf_alignment
+    }
+
+    /// Returns `true` when the cell in `column` is flagged, so a renderer can draw a check mark.
+    #[must_use]
+    pub fn is_cell_flagged(&self, column: usize) -> bool {
+        self.flagged_columns.binary_search(&column).is_ok()
+    }
 }
@@ -0,0 +1,13 @@
+use std::borrow::Cow;
+
+use crate::model::TreeModel;
+
+/// Supplies the lines of the detail popup opened by
+/// [`TreeViewAction::ShowDetails`](crate::TreeViewAction::ShowDetails).
+///
+/// Useful for metadata that does not fit in columns. Pass the provider to
+/// [`TreeListView::details`](crate::TreeListView::details).
+pub trait TreeDetailProvider<T: TreeModel> {
+    /// Returns the key/value lines for `id`. No popup is drawn when the list is empty.
+    fn details<'a>(&'a self, model: &'a T, id: T::Id) -> Vec<(Cow<'a, str>, Cow<'a, str>)>;
+}
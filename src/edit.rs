@@ -1,6 +1,12 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
 
 use crate::model::TreeModel;
+use crate::traversal::TreeWalk;
 
 /// An insertion position within a child list.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -69,6 +75,105 @@ pub struct TreeChangeSet<Id> {
     pub selection: TreeSelectionUpdate<Id>,
 }
 
+/// An error produced by [`crate::TreeListViewState::apply_edit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeEditError<Id, E> {
+    /// Moving `node` under `parent` would place it inside its own subtree.
+    Cycle { node: Id, parent: Id },
+    /// A root cannot be detached because it has no parent.
+    RootProtected(Id),
+    /// The target node is not reachable from the model roots.
+    InvalidTarget(Id),
+    /// The model rejected the command.
+    Model(E),
+}
+
+impl<Id, E: Display> Display for TreeEditError<Id, E> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle { .. } => formatter.write_str("node cannot be moved into its own subtree"),
+            Self::RootProtected(_) => formatter.write_str("root node cannot be detached"),
+            Self::InvalidTarget(_) => formatter.write_str("target node is not in the tree"),
+            Self::Model(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl<Id: Debug, E: Error + 'static> Error for TreeEditError<Id, E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Cycle { .. } | Self::RootProtected(_) | Self::InvalidTarget(_) => None,
+            Self::Model(error) => Some(error),
+        }
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeEditCommand<Id> {
+    /// Checks structural preconditions that hold for every model.
+    ///
+    /// Every target, including moved nodes, must be reachable from the roots. The forest is
+    /// walked once without revisiting shared subtrees, and only until every target is found.
+    pub(crate) fn validate<T, E>(&self, model: &T) -> Result<(), TreeEditError<Id, E>>
+    where
+        T: TreeModel<Id = Id>,
+    {
+        let mut missing: FxHashSet<Id> = self.targets().collect();
+        for node in TreeWalk::forest(model).once() {
+            if missing.is_empty() {
+                break;
+            }
+            missing.remove(&node.id);
+        }
+        let require = |id: Id| {
+            if missing.contains(&id) {
+                Err(TreeEditError::InvalidTarget(id))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            Self::CreateChild { parent } => require(*parent),
+            Self::Rename { node } => require(*node),
+            Self::Move { nodes, parent, .. } => {
+                require(*parent)?;
+                nodes.iter().try_for_each(|node| {
+                    require(*node)?;
+                    if TreeWalk::subtree(model, None, *node)
+                        .once()
+                        .any(|walk| walk.id == *parent)
+                    {
+                        Err(TreeEditError::Cycle {
+                            node: *node,
+                            parent: *parent,
+                        })
+                    } else {
+                        Ok(())
+                    }
+                })
+            }
+            Self::Detach { nodes } => nodes.iter().try_for_each(|node| {
+                if model.roots().any(|root| root == *node) {
+                    Err(TreeEditError::RootProtected(*node))
+                } else {
+                    require(*node)
+                }
+            }),
+            Self::Delete { nodes } => nodes.iter().try_for_each(|node| require(*node)),
+        }
+    }
+
+    /// Returns every id the command acts on.
+    fn targets(&self) -> impl Iterator<Item = Id> + '_ {
+        let (nodes, single): (&[Id], Option<Id>) = match self {
+            Self::CreateChild { parent } => (&[], Some(*parent)),
+            Self::Rename { node } => (&[], Some(*node)),
+            Self::Move { nodes, parent, .. } => (nodes, Some(*parent)),
+            Self::Detach { nodes } | Self::Delete { nodes } => (nodes, None),
+        };
+        nodes.iter().copied().chain(single)
+    }
+}
+
 /// Applies typed editing commands to a domain model.
 pub trait TreeEditor: TreeModel {
     type Error;
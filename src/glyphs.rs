@@ -1,12 +1,23 @@
 use std::borrow::Cow;
 
-use ratatui::text::{Line, Span};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::Cell;
 use smallvec::SmallVec;
 
 use crate::context::{TreeExpansionState, TreeRowContext};
 use crate::model::TreeModel;
 
+/// How leaf labels line up with the labels of expandable siblings, set through
+/// [`TreeListViewStyle::leaf_alignment`](crate::TreeListViewStyle::leaf_alignment).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeLeafAlignment {
+    /// The leaf glyph is drawn as is, so an empty one shifts the name left.
+    #[default]
+    Compact,
+    /// The leaf glyph is padded to the widest expander glyph, so names line up vertically.
+    Aligned,
+}
+
 /// Glyphs for tree structure and lazy-loading states.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TreeGlyphs<'a> {
@@ -56,6 +67,64 @@ impl TreeGlyphs<'static> {
             loading: "~",
         }
     }
+
+    /// A Unicode glyph set whose characters stay narrow in CJK terminals.
+    ///
+    /// Box-drawing characters have ambiguous East Asian width and occupy two cells in some CJK
+    /// locales, so guides use ASCII while state glyphs keep narrow Unicode shapes.
+    #[must_use]
+    pub const fn unicode_light() -> Self {
+        Self {
+            indent: "   ",
+            branch_last: "`--",
+            branch: "|--",
+            vert: "|  ",
+            empty: "   ",
+            leaf: "∙",
+            expanded: "▾",
+            collapsed: "▸",
+            unloaded: "◦",
+            loading: "◌",
+        }
+    }
+
+    /// Picks a glyph set from the terminal environment.
+    ///
+    /// Returns [`Self::ascii`] for dumb terminals, non-UTF-8 locales, and legacy Windows
+    /// consoles; [`Self::unicode_light`] for CJK UTF-8 locales; and [`Self::unicode`] otherwise.
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::detect_with(|name| std::env::var(name).ok(), cfg!(windows))
+    }
+
+    fn detect_with(var: impl Fn(&str) -> Option<String>, windows: bool) -> Self {
+        let var = |name| var(name).filter(|value| !value.is_empty());
+        if var("TERM").is_some_and(|term| term == "dumb") {
+            return Self::ascii();
+        }
+        if windows && var("WT_SESSION").is_none() && var("TERM_PROGRAM").is_none() {
+            return Self::ascii();
+        }
+
+        let Some(locale) = var("LC_ALL")
+            .or_else(|| var("LC_CTYPE"))
+            .or_else(|| var("LANG"))
+        else {
+            return Self::unicode();
+        };
+        let locale = locale.to_ascii_lowercase();
+        if !(locale.contains("utf-8") || locale.contains("utf8")) {
+            return Self::ascii();
+        }
+        if ["ja", "ko", "zh"]
+            .iter()
+            .any(|language| locale.starts_with(language))
+        {
+            Self::unicode_light()
+        } else {
+            Self::unicode()
+        }
+    }
 }
 
 /// A node name with an optional leading icon.
@@ -90,6 +159,31 @@ pub trait TreeLabelRenderer<T: TreeModel> {
         context: &TreeRowContext<'_>,
         glyphs: &TreeGlyphs<'a>,
     ) -> Cell<'a>;
+
+    /// Returns the display width of the contents of [`cell`](Self::cell), which
+    /// [`TreeLabelScrollbar::Visible`](crate::TreeLabelScrollbar::Visible) compares with the tree
+    /// column. `None` leaves the row out.
+    fn width<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        glyphs: &TreeGlyphs<'a>,
+    ) -> Option<usize> {
+        let _ = (model, id, context, glyphs);
+        None
+    }
+
+    /// Returns lines drawn above the rows for the model's first root, such as its path or
+    /// statistics, giving dashboards a header-like root.
+    ///
+    /// The banner is not part of the root row: it stays in place while the rows scroll, and the
+    /// root row itself stays one line tall, so selection, paging, and hit testing keep counting
+    /// one line per row.
+    fn root_banner<'a>(&'a self, model: &'a T, root: T::Id) -> Option<Text<'a>> {
+        let _ = (model, root);
+        None
+    }
 }
 
 impl<T, P> TreeLabelRenderer<T> for P
@@ -106,9 +200,22 @@ where
     ) -> Cell<'a> {
         tree_name_cell(context, self.label_parts(model, id), glyphs)
     }
+
+    fn width<'a>(
+        &'a self,
+        model: &'a T,
+        id: T::Id,
+        context: &TreeRowContext<'_>,
+        glyphs: &TreeGlyphs<'a>,
+    ) -> Option<usize> {
+        Some(tree_label_line(context, self.label_parts(model, id), glyphs).width())
+    }
 }
 
 /// Builds the primary cell contents, including guides and branch state.
+///
+/// Spans are measured by display width, so CJK, emoji, and combining characters align with the
+/// column layout.
 #[must_use]
 pub fn tree_label_line<'a>(
     context: &TreeRowContext<'_>,
@@ -148,7 +255,29 @@ pub fn tree_label_line<'a>(
         TreeExpansionState::Loading => Some(glyphs.loading),
     };
 
-    if let Some(glyph) = state_glyph.filter(|glyph| !glyph.is_empty()) {
+    let aligned = context.leaf_alignment() == TreeLeafAlignment::Aligned
+        && context.node.expansion == TreeExpansionState::Leaf;
+    if aligned {
+        let glyph = state_glyph.unwrap_or_default();
+        let expander = [
+            glyphs.collapsed,
+            glyphs.expanded,
+            glyphs.unloaded,
+            glyphs.loading,
+        ]
+        .into_iter()
+        .map(|expander| Span::raw(expander).width())
+        .max()
+        .unwrap_or_default();
+        let padding = expander.saturating_sub(Span::raw(glyph).width());
+        if expander > 0 {
+            push_separator(&mut spans);
+            spans.push(Span::raw(glyph));
+            if padding > 0 {
+                spans.push(Span::raw(" ".repeat(padding)));
+            }
+        }
+    } else if let Some(glyph) = state_glyph.filter(|glyph| !glyph.is_empty()) {
         push_separator(&mut spans);
         spans.push(Span::raw(glyph));
     }
@@ -187,21 +316,21 @@ mod tests {
     use crate::context::{TreeMarkState, TreeMatchState, TreeRowNodeState, TreeRowRenderState};
 
     fn context(level: usize, tails: &[bool], expansion: TreeExpansionState) -> TreeRowContext<'_> {
-        TreeRowContext {
+        TreeRowContext::new(
             level,
-            is_tail_stack: tails,
-            node: TreeRowNodeState {
+            tails,
+            TreeRowNodeState::new(
                 expansion,
-                mark: TreeMarkState::Unmarked,
-                match_state: TreeMatchState::Unfiltered,
-            },
-            render: TreeRowRenderState {
+                TreeMarkState::Unmarked,
+                TreeMatchState::Unfiltered,
+            ),
+            TreeRowRenderState {
                 draw_lines: true,
                 is_selected: false,
                 selected_column: None,
             },
-            line_style: Style::default(),
-        }
+            Style::default(),
+        )
     }
 
     #[test]
@@ -221,6 +350,50 @@ mod tests {
         assert_eq!(leaf.to_string(), "│  └── • leaf");
     }
 
+    #[test]
+    fn measures_labels_by_display_width() {
+        let wide = tree_label_line(
+            &context(2, &[false, true], TreeExpansionState::Leaf),
+            TreeLabelPrefix {
+                name: Cow::Borrowed("日本語"),
+                prefix: Some(Cow::Borrowed("📁")),
+            },
+            &TreeGlyphs::unicode(),
+        );
+        assert_eq!(wide.to_string(), "│  └── • 📁 日本語");
+        assert_eq!(wide.width(), 18);
+
+        let combining = tree_label_line(
+            &context(0, &[], TreeExpansionState::Leaf),
+            TreeLabelPrefix::borrowed("cafe\u{301}"),
+            &TreeGlyphs::unicode(),
+        );
+        assert_eq!(combining.width(), 4);
+    }
+
+    #[test]
+    fn aligned_leaves_pad_to_the_expander_width() {
+        let glyphs = TreeGlyphs {
+            leaf: "",
+            ..TreeGlyphs::unicode()
+        };
+        let label = |level, tails, expansion| {
+            let context =
+                context(level, tails, expansion).with_leaf_alignment(TreeLeafAlignment::Aligned);
+            tree_label_line(&context, TreeLabelPrefix::borrowed("name"), &glyphs).to_string()
+        };
+        assert_eq!(
+            label(1, &[false], TreeExpansionState::Collapsed),
+            "├── ▶ name"
+        );
+        assert_eq!(label(1, &[true], TreeExpansionState::Leaf), "└──   name");
+        assert_eq!(label(0, &[], TreeExpansionState::Leaf), "  name");
+
+        let context = context(1, &[true], TreeExpansionState::Leaf);
+        let line = tree_label_line(&context, TreeLabelPrefix::borrowed("name"), &glyphs);
+        assert_eq!(line.to_string(), "└── name");
+    }
+
     #[test]
     fn renders_lazy_states() {
         let unloaded = tree_label_line(
@@ -230,4 +403,31 @@ mod tests {
         );
         assert_eq!(unloaded.to_string(), "◇ remote");
     }
+
+    #[test]
+    fn detects_glyphs_from_the_environment() {
+        let detect = |vars: &[(&str, &str)], windows| {
+            TreeGlyphs::detect_with(
+                |name| {
+                    vars.iter()
+                        .find(|(key, _)| *key == name)
+                        .map(|(_, value)| (*value).to_owned())
+                },
+                windows,
+            )
+        };
+        assert_eq!(detect(&[], false), TreeGlyphs::unicode());
+        assert_eq!(detect(&[("TERM", "dumb")], false), TreeGlyphs::ascii());
+        assert_eq!(detect(&[("LANG", "C")], false), TreeGlyphs::ascii());
+        assert_eq!(
+            detect(&[("LANG", "C"), ("LC_ALL", "en_US.UTF-8")], false),
+            TreeGlyphs::unicode()
+        );
+        assert_eq!(
+            detect(&[("LANG", "ja_JP.UTF-8")], false),
+            TreeGlyphs::unicode_light()
+        );
+        assert_eq!(detect(&[], true), TreeGlyphs::ascii());
+        assert_eq!(detect(&[("WT_SESSION", "1")], true), TreeGlyphs::unicode());
+    }
 }
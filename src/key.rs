@@ -0,0 +1,511 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{BitOr, BitOrAssign};
+use std::str::FromStr;
+
+/// A key without its modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TreeKeyCode {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Backspace,
+    Delete,
+    Insert,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+    /// A key the bindings do not distinguish, such as a media or lone modifier key.
+    Other,
+}
+
+/// Modifier keys held with a [`TreeKey`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TreeKeyModifiers(u8);
+
+impl TreeKeyModifiers {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(1);
+    pub const CONTROL: Self = Self(1 << 1);
+    pub const ALT: Self = Self(1 << 2);
+    pub const SUPER: Self = Self(1 << 3);
+    pub const HYPER: Self = Self(1 << 4);
+    pub const META: Self = Self(1 << 5);
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` when all modifiers of `other` are held.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for TreeKeyModifiers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for TreeKeyModifiers {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+/// Whether a key was pressed, held, or released. Terminals that only report presses use
+/// [`TreeKeyKind::Press`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TreeKeyKind {
+    #[default]
+    Press,
+    Repeat,
+    Release,
+}
+
+/// A terminal key event independent of the terminal library.
+///
+/// The `keymap`, `termion`, and `termwiz` features convert their libraries' key events into it,
+/// so [`TreeKeyBindings`](crate::TreeKeyBindings) and the `handle_key*` helpers accept them
+/// directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TreeKey {
+    pub code: TreeKeyCode,
+    pub modifiers: TreeKeyModifiers,
+    pub kind: TreeKeyKind,
+}
+
+impl TreeKey {
+    /// Creates a key press.
+    #[must_use]
+    pub const fn new(code: TreeKeyCode, modifiers: TreeKeyModifiers) -> Self {
+        Self {
+            code,
+            modifiers,
+            kind: TreeKeyKind::Press,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_kind(mut self, kind: TreeKeyKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+impl From<TreeKeyCode> for TreeKey {
+    fn from(code: TreeKeyCode) -> Self {
+        Self::new(code, TreeKeyModifiers::NONE)
+    }
+}
+
+/// An error produced while parsing a key spec such as `ctrl+shift+up`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeKeyParseError {
+    /// The spec has no key, such as `""` or `ctrl+`.
+    Empty,
+    /// A part before the key is not a modifier name such as `ctrl` or `shift`.
+    UnknownModifier(String),
+    /// The key is neither a single character nor a key name such as `enter` or `f5`.
+    UnknownKey(String),
+}
+
+impl Display for TreeKeyParseError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => formatter.write_str("key spec is empty"),
+            Self::UnknownModifier(modifier) => write!(formatter, "unknown modifier: {modifier}"),
+            Self::UnknownKey(key) => write!(formatter, "unknown key: {key}"),
+        }
+    }
+}
+
+impl Error for TreeKeyParseError {}
+
+impl FromStr for TreeKeyModifiers {
+    type Err = TreeKeyParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "shift" => Ok(Self::SHIFT),
+            "ctrl" | "control" => Ok(Self::CONTROL),
+            "alt" => Ok(Self::ALT),
+            "super" => Ok(Self::SUPER),
+            "hyper" => Ok(Self::HYPER),
+            "meta" => Ok(Self::META),
+            _ => Err(TreeKeyParseError::UnknownModifier(name.to_owned())),
+        }
+    }
+}
+
+impl FromStr for TreeKeyCode {
+    type Err = TreeKeyParseError;
+
+    /// Parses a single character or a key name such as `enter`, `pagedown`, `space`, or `f5`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let mut characters = name.chars();
+        if let (Some(character), None) = (characters.next(), characters.next()) {
+            return Ok(Self::Char(character));
+        }
+        let code = match name.to_ascii_lowercase().as_str() {
+            "enter" | "return" => Self::Enter,
+            "esc" | "escape" => Self::Esc,
+            "tab" => Self::Tab,
+            "backtab" => Self::BackTab,
+            "backspace" => Self::Backspace,
+            "delete" | "del" => Self::Delete,
+            "insert" | "ins" => Self::Insert,
+            "up" => Self::Up,
+            "down" => Self::Down,
+            "left" => Self::Left,
+            "right" => Self::Right,
+            "home" => Self::Home,
+            "end" => Self::End,
+            "pageup" | "pgup" => Self::PageUp,
+            "pagedown" | "pgdn" => Self::PageDown,
+            "space" => Self::Char(' '),
+            "plus" => Self::Char('+'),
+            lower => {
+                return lower
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse().ok())
+                    .map(Self::F)
+                    .ok_or_else(|| TreeKeyParseError::UnknownKey(name.to_owned()));
+            }
+        };
+        Ok(code)
+    }
+}
+
+impl FromStr for TreeKey {
+    type Err = TreeKeyParseError;
+
+    /// Parses modifiers and a key joined by `+`, such as `ctrl+shift+up` or `G`.
+    ///
+    /// Shifted letters are normalized to an uppercase character with
+    /// [`TreeKeyModifiers::SHIFT`], as terminals report them, so `shift+g` and `G` are the same
+    /// key. Write `plus` for the `+` key.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut parts = spec.trim().split('+').map(str::trim).rev();
+        let code = match parts.next() {
+            None | Some("") => return Err(TreeKeyParseError::Empty),
+            Some(code) => code.parse()?,
+        };
+        let mut modifiers = parts.try_fold(TreeKeyModifiers::NONE, |held, modifier| {
+            modifier.parse().map(|modifier| held | modifier)
+        })?;
+        let code = match code {
+            TreeKeyCode::Char(letter) if letter.is_alphabetic() => {
+                if letter.is_uppercase() {
+                    modifiers |= TreeKeyModifiers::SHIFT;
+                }
+                if modifiers.contains(TreeKeyModifiers::SHIFT) {
+                    TreeKeyCode::Char(letter.to_uppercase().next().unwrap_or(letter))
+                } else {
+                    code
+                }
+            }
+            _ => code,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+#[cfg(feature = "keymap")]
+mod crossterm_keys {
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    use super::{TreeKey, TreeKeyCode, TreeKeyKind, TreeKeyModifiers};
+
+    impl From<KeyCode> for TreeKeyCode {
+        fn from(code: KeyCode) -> Self {
+            match code {
+                KeyCode::Char(character) => Self::Char(character),
+                KeyCode::Enter => Self::Enter,
+                KeyCode::Esc => Self::Esc,
+                KeyCode::Tab => Self::Tab,
+                KeyCode::BackTab => Self::BackTab,
+                KeyCode::Backspace => Self::Backspace,
+                KeyCode::Delete => Self::Delete,
+                KeyCode::Insert => Self::Insert,
+                KeyCode::Up => Self::Up,
+                KeyCode::Down => Self::Down,
+                KeyCode::Left => Self::Left,
+                KeyCode::Right => Self::Right,
+                KeyCode::Home => Self::Home,
+                KeyCode::End => Self::End,
+                KeyCode::PageUp => Self::PageUp,
+                KeyCode::PageDown => Self::PageDown,
+                KeyCode::F(number) => Self::F(number),
+                _ => Self::Other,
+            }
+        }
+    }
+
+    impl From<KeyModifiers> for TreeKeyModifiers {
+        fn from(modifiers: KeyModifiers) -> Self {
+            [
+                (KeyModifiers::SHIFT, Self::SHIFT),
+                (KeyModifiers::CONTROL, Self::CONTROL),
+                (KeyModifiers::ALT, Self::ALT),
+                (KeyModifiers::SUPER, Self::SUPER),
+                (KeyModifiers::HYPER, Self::HYPER),
+                (KeyModifiers::META, Self::META),
+            ]
+            .into_iter()
+            .filter(|(modifier, _)| modifiers.contains(*modifier))
+            .fold(Self::NONE, |held, (_, modifier)| held | modifier)
+        }
+    }
+
+    impl From<KeyEvent> for TreeKey {
+        fn from(event: KeyEvent) -> Self {
+            let kind = match event.kind {
+                KeyEventKind::Press => TreeKeyKind::Press,
+                KeyEventKind::Repeat => TreeKeyKind::Repeat,
+                KeyEventKind::Release => TreeKeyKind::Release,
+            };
+            Self::new(event.code.into(), event.modifiers.into()).with_kind(kind)
+        }
+    }
+}
+
+#[cfg(feature = "termion")]
+impl From<termion::event::Key> for TreeKey {
+    fn from(key: termion::event::Key) -> Self {
+        use termion::event::Key;
+
+        let (code, modifiers) = match key {
+            Key::Char('\n' | '\r') => (TreeKeyCode::Enter, TreeKeyModifiers::NONE),
+            Key::Char('\t') => (TreeKeyCode::Tab, TreeKeyModifiers::NONE),
+            Key::Char(character) => (TreeKeyCode::Char(character), TreeKeyModifiers::NONE),
+            Key::Alt(character) => (TreeKeyCode::Char(character), TreeKeyModifiers::ALT),
+            Key::Ctrl(character) => (TreeKeyCode::Char(character), TreeKeyModifiers::CONTROL),
+            Key::Esc => (TreeKeyCode::Esc, TreeKeyModifiers::NONE),
+            Key::BackTab => (TreeKeyCode::BackTab, TreeKeyModifiers::SHIFT),
+            Key::Backspace => (TreeKeyCode::Backspace, TreeKeyModifiers::NONE),
+            Key::Delete => (TreeKeyCode::Delete, TreeKeyModifiers::NONE),
+            Key::Insert => (TreeKeyCode::Insert, TreeKeyModifiers::NONE),
+            Key::Up => (TreeKeyCode::Up, TreeKeyModifiers::NONE),
+            Key::ShiftUp => (TreeKeyCode::Up, TreeKeyModifiers::SHIFT),
+            Key::AltUp => (TreeKeyCode::Up, TreeKeyModifiers::ALT),
+            Key::CtrlUp => (TreeKeyCode::Up, TreeKeyModifiers::CONTROL),
+            Key::Down => (TreeKeyCode::Down, TreeKeyModifiers::NONE),
+            Key::ShiftDown => (TreeKeyCode::Down, TreeKeyModifiers::SHIFT),
+            Key::AltDown => (TreeKeyCode::Down, TreeKeyModifiers::ALT),
+            Key::CtrlDown => (TreeKeyCode::Down, TreeKeyModifiers::CONTROL),
+            Key::Left => (TreeKeyCode::Left, TreeKeyModifiers::NONE),
+            Key::ShiftLeft => (TreeKeyCode::Left, TreeKeyModifiers::SHIFT),
+            Key::AltLeft => (TreeKeyCode::Left, TreeKeyModifiers::ALT),
+            Key::CtrlLeft => (TreeKeyCode::Left, TreeKeyModifiers::CONTROL),
+            Key::Right => (TreeKeyCode::Right, TreeKeyModifiers::NONE),
+            Key::ShiftRight => (TreeKeyCode::Right, TreeKeyModifiers::SHIFT),
+            Key::AltRight => (TreeKeyCode::Right, TreeKeyModifiers::ALT),
+            Key::CtrlRight => (TreeKeyCode::Right, TreeKeyModifiers::CONTROL),
+            Key::Home => (TreeKeyCode::Home, TreeKeyModifiers::NONE),
+            Key::CtrlHome => (TreeKeyCode::Home, TreeKeyModifiers::CONTROL),
+            Key::End => (TreeKeyCode::End, TreeKeyModifiers::NONE),
+            Key::CtrlEnd => (TreeKeyCode::End, TreeKeyModifiers::CONTROL),
+            Key::PageUp => (TreeKeyCode::PageUp, TreeKeyModifiers::NONE),
+            Key::PageDown => (TreeKeyCode::PageDown, TreeKeyModifiers::NONE),
+            Key::F(number) => (TreeKeyCode::F(number), TreeKeyModifiers::NONE),
+            _ => (TreeKeyCode::Other, TreeKeyModifiers::NONE),
+        };
+        Self::new(code, modifiers)
+    }
+}
+
+#[cfg(feature = "termwiz")]
+impl From<termwiz::input::KeyEvent> for TreeKey {
+    fn from(event: termwiz::input::KeyEvent) -> Self {
+        use termwiz::input::{KeyCode, Modifiers};
+
+        let code = match event.key {
+            KeyCode::Char('\n' | '\r') | KeyCode::Enter => TreeKeyCode::Enter,
+            KeyCode::Char('\t') | KeyCode::Tab => TreeKeyCode::Tab,
+            KeyCode::Char(character) => TreeKeyCode::Char(character),
+            KeyCode::Escape => TreeKeyCode::Esc,
+            KeyCode::Backspace => TreeKeyCode::Backspace,
+            KeyCode::Delete => TreeKeyCode::Delete,
+            KeyCode::Insert => TreeKeyCode::Insert,
+            KeyCode::UpArrow | KeyCode::ApplicationUpArrow => TreeKeyCode::Up,
+            KeyCode::DownArrow | KeyCode::ApplicationDownArrow => TreeKeyCode::Down,
+            KeyCode::LeftArrow | KeyCode::ApplicationLeftArrow => TreeKeyCode::Left,
+            KeyCode::RightArrow | KeyCode::ApplicationRightArrow => TreeKeyCode::Right,
+            KeyCode::Home | KeyCode::KeyPadHome => TreeKeyCode::Home,
+            KeyCode::End | KeyCode::KeyPadEnd => TreeKeyCode::End,
+            KeyCode::PageUp | KeyCode::KeyPadPageUp => TreeKeyCode::PageUp,
+            KeyCode::PageDown | KeyCode::KeyPadPageDown => TreeKeyCode::PageDown,
+            KeyCode::Function(number) => TreeKeyCode::F(number),
+            _ => TreeKeyCode::Other,
+        };
+        let modifiers = [
+            (Modifiers::SHIFT, TreeKeyModifiers::SHIFT),
+            (Modifiers::CTRL, TreeKeyModifiers::CONTROL),
+            (Modifiers::ALT, TreeKeyModifiers::ALT),
+            (Modifiers::SUPER, TreeKeyModifiers::SUPER),
+        ]
+        .into_iter()
+        .filter(|(modifier, _)| event.modifiers.contains(*modifier))
+        .fold(TreeKeyModifiers::NONE, |held, (_, modifier)| {
+            held | modifier
+        });
+        // Terminals report Shift+Tab as a shifted tab rather than a distinct key.
+        let code = match code {
+            TreeKeyCode::Tab if modifiers.contains(TreeKeyModifiers::SHIFT) => TreeKeyCode::BackTab,
+            code => code,
+        };
+        Self::new(code, modifiers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_combine_and_compare_as_sets() {
+        let held = TreeKeyModifiers::SHIFT | TreeKeyModifiers::CONTROL;
+        assert!(held.contains(TreeKeyModifiers::SHIFT));
+        assert!(!held.contains(TreeKeyModifiers::ALT));
+        assert!(TreeKeyModifiers::NONE.is_empty());
+        assert_eq!(
+            TreeKey::from(TreeKeyCode::Enter),
+            TreeKey::new(TreeKeyCode::Enter, TreeKeyModifiers::NONE)
+        );
+    }
+
+    #[cfg(feature = "keymap")]
+    #[test]
+    fn converts_crossterm_events() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+        let event = KeyEvent::new_with_kind(
+            KeyCode::Char('E'),
+            KeyModifiers::SHIFT | KeyModifiers::ALT,
+            KeyEventKind::Release,
+        );
+        assert_eq!(
+            TreeKey::from(event),
+            TreeKey::new(
+                TreeKeyCode::Char('E'),
+                TreeKeyModifiers::SHIFT | TreeKeyModifiers::ALT
+            )
+            .with_kind(TreeKeyKind::Release)
+        );
+    }
+
+    #[cfg(feature = "termion")]
+    #[test]
+    fn converts_termion_keys() {
+        use termion::event::Key;
+
+        assert_eq!(
+            TreeKey::from(Key::CtrlUp),
+            TreeKey::new(TreeKeyCode::Up, TreeKeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            TreeKey::from(Key::Alt('x')),
+            TreeKey::new(TreeKeyCode::Char('x'), TreeKeyModifiers::ALT)
+        );
+        assert_eq!(TreeKey::from(Key::Char('\n')), TreeKeyCode::Enter.into());
+        assert_eq!(
+            TreeKey::from(Key::BackTab),
+            TreeKey::new(TreeKeyCode::BackTab, TreeKeyModifiers::SHIFT)
+        );
+        assert_eq!(TreeKey::from(Key::Null), TreeKeyCode::Other.into());
+    }
+
+    #[cfg(feature = "termwiz")]
+    #[test]
+    fn converts_termwiz_events() {
+        use termwiz::input::{KeyCode, KeyEvent, Modifiers};
+
+        let event = |key, modifiers| TreeKey::from(KeyEvent { key, modifiers });
+        assert_eq!(
+            event(
+                KeyCode::ApplicationUpArrow,
+                Modifiers::CTRL | Modifiers::ALT
+            ),
+            TreeKey::new(
+                TreeKeyCode::Up,
+                TreeKeyModifiers::CONTROL | TreeKeyModifiers::ALT
+            )
+        );
+        assert_eq!(
+            event(KeyCode::Tab, Modifiers::SHIFT),
+            TreeKey::new(TreeKeyCode::BackTab, TreeKeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            event(KeyCode::Char('\r'), Modifiers::NONE),
+            TreeKeyCode::Enter.into()
+        );
+        assert_eq!(
+            event(KeyCode::Function(5), Modifiers::NONE),
+            TreeKeyCode::F(5).into()
+        );
+    }
+
+    #[test]
+    fn parses_key_codes_and_modifiers() {
+        assert_eq!("x".parse(), Ok(TreeKeyCode::Char('x')));
+        assert_eq!("PgDn".parse(), Ok(TreeKeyCode::PageDown));
+        assert_eq!("space".parse(), Ok(TreeKeyCode::Char(' ')));
+        assert_eq!("f12".parse(), Ok(TreeKeyCode::F(12)));
+        assert_eq!(
+            "fx".parse::<TreeKeyCode>(),
+            Err(TreeKeyParseError::UnknownKey("fx".to_owned()))
+        );
+        assert_eq!("Control".parse(), Ok(TreeKeyModifiers::CONTROL));
+        assert_eq!(
+            "cmd".parse::<TreeKeyModifiers>(),
+            Err(TreeKeyParseError::UnknownModifier("cmd".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parses_key_specs() {
+        assert_eq!(
+            "ctrl + shift + up".parse(),
+            Ok(TreeKey::new(
+                TreeKeyCode::Up,
+                TreeKeyModifiers::CONTROL | TreeKeyModifiers::SHIFT
+            ))
+        );
+        let shifted = TreeKey::new(TreeKeyCode::Char('G'), TreeKeyModifiers::SHIFT);
+        assert_eq!("G".parse(), Ok(shifted));
+        assert_eq!("shift+g".parse(), Ok(shifted));
+        assert_eq!(
+            "alt+plus".parse(),
+            Ok(TreeKey::new(TreeKeyCode::Char('+'), TreeKeyModifiers::ALT))
+        );
+        assert_eq!("".parse::<TreeKey>(), Err(TreeKeyParseError::Empty));
+        assert_eq!("ctrl+".parse::<TreeKey>(), Err(TreeKeyParseError::Empty));
+        assert_eq!(
+            "hold+x".parse::<TreeKey>(),
+            Err(TreeKeyParseError::UnknownModifier("hold".to_owned()))
+        );
+        assert_eq!(
+            "ctrl+nope".parse::<TreeKey>(),
+            Err(TreeKeyParseError::UnknownKey("nope".to_owned()))
+        );
+    }
+}
@@ -1,9 +1,59 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::de::{Deserialize, Deserializer, Error as _};
+
+#[cfg(feature = "serde")]
+use crate::key::TreeKeyParseError;
+
+use crate::key::{TreeKey, TreeKeyCode, TreeKeyKind, TreeKeyModifiers};
 
 use crate::action::{TreeAction, TreeEditAction, TreeViewAction};
+use crate::model::TreeRevision;
+
+/// The pause after which a half-typed chord is discarded.
+const CHORD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Chords bound by [`TreeKeyBindings::bind_vim_chords`], with shifted letters also matched
+/// without [`TreeKeyModifiers::SHIFT`] as the single-key bindings do.
+const VIM_CHORDS: [([TreeKey; 2], TreeViewAction); 5] = {
+    const fn key(character: char, modifiers: TreeKeyModifiers) -> TreeKey {
+        TreeKey::new(TreeKeyCode::Char(character), modifiers)
+    }
+    const NONE: TreeKeyModifiers = TreeKeyModifiers::NONE;
+    const SHIFT: TreeKeyModifiers = TreeKeyModifiers::SHIFT;
+    [
+        (
+            [key('g', NONE), key('g', NONE)],
+            TreeViewAction::SelectFirst,
+        ),
+        ([key('z', NONE), key('R', SHIFT)], TreeViewAction::ExpandAll),
+        ([key('z', NONE), key('R', NONE)], TreeViewAction::ExpandAll),
+        (
+            [key('z', NONE), key('M', SHIFT)],
+            TreeViewAction::CollapseAll,
+        ),
+        (
+            [key('z', NONE), key('M', NONE)],
+            TreeViewAction::CollapseAll,
+        ),
+    ]
+};
+
+/// How the keys typed so far relate to the bound chords.
+enum ChordMatch<A> {
+    Unmatched,
+    Prefix,
+    Complete(Option<TreeAction<A>>),
+}
 
 /// A key profile for vertical and hierarchical navigation.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum KeymapProfile {
     #[default]
     Default,
@@ -11,23 +61,557 @@ pub enum KeymapProfile {
     Arrows,
 }
 
-/// A stateless key resolver stored with view state for convenient profile switching.
-#[derive(Clone, Copy, Debug)]
-pub struct TreeKeyBindings {
+/// A set of bindings pushed over [`TreeKeyBindings`] for a temporary mode such as renaming.
+///
+/// Keys the layer does not bind fall through to the layers below it, unless the layer is
+/// [capturing](Self::capturing).
+#[derive(Clone, Debug)]
+pub struct TreeKeyLayer<C = ()> {
+    bindings: Vec<(TreeKeyCode, TreeKeyModifiers, TreeAction<C>)>,
+    captures: bool,
+}
+
+impl<C> TreeKeyLayer<C> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            captures: false,
+        }
+    }
+
+    /// Makes keys without a binding in this layer resolve to nothing instead of falling through.
+    #[must_use]
+    pub const fn capturing(mut self) -> Self {
+        self.captures = true;
+        self
+    }
+
+    /// Adds a binding, replacing an earlier one for the same key.
+    #[must_use]
+    pub fn with_binding(
+        mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+        action: impl Into<TreeAction<C>>,
+    ) -> Self {
+        self.bind(code, modifiers, action);
+        self
+    }
+
+    /// Binds a key within the layer and returns the action previously bound to it.
+    pub fn bind(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+        action: impl Into<TreeAction<C>>,
+    ) -> Option<TreeAction<C>> {
+        let (code, modifiers, action) = (code.into(), modifiers.into(), action.into());
+        if let Some(binding) = self
+            .bindings
+            .iter_mut()
+            .find(|binding| binding.0 == code && binding.1 == modifiers)
+        {
+            return Some(std::mem::replace(&mut binding.2, action));
+        }
+        self.bindings.push((code, modifiers, action));
+        None
+    }
+
+    /// Removes a binding from the layer and returns its action.
+    pub fn unbind(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+    ) -> Option<TreeAction<C>> {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        let index = self
+            .bindings
+            .iter()
+            .position(|binding| binding.0 == code && binding.1 == modifiers)?;
+        Some(self.bindings.remove(index).2)
+    }
+
+    #[must_use]
+    pub const fn captures(&self) -> bool {
+        self.captures
+    }
+
+    /// Returns the action bound to `key` in this layer.
+    #[must_use]
+    pub fn action(&self, key: impl Into<TreeKey>) -> Option<&TreeAction<C>> {
+        let key = key.into();
+        self.bindings
+            .iter()
+            .find(|binding| binding.0 == key.code && binding.1 == key.modifiers)
+            .map(|binding| &binding.2)
+    }
+}
+
+impl<C> Default for TreeKeyLayer<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A key resolver stored with view state for convenient profile switching.
+///
+/// Besides the stateless [`Self::resolve`], it accumulates Vim-style numeric prefixes and
+/// multi-key chords such as `gg` through [`Self::resolve_counted_with`]. Application actions of
+/// type `C` can be bound with [`Self::bind_custom`] and are resolved by [`Self::resolve_bound`].
+///
+/// [`Self::resolve_bound`] looks keys up in layers: pushed [`TreeKeyLayer`]s from the most
+/// recent down, then keys remapped with [`Self::bind`] or [`Self::unbind`], then the application
+/// bindings, then the profile's navigation keys, and finally the keys shared by all profiles.
+/// The first layer that binds a key wins.
+///
+/// The bindings own their remapped keys, chords, and layers, so unlike earlier versions they are
+/// `Clone` but not `Copy`; clone them where a copy used to be implicit.
+#[derive(Clone, Debug)]
+pub struct TreeKeyBindings<C = ()> {
     profile: KeymapProfile,
+    pending_count: Option<usize>,
+    pending_chord: Vec<TreeKey>,
+    pending_since: Option<Instant>,
+    chord_revision: TreeRevision,
+    chord_timeout: Option<Duration>,
+    chords: Vec<(Vec<TreeKey>, Option<TreeAction<C>>)>,
+    remapped: Vec<(TreeKeyCode, TreeKeyModifiers, Option<TreeAction<C>>)>,
+    custom: Vec<(TreeKeyCode, TreeKeyModifiers, C)>,
+    layers: Vec<TreeKeyLayer<C>>,
 }
 
 impl TreeKeyBindings {
     #[must_use]
     pub const fn new() -> Self {
+        Self::with_profile(KeymapProfile::Default)
+    }
+
+    #[must_use]
+    pub const fn with_profile(profile: KeymapProfile) -> Self {
+        Self::custom_with_profile(profile)
+    }
+}
+
+impl<C> TreeKeyBindings<C> {
+    /// Creates bindings that accept application actions of type `C`.
+    #[must_use]
+    pub const fn custom_with_profile(profile: KeymapProfile) -> Self {
         Self {
-            profile: KeymapProfile::Default,
+            profile,
+            pending_count: None,
+            pending_chord: Vec::new(),
+            pending_since: None,
+            chord_revision: TreeRevision::INITIAL,
+            chord_timeout: Some(CHORD_TIMEOUT),
+            chords: Vec::new(),
+            remapped: Vec::new(),
+            custom: Vec::new(),
+            layers: Vec::new(),
         }
     }
 
+    /// Pushes a layer that takes precedence over all bindings below it.
+    pub fn push_layer(&mut self, layer: TreeKeyLayer<C>) {
+        self.layers.push(layer);
+    }
+
+    /// Removes the most recently pushed layer.
+    pub fn pop_layer(&mut self) -> Option<TreeKeyLayer<C>> {
+        self.layers.pop()
+    }
+
+    /// Returns the pushed layers, from the bottom up.
     #[must_use]
-    pub const fn with_profile(profile: KeymapProfile) -> Self {
-        Self { profile }
+    pub fn layers(&self) -> &[TreeKeyLayer<C>] {
+        &self.layers
+    }
+
+    /// Breaks with the action of the topmost layer that binds or captures `key`, treating the
+    /// remapped keys as the bottom layer.
+    fn layer_action(&self, key: TreeKey) -> ControlFlow<Option<&TreeAction<C>>> {
+        for layer in self.layers.iter().rev() {
+            if let Some(action) = layer.action(key) {
+                return ControlFlow::Break(Some(action));
+            }
+            if layer.captures {
+                return ControlFlow::Break(None);
+            }
+        }
+        self.remapped(key)
+            .map_or(ControlFlow::Continue(()), |action| {
+                ControlFlow::Break(action.as_ref())
+            })
+    }
+
+    fn remapped(&self, key: TreeKey) -> Option<&Option<TreeAction<C>>> {
+        self.remapped
+            .iter()
+            .find(|binding| binding.0 == key.code && binding.1 == key.modifiers)
+            .map(|binding| &binding.2)
+    }
+
+    /// Sets the remapping of a key, replacing its application binding.
+    fn remap(
+        &mut self,
+        code: TreeKeyCode,
+        modifiers: TreeKeyModifiers,
+        action: Option<TreeAction<C>>,
+    ) {
+        self.custom
+            .retain(|binding| binding.0 != code || binding.1 != modifiers);
+        match self
+            .remapped
+            .iter_mut()
+            .find(|binding| binding.0 == code && binding.1 == modifiers)
+        {
+            Some(binding) => binding.2 = action,
+            None => self.remapped.push((code, modifiers, action)),
+        }
+    }
+
+    /// Binds a key to any action, replacing its built-in or application binding, so an
+    /// application can remap keys such as swapping `Delete` and `d`.
+    ///
+    /// Modifiers must match exactly, as in [`Self::bind_custom`]. Returns the action the key
+    /// resolved to before.
+    pub fn bind(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+        action: impl Into<TreeAction<C>>,
+    ) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        let previous = self.binding(TreeKey::new(code, modifiers));
+        self.remap(code, modifiers, Some(action.into()));
+        previous
+    }
+
+    /// Makes a key resolve to nothing below the pushed layers, including its built-in binding,
+    /// and returns the action it resolved to before.
+    pub fn unbind(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+    ) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        let previous = self.binding(TreeKey::new(code, modifiers));
+        self.remap(code, modifiers, None);
+        previous
+    }
+
+    /// Restores the built-in binding of a key changed with [`Self::bind`] or [`Self::unbind`].
+    ///
+    /// Returns `false` when the key was not remapped.
+    pub fn reset_binding(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+    ) -> bool {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        let count = self.remapped.len();
+        self.remapped
+            .retain(|binding| binding.0 != code || binding.1 != modifiers);
+        self.remapped.len() != count
+    }
+
+    /// Returns the action a press of `key` resolves to, ignoring pushed layers.
+    #[must_use]
+    pub fn binding(&self, key: impl Into<TreeKey>) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        self.remapped(key).map_or_else(
+            || {
+                self.custom_action(key)
+                    .cloned()
+                    .map(TreeAction::Custom)
+                    .or_else(|| self.resolve(key))
+            },
+            Clone::clone,
+        )
+    }
+
+    /// Binds a sequence of keys, such as `g` followed by `g`, to an action.
+    ///
+    /// The counted resolvers hold back a key that starts a chord until the next key arrives, so
+    /// the key's own binding no longer fires. Returns the action the chord resolved to before.
+    pub fn bind_chord<K: Into<TreeKey>>(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+        action: impl Into<TreeAction<C>>,
+    ) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        self.set_chord(keys, Some(action.into()))
+    }
+
+    /// Makes a chord resolve to nothing and returns the action it resolved to before.
+    pub fn unbind_chord<K: Into<TreeKey>>(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        self.set_chord(keys, None)
+    }
+
+    /// Binds Vim's `gg` to [`SelectFirst`](TreeViewAction::SelectFirst), and `zR` and `zM` to
+    /// [`ExpandAll`](TreeViewAction::ExpandAll) and [`CollapseAll`](TreeViewAction::CollapseAll).
+    ///
+    /// Chords are opt-in because `g` and `z` then wait for their next key, so the single-key
+    /// binding of `g`, [`ToggleGuides`](TreeViewAction::ToggleGuides), no longer fires.
+    pub fn bind_vim_chords(&mut self)
+    where
+        C: Clone,
+    {
+        for (keys, action) in VIM_CHORDS {
+            self.set_chord(keys, Some(action.into()));
+        }
+    }
+
+    fn set_chord<K: Into<TreeKey>>(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+        action: Option<TreeAction<C>>,
+    ) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let keys: Vec<TreeKey> = keys
+            .into_iter()
+            .map(|key| key.into().with_kind(TreeKeyKind::Press))
+            .collect();
+        let previous = self.chord(&keys);
+        match self.chords.iter_mut().find(|chord| chord.0 == keys) {
+            Some(chord) => chord.1 = action,
+            None => self.chords.push((keys, action)),
+        }
+        previous
+    }
+
+    /// Returns the action a complete chord resolves to.
+    #[must_use]
+    pub fn chord(&self, keys: &[TreeKey]) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let keys: Vec<TreeKey> = keys
+            .iter()
+            .map(|key| key.with_kind(TreeKeyKind::Press))
+            .collect();
+        match self.chord_match(&keys, |action| Some(action.clone())) {
+            ChordMatch::Complete(action) => action,
+            ChordMatch::Unmatched | ChordMatch::Prefix => None,
+        }
+    }
+
+    /// Looks up the press keys typed so far in the bound chords.
+    fn chord_match<A>(
+        &self,
+        keys: &[TreeKey],
+        convert: impl Fn(&TreeAction<C>) -> Option<TreeAction<A>>,
+    ) -> ChordMatch<A> {
+        let mut prefix = false;
+        for (chord, action) in &self.chords {
+            if chord.as_slice() == keys {
+                return action.as_ref().map_or(ChordMatch::Unmatched, |action| {
+                    ChordMatch::Complete(convert(action))
+                });
+            }
+            prefix |= action.is_some() && chord.starts_with(keys);
+        }
+        if prefix {
+            ChordMatch::Prefix
+        } else {
+            ChordMatch::Unmatched
+        }
+    }
+
+    /// Adds `key` to the pending chord, starting over from `key` when it does not continue it.
+    fn advance_chord<A>(
+        &mut self,
+        key: TreeKey,
+        now: Instant,
+        convert: impl Fn(&TreeAction<C>) -> Option<TreeAction<A>>,
+    ) -> ChordMatch<A> {
+        self.expire_pending_chord(now);
+        let key = key.with_kind(TreeKeyKind::Press);
+        let continued = !self.pending_chord.is_empty();
+        self.pending_chord.push(key);
+        let mut step = self.chord_match(&self.pending_chord, &convert);
+        if continued && matches!(step, ChordMatch::Unmatched) {
+            self.pending_chord.clear();
+            self.pending_chord.push(key);
+            step = self.chord_match(&self.pending_chord, &convert);
+        }
+        if matches!(step, ChordMatch::Prefix) {
+            self.pending_since = Some(now);
+            self.chord_revision.advance();
+        } else if continued {
+            self.clear_pending_chord();
+        } else {
+            self.pending_chord.clear();
+        }
+        step
+    }
+
+    /// Returns the keys typed so far of a chord such as `gg`, for status display.
+    #[must_use]
+    pub fn pending_chord(&self) -> &[TreeKey] {
+        &self.pending_chord
+    }
+
+    /// Discards the keys typed so far of a chord.
+    pub fn clear_pending_chord(&mut self) -> bool {
+        self.pending_since = None;
+        if self.pending_chord.is_empty() {
+            return false;
+        }
+        self.pending_chord.clear();
+        self.chord_revision.advance();
+        true
+    }
+
+    pub(crate) const fn chord_revision(&self) -> TreeRevision {
+        self.chord_revision
+    }
+
+    /// Returns the pause after which a half-typed chord is discarded, one second by default.
+    #[must_use]
+    pub const fn chord_timeout(&self) -> Option<Duration> {
+        self.chord_timeout
+    }
+
+    /// Sets the pause after which a half-typed chord is discarded; `None` waits indefinitely.
+    pub const fn set_chord_timeout(&mut self, timeout: Option<Duration>) {
+        self.chord_timeout = timeout;
+    }
+
+    /// Discards a half-typed chord whose last key arrived longer than the timeout before `now`.
+    ///
+    /// The counted resolvers check the timeout as the next key arrives; applications that show
+    /// [`Self::pending_chord`] call this from their tick to clear it sooner.
+    pub fn expire_pending_chord(&mut self, now: Instant) -> bool {
+        let expired = self.chord_timeout.is_some_and(|timeout| {
+            self.pending_since
+                .is_some_and(|since| now.saturating_duration_since(since) > timeout)
+        });
+        expired && self.clear_pending_chord()
+    }
+
+    /// Binds a key to an application action, taking precedence over built-in bindings.
+    ///
+    /// Modifiers must match exactly, so a shifted letter is bound as its uppercase character with
+    /// [`TreeKeyModifiers::SHIFT`]. Replaces a remapping of the key made with [`Self::bind`] or
+    /// [`Self::unbind`], and returns the application action previously bound to it.
+    pub fn bind_custom(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+        action: C,
+    ) -> Option<C> {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        self.remapped
+            .retain(|binding| binding.0 != code || binding.1 != modifiers);
+        if let Some(binding) = self
+            .custom
+            .iter_mut()
+            .find(|binding| binding.0 == code && binding.1 == modifiers)
+        {
+            return Some(std::mem::replace(&mut binding.2, action));
+        }
+        self.custom.push((code, modifiers, action));
+        None
+    }
+
+    /// Removes an application binding and returns its action.
+    pub fn unbind_custom(
+        &mut self,
+        code: impl Into<TreeKeyCode>,
+        modifiers: impl Into<TreeKeyModifiers>,
+    ) -> Option<C> {
+        let (code, modifiers) = (code.into(), modifiers.into());
+        let index = self
+            .custom
+            .iter()
+            .position(|binding| binding.0 == code && binding.1 == modifiers)?;
+        Some(self.custom.remove(index).2)
+    }
+
+    /// Returns the application action bound to a press or repeat of `key`.
+    #[must_use]
+    pub fn custom_action(&self, key: impl Into<TreeKey>) -> Option<&C> {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        self.custom
+            .iter()
+            .find(|binding| binding.0 == key.code && binding.1 == key.modifiers)
+            .map(|binding| &binding.2)
+    }
+
+    /// Resolves pushed layers, remapped keys, and application bindings first, then the built-in
+    /// ones.
+    #[must_use]
+    pub fn resolve_bound(&self, key: impl Into<TreeKey>) -> Option<TreeAction<C>>
+    where
+        C: Clone,
+    {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        match self.layer_action(key) {
+            ControlFlow::Break(action) => action.cloned(),
+            ControlFlow::Continue(()) => self
+                .custom_action(key)
+                .cloned()
+                .map(TreeAction::Custom)
+                .or_else(|| self.resolve(key)),
+        }
+    }
+
+    /// A version of [`Self::resolve_counted_with`] that uses the layers and application bindings.
+    ///
+    /// Digits and `Esc` bound or captured by a pushed layer resolve through it instead of
+    /// editing the numeric prefix.
+    pub fn resolve_bound_counted(
+        &mut self,
+        key: impl Into<TreeKey>,
+    ) -> Option<(TreeAction<C>, Option<usize>)>
+    where
+        C: Clone,
+    {
+        self.resolve_bound_counted_at(key, Instant::now())
+    }
+
+    /// A version of [`Self::resolve_bound_counted`] that measures the
+    /// [chord timeout](Self::chord_timeout) from `now` instead of the system clock.
+    pub fn resolve_bound_counted_at(
+        &mut self,
+        key: impl Into<TreeKey>,
+        now: Instant,
+    ) -> Option<(TreeAction<C>, Option<usize>)>
+    where
+        C: Clone,
+    {
+        self.resolve_counted_by(key.into(), now, Self::resolve_bound, |action| {
+            Some(action.clone())
+        })
     }
 
     #[must_use]
@@ -39,29 +623,36 @@ impl TreeKeyBindings {
         self.profile = profile;
     }
 
-    /// Resolves only press/repeat events and handles modifiers explicitly.
+    /// Resolves the built-in bindings for press/repeat events and handles modifiers explicitly.
     #[must_use]
-    pub fn resolve<C>(&self, key: KeyEvent) -> Option<TreeAction<C>> {
-        if key.kind == KeyEventKind::Release {
+    pub fn resolve<A>(&self, key: impl Into<TreeKey>) -> Option<TreeAction<A>> {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
             return None;
         }
 
         match (key.code, key.modifiers) {
-            (KeyCode::Up, KeyModifiers::SHIFT) => {
+            (TreeKeyCode::Up, TreeKeyModifiers::SHIFT) => {
                 return Some(TreeEditAction::ReorderUp.into());
             }
-            (KeyCode::Down, KeyModifiers::SHIFT) => {
+            (TreeKeyCode::Down, TreeKeyModifiers::SHIFT) => {
                 return Some(TreeEditAction::ReorderDown.into());
             }
-            (KeyCode::Delete, KeyModifiers::SHIFT) => {
+            (TreeKeyCode::Delete, TreeKeyModifiers::SHIFT) => {
                 return Some(TreeEditAction::Delete.into());
             }
-            (KeyCode::Left, KeyModifiers::CONTROL) => {
+            (TreeKeyCode::Left, TreeKeyModifiers::CONTROL) => {
                 return Some(TreeViewAction::ScrollLeft.into());
             }
-            (KeyCode::Right, KeyModifiers::CONTROL) => {
+            (TreeKeyCode::Right, TreeKeyModifiers::CONTROL) => {
                 return Some(TreeViewAction::ScrollRight.into());
             }
+            (TreeKeyCode::Left, TreeKeyModifiers::ALT) => {
+                return Some(TreeViewAction::ScrollColumnsLeft.into());
+            }
+            (TreeKeyCode::Right, TreeKeyModifiers::ALT) => {
+                return Some(TreeViewAction::ScrollColumnsRight.into());
+            }
             _ => {}
         }
 
@@ -74,102 +665,264 @@ impl TreeKeyBindings {
         Self::common(key)
     }
 
+    /// Resolves pushed layers and remapped keys first, then `custom`, then the built-in bindings.
+    ///
+    /// Layer bindings and remappings to application actions resolve to nothing here, since
+    /// `custom` produces actions of a different type.
+    #[must_use]
+    pub fn resolve_with<A, F>(&self, key: impl Into<TreeKey>, custom: F) -> Option<TreeAction<A>>
+    where
+        F: Fn(TreeKey) -> Option<A>,
+    {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        match self.layer_action(key) {
+            ControlFlow::Break(action) => action.and_then(built_in),
+            ControlFlow::Continue(()) => custom(key)
+                .map(TreeAction::Custom)
+                .or_else(|| self.resolve(key)),
+        }
+    }
+
+    /// Returns the numeric prefix typed so far, for status display.
     #[must_use]
-    pub fn resolve_with<C, F>(&self, key: KeyEvent, custom: F) -> Option<TreeAction<C>>
+    pub const fn pending_count(&self) -> Option<usize> {
+        self.pending_count
+    }
+
+    /// Discards the numeric prefix typed so far.
+    pub const fn clear_pending_count(&mut self) -> bool {
+        self.pending_count.take().is_some()
+    }
+
+    /// Resolves a key while accumulating a numeric prefix such as the `12` in `12j`.
+    ///
+    /// Digits extend the prefix and `Esc` clears it; both return `None`. The next resolved
+    /// action is returned with the prefix, which is then cleared along with unbound keys.
+    ///
+    /// Keys that start a chord bound with [`Self::bind_chord`] or [`Self::bind_vim_chords`] also
+    /// return `None` until the chord completes. A key that does not continue the chord discards
+    /// it and resolves on its own. Chords bound to application actions resolve to nothing here.
+    pub fn resolve_counted_with<A, F>(
+        &mut self,
+        key: impl Into<TreeKey>,
+        custom: F,
+    ) -> Option<(TreeAction<A>, Option<usize>)>
     where
-        F: Fn(KeyEvent) -> Option<C>,
+        F: Fn(TreeKey) -> Option<A>,
     {
-        custom(key)
-            .map(TreeAction::Custom)
-            .or_else(|| self.resolve(key))
+        self.resolve_counted_by(
+            key.into(),
+            Instant::now(),
+            |bindings, key| bindings.resolve_with(key, custom),
+            built_in,
+        )
+    }
+
+    fn resolve_counted_by<A>(
+        &mut self,
+        key: TreeKey,
+        now: Instant,
+        resolve: impl FnOnce(&Self, TreeKey) -> Option<TreeAction<A>>,
+        chord: impl Fn(&TreeAction<C>) -> Option<TreeAction<A>>,
+    ) -> Option<(TreeAction<A>, Option<usize>)> {
+        if key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        let layered = self.layer_action(key).is_break();
+        if layered {
+            self.clear_pending_chord();
+        } else {
+            match self.advance_chord(key, now, chord) {
+                ChordMatch::Unmatched => {}
+                ChordMatch::Prefix => return None,
+                ChordMatch::Complete(action) => {
+                    let count = self.pending_count.take();
+                    return action.map(|action| (action, count));
+                }
+            }
+        }
+        if let (TreeKeyCode::Char(digit @ '0'..='9'), TreeKeyModifiers::NONE) =
+            (key.code, key.modifiers)
+            && (digit != '0' || self.pending_count.is_some())
+            && !layered
+        {
+            let digit = digit.to_digit(10).map_or(0, |digit| digit as usize);
+            let count = self.pending_count.unwrap_or(0);
+            self.pending_count = Some(count.saturating_mul(10).saturating_add(digit));
+            return None;
+        }
+        let count = self.pending_count.take();
+        if key.code == TreeKeyCode::Esc && !layered {
+            return None;
+        }
+        resolve(self, key).map(|action| (action, count))
     }
 
-    const fn navigation(profile: KeymapProfile, code: KeyCode) -> Option<TreeViewAction> {
+    const fn navigation(profile: KeymapProfile, code: TreeKeyCode) -> Option<TreeViewAction> {
         match (profile, code) {
-            (KeymapProfile::Default, KeyCode::Up | KeyCode::Char('k'))
-            | (KeymapProfile::Vim, KeyCode::Char('k'))
-            | (KeymapProfile::Arrows, KeyCode::Up) => Some(TreeViewAction::SelectPrev),
-            (KeymapProfile::Default, KeyCode::Down | KeyCode::Char('j'))
-            | (KeymapProfile::Vim, KeyCode::Char('j'))
-            | (KeymapProfile::Arrows, KeyCode::Down) => Some(TreeViewAction::SelectNext),
-            (KeymapProfile::Default, KeyCode::Left | KeyCode::Char('h'))
-            | (KeymapProfile::Vim, KeyCode::Char('h'))
-            | (KeymapProfile::Arrows, KeyCode::Left) => {
+            (KeymapProfile::Default, TreeKeyCode::Up | TreeKeyCode::Char('k'))
+            | (KeymapProfile::Vim, TreeKeyCode::Char('k'))
+            | (KeymapProfile::Arrows, TreeKeyCode::Up) => Some(TreeViewAction::SelectPrev),
+            (KeymapProfile::Default, TreeKeyCode::Down | TreeKeyCode::Char('j'))
+            | (KeymapProfile::Vim, TreeKeyCode::Char('j'))
+            | (KeymapProfile::Arrows, TreeKeyCode::Down) => Some(TreeViewAction::SelectNext),
+            (KeymapProfile::Default, TreeKeyCode::Left | TreeKeyCode::Char('h'))
+            | (KeymapProfile::Vim, TreeKeyCode::Char('h'))
+            | (KeymapProfile::Arrows, TreeKeyCode::Left) => {
                 Some(TreeViewAction::CollapseOrSelectParent)
             }
-            (KeymapProfile::Default, KeyCode::Right | KeyCode::Char('l'))
-            | (KeymapProfile::Vim, KeyCode::Char('l'))
-            | (KeymapProfile::Arrows, KeyCode::Right) => {
+            (KeymapProfile::Default, TreeKeyCode::Right | TreeKeyCode::Char('l'))
+            | (KeymapProfile::Vim, TreeKeyCode::Char('l'))
+            | (KeymapProfile::Arrows, TreeKeyCode::Right) => {
                 Some(TreeViewAction::ExpandOrSelectFirstChild)
             }
             _ => None,
         }
     }
 
-    const fn common<C>(key: KeyEvent) -> Option<TreeAction<C>> {
+    const fn common<A>(key: TreeKey) -> Option<TreeAction<A>> {
         match (key.code, key.modifiers) {
-            (KeyCode::Char(' '), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char(' '), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::ToggleRecursive))
             }
-            (KeyCode::Enter, KeyModifiers::NONE) => {
+            (TreeKeyCode::Enter, TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::ToggleNode))
             }
-            (KeyCode::Char('E'), KeyModifiers::SHIFT | KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('E'), TreeKeyModifiers::SHIFT | TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::ExpandAll))
             }
-            (KeyCode::Char('C'), KeyModifiers::SHIFT | KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('C'), TreeKeyModifiers::SHIFT | TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::CollapseAll))
             }
-            (KeyCode::Char('a' | '+'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('Z'), TreeKeyModifiers::SHIFT | TreeKeyModifiers::NONE) => {
+                Some(TreeAction::View(TreeViewAction::CollapseToSelection))
+            }
+            (TreeKeyCode::Char('a' | '+'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::AddChild))
             }
-            (KeyCode::Char('e'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('e'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::Rename))
             }
-            (KeyCode::Delete | KeyCode::Char('d'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Delete | TreeKeyCode::Char('d'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::Detach))
             }
-            (KeyCode::Char('D'), KeyModifiers::SHIFT | KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('D'), TreeKeyModifiers::SHIFT | TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::Delete))
             }
-            (KeyCode::Char('y'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('y'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::Yank))
             }
-            (KeyCode::Char('p'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('p'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::Edit(TreeEditAction::Paste))
             }
-            (KeyCode::Char('g'), KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('g'), TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::ToggleGuides))
             }
-            (KeyCode::Char('m' | 'M'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+            (TreeKeyCode::Char('m' | 'M'), TreeKeyModifiers::NONE | TreeKeyModifiers::SHIFT) => {
                 Some(TreeAction::View(TreeViewAction::ToggleMark))
             }
-            (KeyCode::Home, KeyModifiers::NONE) => {
+            (TreeKeyCode::Char('i'), TreeKeyModifiers::NONE) => {
+                Some(TreeAction::View(TreeViewAction::ShowDetails))
+            }
+            (TreeKeyCode::Char('>'), TreeKeyModifiers::NONE | TreeKeyModifiers::SHIFT) => {
+                Some(TreeAction::View(TreeViewAction::GrowColumn))
+            }
+            (TreeKeyCode::Char('<'), TreeKeyModifiers::NONE | TreeKeyModifiers::SHIFT) => {
+                Some(TreeAction::View(TreeViewAction::ShrinkColumn))
+            }
+            (TreeKeyCode::Home, TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::SelectFirst))
             }
-            (KeyCode::End, KeyModifiers::NONE) => {
+            (TreeKeyCode::End, TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::SelectLast))
             }
-            (KeyCode::Tab, KeyModifiers::NONE) => {
+            (TreeKeyCode::Tab, TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::SelectColumnRight))
             }
-            (KeyCode::BackTab, KeyModifiers::SHIFT | KeyModifiers::NONE) => {
+            (TreeKeyCode::BackTab, TreeKeyModifiers::SHIFT | TreeKeyModifiers::NONE) => {
                 Some(TreeAction::View(TreeViewAction::SelectColumnLeft))
             }
-            (KeyCode::PageUp, KeyModifiers::NONE) => {
-                Some(TreeAction::View(TreeViewAction::ScrollViewUp))
+            (TreeKeyCode::PageUp, TreeKeyModifiers::NONE) => {
+                Some(TreeAction::View(TreeViewAction::PageUp))
             }
-            (KeyCode::PageDown, KeyModifiers::NONE) => {
-                Some(TreeAction::View(TreeViewAction::ScrollViewDown))
+            (TreeKeyCode::PageDown, TreeKeyModifiers::NONE) => {
+                Some(TreeAction::View(TreeViewAction::PageDown))
             }
             _ => None,
         }
     }
 }
 
-impl Default for TreeKeyBindings {
+const fn built_in<A, C>(action: &TreeAction<C>) -> Option<TreeAction<A>> {
+    match *action {
+        TreeAction::View(action) => Some(TreeAction::View(action)),
+        TreeAction::Edit(action) => Some(TreeAction::Edit(action)),
+        TreeAction::Custom(_) => None,
+    }
+}
+
+/// The config format read by the `Deserialize` impl of [`TreeKeyBindings`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TreeKeyBindingsConfig {
+    #[serde(default)]
+    profile: KeymapProfile,
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+/// Reads bindings from an application config, such as this TOML:
+///
+/// ```toml
+/// profile = "vim"
+///
+/// [bindings]
+/// "ctrl+shift+up" = "reorder_up"
+/// "d" = "delete"
+/// "delete" = "none"
+/// "g h" = "select_first"
+/// ```
+///
+/// Keys are [`TreeKey`] specs and values are action [names](TreeAction::from_name), bound with
+/// [`TreeKeyBindings::bind`]; `none` [unbinds](TreeKeyBindings::unbind) the key. Specs separated
+/// by spaces, such as `"g g"`, bind a [chord](TreeKeyBindings::bind_chord).
+#[cfg(feature = "serde")]
+impl<'de, C> Deserialize<'de> for TreeKeyBindings<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = TreeKeyBindingsConfig::deserialize(deserializer)?;
+        let mut bindings = Self::custom_with_profile(config.profile);
+        for (spec, name) in config.bindings {
+            let keys = spec
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<TreeKey>, _>>()
+                .map_err(D::Error::custom)?;
+            let action = match name.as_str() {
+                "none" => None,
+                name => Some(
+                    TreeAction::from_name(name)
+                        .ok_or_else(|| D::Error::custom(format!("unknown action: {name}")))?,
+                ),
+            };
+            match keys.as_slice() {
+                [] => return Err(D::Error::custom(TreeKeyParseError::Empty)),
+                [key] => bindings.remap(key.code, key.modifiers, action),
+                _ => {
+                    bindings.chords.push((keys, action));
+                }
+            }
+        }
+        Ok(bindings)
+    }
+}
+
+impl<C> Default for TreeKeyBindings<C> {
     fn default() -> Self {
-        Self::new()
+        Self::custom_with_profile(KeymapProfile::Default)
     }
 }
 
@@ -181,27 +934,180 @@ mod tests {
     fn ignores_release_and_unrelated_modifiers() {
         let bindings = TreeKeyBindings::new();
         let release =
-            KeyEvent::new_with_kind(KeyCode::Down, KeyModifiers::NONE, KeyEventKind::Release);
+            TreeKey::new(TreeKeyCode::Down, TreeKeyModifiers::NONE).with_kind(TreeKeyKind::Release);
         assert_eq!(bindings.resolve::<()>(release), None);
 
-        let control_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
+        let control_e = TreeKey::new(TreeKeyCode::Char('e'), TreeKeyModifiers::CONTROL);
         assert_eq!(bindings.resolve::<()>(control_e), None);
     }
 
+    #[test]
+    fn custom_bindings_take_precedence_over_built_in_ones() {
+        let mut bindings = TreeKeyBindings::default();
+        let enter = TreeKey::new(TreeKeyCode::Enter, TreeKeyModifiers::NONE);
+        assert_eq!(
+            bindings.bind_custom(TreeKeyCode::Enter, TreeKeyModifiers::NONE, "open"),
+            None
+        );
+        assert_eq!(
+            bindings.bind_custom(TreeKeyCode::Enter, TreeKeyModifiers::NONE, "edit"),
+            Some("open")
+        );
+        assert_eq!(
+            bindings.resolve_bound(enter),
+            Some(TreeAction::Custom("edit"))
+        );
+
+        let press = |code| TreeKey::new(code, TreeKeyModifiers::NONE);
+        assert_eq!(
+            bindings.resolve_bound_counted(press(TreeKeyCode::Char('3'))),
+            None
+        );
+        assert_eq!(
+            bindings.resolve_bound_counted(enter),
+            Some((TreeAction::Custom("edit"), Some(3)))
+        );
+
+        assert_eq!(
+            bindings.unbind_custom(TreeKeyCode::Enter, TreeKeyModifiers::NONE),
+            Some("edit")
+        );
+        assert_eq!(
+            bindings.resolve_bound(enter),
+            Some(TreeViewAction::ToggleNode.into())
+        );
+    }
+
     #[test]
     fn resolves_standard_tree_navigation() {
         let bindings = TreeKeyBindings::new();
-        let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+        let right = TreeKey::new(TreeKeyCode::Right, TreeKeyModifiers::NONE);
         assert_eq!(
             bindings.resolve::<()>(right),
             Some(TreeViewAction::ExpandOrSelectFirstChild.into())
         );
     }
 
+    #[test]
+    fn numeric_prefixes_accumulate_until_an_action() {
+        let mut bindings = TreeKeyBindings::new();
+        let press = |code| TreeKey::new(code, TreeKeyModifiers::NONE);
+        let none = |_| None::<()>;
+
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('0')), none),
+            None
+        );
+        assert_eq!(bindings.pending_count(), None);
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('1')), none),
+            None
+        );
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('2')), none),
+            None
+        );
+        assert_eq!(bindings.pending_count(), Some(12));
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('j')), none),
+            Some((TreeViewAction::SelectNext.into(), Some(12)))
+        );
+        assert_eq!(bindings.pending_count(), None);
+
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('3')), none),
+            None
+        );
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Esc), none),
+            None
+        );
+        assert_eq!(
+            bindings.resolve_counted_with(press(TreeKeyCode::Char('j')), none),
+            Some((TreeViewAction::SelectNext.into(), None))
+        );
+    }
+
+    #[test]
+    fn chords_wait_for_their_next_key() {
+        let mut bindings = TreeKeyBindings::with_profile(KeymapProfile::Vim);
+        let press = |character| TreeKey::new(TreeKeyCode::Char(character), TreeKeyModifiers::NONE);
+        let none = |_| None::<()>;
+
+        assert_eq!(
+            bindings.resolve_counted_with(press('g'), none),
+            Some((TreeViewAction::ToggleGuides.into(), None)),
+            "chords are opt-in"
+        );
+        bindings.bind_vim_chords();
+        assert_eq!(bindings.resolve_counted_with(press('g'), none), None);
+        assert_eq!(bindings.pending_chord(), [press('g')]);
+        assert_eq!(
+            bindings.resolve_counted_with(press('g'), none),
+            Some((TreeViewAction::SelectFirst.into(), None))
+        );
+        assert!(bindings.pending_chord().is_empty());
+
+        assert_eq!(bindings.resolve_counted_with(press('3'), none), None);
+        assert_eq!(
+            bindings.resolve_counted_with(press('d'), none),
+            Some((TreeEditAction::Detach.into(), Some(3)))
+        );
+
+        let shift_r = TreeKey::new(TreeKeyCode::Char('R'), TreeKeyModifiers::SHIFT);
+        assert_eq!(bindings.resolve_counted_with(press('z'), none), None);
+        assert_eq!(
+            bindings.resolve_counted_with(shift_r, none),
+            Some((TreeViewAction::ExpandAll.into(), None))
+        );
+
+        assert_eq!(bindings.resolve_counted_with(press('g'), none), None);
+        assert_eq!(
+            bindings.resolve_counted_with(press('j'), none),
+            Some((TreeViewAction::SelectNext.into(), None))
+        );
+        assert!(bindings.pending_chord().is_empty());
+
+        assert_eq!(bindings.resolve_counted_with(press('g'), none), None);
+        assert!(!bindings.expire_pending_chord(Instant::now()));
+        assert!(bindings.expire_pending_chord(Instant::now() + Duration::from_secs(2)));
+        assert!(bindings.pending_chord().is_empty());
+
+        let start = Instant::now();
+        assert_eq!(bindings.resolve_bound_counted_at(press('g'), start), None);
+        assert_eq!(
+            bindings.resolve_bound_counted_at(press('g'), start + Duration::from_secs(2)),
+            None,
+            "the first `g` timed out, so the second one starts a new chord"
+        );
+        assert_eq!(
+            bindings.resolve_bound_counted_at(press('g'), start + Duration::from_millis(2500)),
+            Some((TreeViewAction::SelectFirst.into(), None))
+        );
+
+        assert_eq!(
+            bindings.unbind_chord([press('g'), press('g')]),
+            Some(TreeViewAction::SelectFirst.into())
+        );
+        assert_eq!(
+            bindings.resolve_counted_with(press('g'), none),
+            Some((TreeViewAction::ToggleGuides.into(), None))
+        );
+        assert_eq!(
+            bindings.bind_chord([press('g'), press('e')], TreeViewAction::SelectLast),
+            None
+        );
+        assert_eq!(bindings.resolve_counted_with(press('g'), none), None);
+        assert_eq!(
+            bindings.resolve_counted_with(press('e'), none),
+            Some((TreeViewAction::SelectLast.into(), None))
+        );
+    }
+
     #[test]
     fn navigation_profiles_share_actions_but_restrict_keys() {
-        let up = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-        let k = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE);
+        let up = TreeKey::new(TreeKeyCode::Up, TreeKeyModifiers::NONE);
+        let k = TreeKey::new(TreeKeyCode::Char('k'), TreeKeyModifiers::NONE);
         let vim = TreeKeyBindings::with_profile(KeymapProfile::Vim);
         let arrows = TreeKeyBindings::with_profile(KeymapProfile::Arrows);
 
@@ -216,4 +1122,162 @@ mod tests {
             Some(TreeViewAction::SelectPrev.into())
         );
     }
+
+    #[test]
+    fn layers_resolve_before_application_and_built_in_bindings() {
+        let press = |code| TreeKey::new(code, TreeKeyModifiers::NONE);
+        let mut bindings = TreeKeyBindings::default();
+        let _ = bindings.bind_custom(TreeKeyCode::Enter, TreeKeyModifiers::NONE, "open");
+        bindings.push_layer(
+            TreeKeyLayer::new()
+                .with_binding(
+                    TreeKeyCode::Char('j'),
+                    TreeKeyModifiers::NONE,
+                    TreeViewAction::SelectLast,
+                )
+                .with_binding(
+                    TreeKeyCode::Char('1'),
+                    TreeKeyModifiers::NONE,
+                    TreeAction::Custom("one"),
+                ),
+        );
+        assert_eq!(
+            bindings.resolve_bound(press(TreeKeyCode::Char('j'))),
+            Some(TreeViewAction::SelectLast.into())
+        );
+        assert_eq!(
+            bindings.resolve_bound(press(TreeKeyCode::Enter)),
+            Some(TreeAction::Custom("open"))
+        );
+        assert_eq!(
+            bindings.resolve_bound_counted(press(TreeKeyCode::Char('1'))),
+            Some((TreeAction::Custom("one"), None))
+        );
+
+        bindings.push_layer(
+            TreeKeyLayer::new()
+                .with_binding(
+                    TreeKeyCode::Esc,
+                    TreeKeyModifiers::NONE,
+                    TreeAction::Custom("cancel"),
+                )
+                .capturing(),
+        );
+        assert_eq!(bindings.resolve_bound(press(TreeKeyCode::Char('j'))), None);
+        assert_eq!(bindings.resolve_bound(press(TreeKeyCode::Enter)), None);
+        assert_eq!(
+            bindings.resolve_bound_counted(press(TreeKeyCode::Esc)),
+            Some((TreeAction::Custom("cancel"), None))
+        );
+
+        assert!(bindings.pop_layer().is_some_and(|layer| layer.captures()));
+        assert!(bindings.pop_layer().is_some());
+        assert_eq!(
+            bindings.resolve_bound(press(TreeKeyCode::Char('j'))),
+            Some(TreeViewAction::SelectNext.into())
+        );
+    }
+
+    #[test]
+    fn remapped_keys_replace_built_in_and_application_bindings() {
+        let press = |code| TreeKey::new(code, TreeKeyModifiers::NONE);
+        let mut bindings = TreeKeyBindings::default();
+        let delete = TreeAction::Edit(TreeEditAction::Detach);
+        assert_eq!(
+            bindings.binding(press(TreeKeyCode::Char('d'))),
+            Some(delete)
+        );
+        assert_eq!(
+            bindings.bind(
+                TreeKeyCode::Char('d'),
+                TreeKeyModifiers::NONE,
+                TreeEditAction::Delete
+            ),
+            Some(delete)
+        );
+        assert_eq!(
+            bindings.unbind(TreeKeyCode::Delete, TreeKeyModifiers::NONE),
+            Some(delete)
+        );
+        assert_eq!(
+            bindings.resolve_bound(press(TreeKeyCode::Char('d'))),
+            Some(TreeEditAction::Delete.into())
+        );
+        assert_eq!(bindings.resolve_bound(press(TreeKeyCode::Delete)), None);
+        assert_eq!(
+            bindings.resolve_with(press(TreeKeyCode::Delete), |_| Some("custom")),
+            None
+        );
+
+        let _ = bindings.bind(
+            TreeKeyCode::Char('3'),
+            TreeKeyModifiers::NONE,
+            TreeAction::Custom("three"),
+        );
+        assert_eq!(
+            bindings.resolve_bound_counted(press(TreeKeyCode::Char('3'))),
+            Some((TreeAction::Custom("three"), None))
+        );
+        assert_eq!(
+            bindings.bind_custom(TreeKeyCode::Char('3'), TreeKeyModifiers::NONE, "custom"),
+            None
+        );
+        assert_eq!(
+            bindings.binding(press(TreeKeyCode::Char('3'))),
+            Some(TreeAction::Custom("custom"))
+        );
+
+        assert!(bindings.reset_binding(TreeKeyCode::Delete, TreeKeyModifiers::NONE));
+        assert!(!bindings.reset_binding(TreeKeyCode::Delete, TreeKeyModifiers::NONE));
+        assert_eq!(bindings.binding(press(TreeKeyCode::Delete)), Some(delete));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bindings_load_from_a_config() {
+        let json = r#"{
+            "profile": "vim",
+            "bindings": {
+                "ctrl+shift+Up": "reorder_up",
+                "d": "delete",
+                "Delete": "none",
+                "G": "select_last",
+                "z z": "collapse_to_selection"
+            }
+        }"#;
+        let bindings: TreeKeyBindings =
+            serde_json::from_str(json).expect("valid key binding config");
+        assert_eq!(bindings.profile(), KeymapProfile::Vim);
+        let up = TreeKey::new(
+            TreeKeyCode::Up,
+            TreeKeyModifiers::CONTROL | TreeKeyModifiers::SHIFT,
+        );
+        assert_eq!(
+            bindings.resolve_bound(up),
+            Some(TreeEditAction::ReorderUp.into())
+        );
+        assert_eq!(
+            bindings.resolve_bound(TreeKey::new(TreeKeyCode::Char('d'), TreeKeyModifiers::NONE)),
+            Some(TreeEditAction::Delete.into())
+        );
+        assert_eq!(bindings.resolve_bound(TreeKeyCode::Delete), None);
+        assert_eq!(
+            bindings.resolve_bound(TreeKey::new(
+                TreeKeyCode::Char('G'),
+                TreeKeyModifiers::SHIFT
+            )),
+            Some(TreeViewAction::SelectLast.into())
+        );
+        let z = TreeKey::new(TreeKeyCode::Char('z'), TreeKeyModifiers::NONE);
+        assert_eq!(
+            bindings.chord(&[z, z]),
+            Some(TreeViewAction::CollapseToSelection.into())
+        );
+
+        let unknown = serde_json::from_str::<TreeKeyBindings>(r#"{"bindings": {"d": "explode"}}"#);
+        assert!(unknown.is_err_and(|error| error.to_string().contains("unknown action: explode")));
+        let bad_key =
+            serde_json::from_str::<TreeKeyBindings>(r#"{"bindings": {"hyperz+d": "delete"}}"#);
+        assert!(bad_key.is_err_and(|error| error.to_string().contains("unknown modifier: hyperz")));
+    }
 }
@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+
+use crate::style::TreeListViewStyle;
+
+/// An entry of the footer legend: a sample glyph in the style it explains, then its meaning.
+///
+/// Pass the entries to [`TreeListView::legend`](crate::TreeListView::legend).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeLegendEntry<'a> {
+    pub symbol: Span<'a>,
+    pub label: Cow<'a, str>,
+}
+
+impl<'a> TreeLegendEntry<'a> {
+    #[must_use]
+    pub fn new(symbol: impl Into<Span<'a>>, label: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Explains the styles of manual, partial, and inherited marks in `style`.
+    #[must_use]
+    pub fn marks(style: &TreeListViewStyle<'_>) -> [TreeLegendEntry<'static>; 3] {
+        [
+            TreeLegendEntry::new(Span::styled("■", style.marked_style), "marked"),
+            TreeLegendEntry::new(Span::styled("■", style.partial_mark_style), "partial"),
+            TreeLegendEntry::new(
+                Span::styled("■", style.marked_style.patch(style.inherited_mark_style)),
+                "inherited",
+            ),
+        ]
+    }
+}
+
+/// Lays `entries` out on one line, such as ` ■ marked  ■ partial `.
+pub fn legend_line<'a>(entries: &[TreeLegendEntry<'a>], label_style: Style) -> Line<'a> {
+    let mut spans = Vec::with_capacity(entries.len().saturating_mul(2).saturating_add(1));
+    for (index, entry) in entries.iter().enumerate() {
+        let gap = if index == 0 { " " } else { "  " };
+        spans.push(Span::styled(gap, label_style));
+        spans.push(entry.symbol.clone());
+        spans.push(Span::styled(format!(" {}", entry.label), label_style));
+    }
+    spans.push(Span::styled(" ", label_style));
+    Line::from(spans)
+}
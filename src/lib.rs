@@ -10,53 +10,98 @@
 //! two-dimensional scrolling, tri-state marks, snapshots, and hit testing.
 //!
 //! Feature flags:
-//! - `keymap`: crossterm-based key bindings and `TreeListViewState::handle_key*` helpers.
-//! - `serde`: serde support for `TreeListViewSnapshot`.
+//! - `keys`: terminal-independent key bindings and `TreeListViewState::handle_key*` helpers.
+//! - `keymap`: `keys` plus crossterm key conversions and mouse handling.
+//! - `termion`, `termwiz`: `keys` plus key conversions from those libraries.
+//! - `serde`: serde support for `TreeListViewSnapshot` and the versioned `TreeVersionedSnapshot`,
+//!   and with `keys`, loading `TreeKeyBindings` from application configs.
 
 #![allow(clippy::multiple_crate_versions)]
 
+#[cfg(feature = "keys")]
+pub use action::TreeKeyDispatch;
 pub use action::{
-    TreeAction, TreeEditAction, TreeEditRequest, TreeEvent, TreeIntent, TreeViewAction,
+    TreeAction, TreeClickKind, TreeEditAction, TreeEditRequest, TreeEvent, TreeIntent,
+    TreeViewAction,
 };
-pub use adapters::{IndexedTree, IndexedTreeError, TreeModelRef};
+pub use adapters::{
+    FnTreeModel, GroupedId, GroupedTreeModel, IndexedTree, IndexedTreeError, OwnedChildrenCache,
+    OwnedTreeModel, SortedChildrenCache, SortedTreeModel, TreeModelRef,
+};
+pub use aggregate::SubtreeAgg;
+pub use buffer_text::tree_buffer_text;
 pub use columns::{
-    ColumnDef, ColumnWidth, ColumnWidthError, TreeCellRenderer, TreeColumnSet, TreeColumns,
-    TreeColumnsError, distribute_widths,
+    AdaptiveColumns, ColumnDef, ColumnFilter, ColumnSort, ColumnWidth, ColumnWidthError,
+    TreeCellOverflow, TreeCellRenderer, TreeColumnSet, TreeColumnSqueeze, TreeColumns,
+    TreeColumnsError, TreeSortDirection, TreeSortKeys, distribute_widths,
 };
+pub use columns_view::TreeColumnsView;
 pub use context::{
-    TreeExpansionState, TreeMarkState, TreeMatchState, TreeRowContext, TreeRowNodeState,
-    TreeRowRenderState,
+    TreeExpansionState, TreeMarkSource, TreeMarkState, TreeMatchState, TreeRowContext,
+    TreeRowNodeState, TreeRowRenderState,
 };
+pub use details::TreeDetailProvider;
 pub use edit::{
-    TreeChangeSet, TreeEditCommand, TreeEditor, TreeInsertPosition, TreeSelectionUpdate,
+    TreeChangeSet, TreeEditCommand, TreeEditError, TreeEditor, TreeInsertPosition,
+    TreeSelectionUpdate,
 };
 pub use glyphs::{
-    TreeGlyphs, TreeLabelPrefix, TreeLabelProvider, TreeLabelRenderer, tree_label_line,
-    tree_name_cell,
+    TreeGlyphs, TreeLabelPrefix, TreeLabelProvider, TreeLabelRenderer, TreeLeafAlignment,
+    tree_label_line, tree_name_cell,
 };
-#[cfg(feature = "keymap")]
-pub use keymap::{KeymapProfile, TreeKeyBindings};
+#[cfg(feature = "keys")]
+pub use key::{TreeKey, TreeKeyCode, TreeKeyKind, TreeKeyModifiers, TreeKeyParseError};
+#[cfg(feature = "keys")]
+pub use keymap::{KeymapProfile, TreeKeyBindings, TreeKeyLayer};
+pub use legend::TreeLegendEntry;
+pub use meter::TreeMeter;
 pub use model::{
-    NoFilter, NoSort, TreeChildren, TreeFilter, TreeFilterConfig, TreeModel, TreeQuery,
-    TreeRevision, TreeRootVisibility, TreeSelectionFallback, TreeSort,
+    NoFilter, NoSort, TreeChildren, TreeExpandAllScope, TreeFilter, TreeFilterConfig, TreeModel,
+    TreeModelOwned, TreePlaceholders, TreeQuery, TreeRevision, TreeRootVisibility,
+    TreeSelectionFallback, TreeSort,
+};
+pub use overlay::TreeOverlayRows;
+pub use path::{PathId, PathInterner, TreePath};
+pub use projection::{ProjectedNode, TreePlaceholder, TreeProjection};
+pub use search::{TextFilter, TreeSearchBar};
+pub use state::{
+    TreeAcceleration, TreeCellFlagging, TreeFollowTail, TreeHit, TreeHitPart, TreeHitRegion,
+    TreeListViewSnapshot, TreeListViewState, TreeListViewStateBuilder, TreeOverlaySelection,
+    TreeRemapReport, TreeReview, TreeScrollLink, TreeScrollMetrics, TreeSharedState,
+    TreeSnapshotV1, TreeSnapshotV2, TreeSnapshotV3, TreeVersionedSnapshot,
+};
+pub use style::{
+    TreeHorizontalScroll, TreeLabelScrollbar, TreeListViewStyle, TreeModelCheck, TreeOverscroll,
+    TreePositionIndicator, TreeRowNumbers, TreeRowRendering, TreeScrollPolicy, TreeTheme,
 };
-pub use projection::{ProjectedNode, TreeProjection};
-pub use state::{TreeHit, TreeHitRegion, TreeListViewSnapshot, TreeListViewState};
-pub use style::{TreeHorizontalScroll, TreeListViewStyle, TreeRowRendering, TreeScrollPolicy};
-pub use widget::TreeListView;
+pub use vec_tree::{VecTreeError, VecTreeModel};
+pub use widget::{TreeListView, TreeMeasure};
 
 mod action;
 mod adapters;
+mod aggregate;
+mod buffer_text;
+mod collections;
 mod columns;
+mod columns_view;
 mod context;
+mod details;
 mod edit;
 mod glyphs;
-#[cfg(feature = "keymap")]
+#[cfg(feature = "keys")]
+mod key;
+#[cfg(feature = "keys")]
 mod keymap;
+mod legend;
+mod meter;
 mod model;
+mod overlay;
+mod path;
 pub mod prelude;
 mod projection;
+mod search;
 mod state;
 mod style;
 mod traversal;
+mod vec_tree;
 mod widget;
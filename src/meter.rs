@@ -0,0 +1,161 @@
+use ratatui::style::Style;
+use ratatui::widgets::Cell;
+
+/// Partial blocks for the last cell of a bar, by eighths.
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Braille dots filled from the bottom up, for the left and right sample of a character.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x40, 0x04, 0x02, 0x01], [0x80, 0x20, 0x10, 0x08]];
+
+/// Builds fixed-width cells that draw fractions in `0.0..=1.0`, for CPU, size, or progress
+/// columns.
+///
+/// A renderer can take the width from [`TreeRowContext::column_widths`] so the meter follows
+/// the column. Values outside the range are clamped and `NaN` draws as zero.
+///
+/// [`TreeRowContext::column_widths`]: crate::TreeRowContext::column_widths
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TreeMeter<'a> {
+    width: u16,
+    style: Style,
+    thresholds: &'a [(f32, Style)],
+}
+
+impl<'a> TreeMeter<'a> {
+    /// Creates a meter `width` cells wide.
+    #[must_use]
+    pub const fn new(width: u16) -> Self {
+        Self {
+            width,
+            style: Style::new(),
+            thresholds: &[],
+        }
+    }
+
+    /// Sets the style of values below every threshold.
+    #[must_use]
+    pub const fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets `(threshold, style)` pairs in ascending order; a value takes the style of the last
+    /// threshold it reaches, such as yellow from `0.7` and red from `0.9`.
+    #[must_use]
+    pub const fn thresholds(mut self, thresholds: &'a [(f32, Style)]) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Returns the style for `value`.
+    #[must_use]
+    pub fn style_for(&self, value: f32) -> Style {
+        self.thresholds
+            .iter()
+            .rev()
+            .find(|(threshold, _)| value >= *threshold)
+            .map_or(self.style, |(_, style)| *style)
+    }
+
+    /// Draws `value` as a horizontal bar with eighth-cell precision.
+    #[must_use]
+    pub fn bar(&self, value: f32) -> Cell<'static> {
+        let eighths = scale(value, self.width.saturating_mul(8));
+        let full = usize::from(eighths / 8);
+        let mut text: String = std::iter::repeat_n('█', full).collect();
+        if full < usize::from(self.width) {
+            text.push(EIGHTHS[usize::from(eighths % 8)]);
+        }
+        text.extend(std::iter::repeat_n(
+            ' ',
+            usize::from(self.width).saturating_sub(full + 1),
+        ));
+        Cell::new(text).style(self.style_for(value))
+    }
+
+    /// Draws the most recent `values` as a braille sparkline of two samples per cell, styled by
+    /// the last value.
+    #[must_use]
+    pub fn sparkline(&self, values: &[f32]) -> Cell<'static> {
+        let capacity = usize::from(self.width) * 2;
+        let shown = &values[values.len().saturating_sub(capacity)..];
+        let padding = capacity - shown.len();
+        let levels: Vec<u16> = std::iter::repeat_n(0, padding)
+            .chain(shown.iter().map(|value| scale(*value, 4)))
+            .collect();
+        let text: String = levels
+            .chunks(2)
+            .map(|pair| {
+                let dots = pair
+                    .iter()
+                    .zip(BRAILLE_DOTS)
+                    .flat_map(|(level, dots)| dots.into_iter().take(usize::from(*level)))
+                    .fold(0, |bits, dot| bits | dot);
+                char::from_u32(0x2800 + dots).unwrap_or(' ')
+            })
+            .collect();
+        let last = values.last().copied().unwrap_or_default();
+        Cell::new(text).style(self.style_for(last))
+    }
+}
+
+/// Maps `value` in `0.0..=1.0` to `0..=steps`.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "the rounded value is clamped to 0..=steps"
+)]
+fn scale(value: f32, steps: u16) -> u16 {
+    if value.is_nan() {
+        return 0;
+    }
+    (f32::from(steps) * value.clamp(0.0, 1.0)).round() as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::Color;
+    use ratatui::widgets::Widget;
+
+    use super::*;
+
+    fn drawn(cell: Cell<'_>, width: u16) -> (String, Style) {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buffer = Buffer::empty(area);
+        ratatui::widgets::Table::new(
+            [ratatui::widgets::Row::new([cell])],
+            [ratatui::layout::Constraint::Length(width)],
+        )
+        .render(area, &mut buffer);
+        let text = (0..width).map(|x| buffer[(x, 0)].symbol()).collect();
+        (text, buffer[(0, 0)].style())
+    }
+
+    #[test]
+    fn bars_fill_eighths_and_take_the_threshold_style() {
+        const RED: Style = Style::new().fg(Color::Red);
+        const THRESHOLDS: [(f32, Style); 2] = [(0.5, Style::new().fg(Color::Yellow)), (0.9, RED)];
+        let meter = TreeMeter::new(4)
+            .style(Style::new().fg(Color::Green))
+            .thresholds(&THRESHOLDS);
+
+        assert_eq!(drawn(meter.bar(0.0), 4).0, "    ");
+        assert_eq!(drawn(meter.bar(0.3125), 4).0, "█▎  ");
+        let (full, style) = drawn(meter.bar(1.5), 4);
+        assert_eq!((full.as_str(), style.fg), ("████", RED.fg));
+        assert_eq!(meter.style_for(0.6).fg, Some(Color::Yellow));
+        assert_eq!(meter.style_for(f32::NAN).fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn sparklines_show_the_latest_samples_two_per_cell() {
+        let meter = TreeMeter::new(2);
+        assert_eq!(drawn(meter.sparkline(&[1.0, 0.5]), 2).0, "⠀⣧");
+        assert_eq!(
+            drawn(meter.sparkline(&[0.0, 0.0, 0.25, 1.0, 0.75, 0.0]), 2).0,
+            "⣸⡆"
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::cmp::Ordering;
 use std::hash::Hash;
 use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 
+use smallvec::SmallVec;
+
 static NEXT_QUERY_POLICY_GENERATION: AtomicU64 = AtomicU64::new(1);
 
 /// The state of a node's child list.
@@ -57,6 +59,19 @@ pub enum TreeRootVisibility {
     Hidden,
 }
 
+/// Controls placeholder rows under expanded nodes that have no visible children.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TreePlaceholders {
+    /// Expanded nodes without visible children are displayed as leaves.
+    #[default]
+    Hidden,
+    /// Expanded nodes whose loaded children are empty, or all filtered out, stay expanded and get
+    /// one indented placeholder row, see
+    /// [`ProjectedNode::placeholder`](crate::ProjectedNode::placeholder). Expanded nodes whose
+    /// children are unloaded or loading get a loading row. Placeholder rows are never selected.
+    Visible,
+}
+
 /// Selection policy used when the selected node disappears from the projection.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum TreeSelectionFallback {
@@ -67,18 +82,50 @@ pub enum TreeSelectionFallback {
     Nearest,
     /// Clear the selection.
     Clear,
+    /// Prefer the next surviving sibling, then the previous one, then the parent and the nearest
+    /// row, as file managers do after a deletion.
+    NextSibling,
+    /// Prefer the previous surviving sibling, then the next one, then the parent and the nearest
+    /// row.
+    PreviousSibling,
+}
+
+/// The branches that [`TreeViewAction::ExpandAll`](crate::TreeViewAction::ExpandAll) opens
+/// while a filter is active.
+///
+/// The default is [`Self::Filtered`]. `ExpandAll` used to expand the whole model under a filter
+/// as well; choose [`Self::Model`] to keep that behavior.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TreeExpandAllScope {
+    /// Expand only the branches that lead to matches, so clearing the filter reveals the paths
+    /// that were found.
+    #[default]
+    Filtered,
+    /// Expand every loaded branch of the model, including branches the filter hides.
+    Model,
 }
 
 /// Tree filtering configuration.
+///
+/// Build [`Self::Enabled`] with [`Self::enabled`] or [`Self::enabled_manual_expand`] and adjust it
+/// with [`Self::with_expand_all`]; the variant is non-exhaustive so new options do not break
+/// callers.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum TreeFilterConfig {
     /// Filtering is disabled.
     #[default]
     Disabled,
     /// Keep matching nodes and the paths leading to them.
+    #[non_exhaustive]
     Enabled {
         /// Force filtered paths to expand.
+        ///
+        /// When `false`, the filtered view respects the persisted expansion: matches are only
+        /// shown inside expanded branches, and collapsed branches that contain them stay
+        /// collapsed.
         auto_expand: bool,
+        /// What [`TreeViewAction::ExpandAll`](crate::TreeViewAction::ExpandAll) expands.
+        expand_all: TreeExpandAllScope,
     },
 }
 
@@ -86,13 +133,42 @@ impl TreeFilterConfig {
     /// Enables filtering with automatic path expansion.
     #[must_use]
     pub const fn enabled() -> Self {
-        Self::Enabled { auto_expand: true }
+        Self::Enabled {
+            auto_expand: true,
+            expand_all: TreeExpandAllScope::Filtered,
+        }
     }
 
-    /// Enables filtering with manual path expansion.
+    /// Enables filtering that keeps the user's expanded and collapsed branches.
     #[must_use]
     pub const fn enabled_manual_expand() -> Self {
-        Self::Enabled { auto_expand: false }
+        Self::Enabled {
+            auto_expand: false,
+            expand_all: TreeExpandAllScope::Filtered,
+        }
+    }
+
+    /// Sets what [`TreeViewAction::ExpandAll`](crate::TreeViewAction::ExpandAll) expands. A
+    /// disabled configuration is returned unchanged.
+    #[must_use]
+    pub const fn with_expand_all(self, scope: TreeExpandAllScope) -> Self {
+        match self {
+            Self::Disabled => Self::Disabled,
+            Self::Enabled { auto_expand, .. } => Self::Enabled {
+                auto_expand,
+                expand_all: scope,
+            },
+        }
+    }
+
+    /// Returns the scope of [`TreeViewAction::ExpandAll`](crate::TreeViewAction::ExpandAll),
+    /// which is the whole model when filtering is disabled.
+    #[must_use]
+    pub const fn expand_all_scope(self) -> TreeExpandAllScope {
+        match self {
+            Self::Disabled => TreeExpandAllScope::Model,
+            Self::Enabled { expand_all, .. } => expand_all,
+        }
     }
 }
 
@@ -169,6 +245,7 @@ pub struct TreeQuery<F = NoFilter, S = NoSort> {
     sort: QueryPolicy<S>,
     filter_config: TreeFilterConfig,
     root_visibility: TreeRootVisibility,
+    placeholders: TreePlaceholders,
     selection_fallback: TreeSelectionFallback,
 }
 
@@ -181,6 +258,7 @@ impl TreeQuery {
             sort: QueryPolicy::new(NoSort, TreeRevision::INITIAL),
             filter_config: TreeFilterConfig::Disabled,
             root_visibility: TreeRootVisibility::Visible,
+            placeholders: TreePlaceholders::Hidden,
             selection_fallback: TreeSelectionFallback::ParentThenNearest,
         }
     }
@@ -200,6 +278,7 @@ impl<F, S> TreeQuery<F, S> {
             sort: self.sort,
             filter_config: config,
             root_visibility: self.root_visibility,
+            placeholders: self.placeholders,
             selection_fallback: self.selection_fallback,
         }
     }
@@ -212,6 +291,7 @@ impl<F, S> TreeQuery<F, S> {
             sort: QueryPolicy::replacement(sort, revision),
             filter_config: self.filter_config,
             root_visibility: self.root_visibility,
+            placeholders: self.placeholders,
             selection_fallback: self.selection_fallback,
         }
     }
@@ -230,6 +310,13 @@ impl<F, S> TreeQuery<F, S> {
         self
     }
 
+    /// Sets whether expanded nodes without visible children get a placeholder row.
+    #[must_use]
+    pub const fn with_placeholders(mut self, placeholders: TreePlaceholders) -> Self {
+        self.placeholders = placeholders;
+        self
+    }
+
     /// Sets the selection fallback policy.
     #[must_use]
     pub const fn with_selection_fallback(mut self, fallback: TreeSelectionFallback) -> Self {
@@ -285,6 +372,13 @@ impl<F, S> TreeQuery<F, S> {
         changed
     }
 
+    /// Changes whether expanded nodes without visible children get a placeholder row.
+    pub fn set_placeholders(&mut self, placeholders: TreePlaceholders) -> bool {
+        let changed = self.placeholders != placeholders;
+        self.placeholders = placeholders;
+        changed
+    }
+
     /// Changes the selection fallback policy.
     pub fn set_selection_fallback(&mut self, fallback: TreeSelectionFallback) -> bool {
         let changed = self.selection_fallback != fallback;
@@ -304,6 +398,12 @@ impl<F, S> TreeQuery<F, S> {
         self.root_visibility
     }
 
+    /// Returns whether placeholder rows are displayed.
+    #[must_use]
+    pub const fn placeholders(&self) -> TreePlaceholders {
+        self.placeholders
+    }
+
     /// Returns the current selection fallback policy.
     #[must_use]
     pub const fn selection_fallback(&self) -> TreeSelectionFallback {
@@ -386,6 +486,69 @@ pub trait TreeModel {
     /// Returns the node's child state and loaded children.
     fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id>;
 
+    /// Returns the revision of the model structure and display data.
+    ///
+    /// Children that change without a new revision are a bug in the model, but not a panic in
+    /// the widget: frames keep rendering the last projection, clamped to its own length, until
+    /// the revision advances. Row renderers still receive ids from that projection, so lookups in
+    /// application code should tolerate ids that were removed since.
+    fn revision(&self) -> TreeRevision;
+
+    /// Returns an approximate number of available nodes.
+    fn size_hint(&self) -> usize {
+        0
+    }
+
+    /// Returns `false` for an id that no longer exists, such as a deleted node still held by a
+    /// stale projection.
+    ///
+    /// Only consulted under [`TreeModelCheck::Verify`](crate::TreeModelCheck::Verify).
+    fn contains(&self, _id: Self::Id) -> bool {
+        true
+    }
+
+    /// Returns `false` for rows drawn without tree guides, such as section headers or separators.
+    ///
+    /// Only consulted while guides are enabled in the view state.
+    fn draws_guides(&self, _id: Self::Id) -> bool {
+        true
+    }
+
+    /// Returns the share of a row, from `0.0` to `1.0`, to paint with the style's `fill_style`.
+    ///
+    /// Suits size or progress bars such as a disk-usage view. Values are clamped; `None` and NaN
+    /// paint nothing.
+    fn fill_fraction(&self, _id: Self::Id) -> Option<f32> {
+        None
+    }
+
+    /// Returns how many synthetic rows to pin directly below the node, such as inline
+    /// diagnostics.
+    ///
+    /// Overlay rows follow the node whether or not it is expanded, are skipped by selection
+    /// unless [`TreeOverlaySelection::Selectable`](crate::TreeOverlaySelection::Selectable) is
+    /// set, and take their text from a [`TreeOverlayRows`](crate::TreeOverlayRows) provider.
+    /// Advance the revision when a count changes.
+    fn overlay_rows(&self, _id: Self::Id) -> usize {
+        0
+    }
+}
+
+/// A model whose children are computed on demand and returned by value, such as database cursors
+/// or filtered views that cannot keep contiguous child arrays.
+///
+/// Present it to the widget through [`OwnedTreeModel`](crate::OwnedTreeModel), which stores the
+/// returned lists between frames.
+pub trait TreeModelOwned {
+    /// The node identifier type.
+    type Id: Copy + Eq + Hash;
+
+    /// Returns forest roots in deterministic order.
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_;
+
+    /// Returns the node's children; an empty list marks a leaf.
+    fn children(&self, id: Self::Id) -> SmallVec<[Self::Id; 8]>;
+
     /// Returns the revision of the model structure and display data.
     fn revision(&self) -> TreeRevision;
 
@@ -393,6 +556,26 @@ pub trait TreeModel {
     fn size_hint(&self) -> usize {
         0
     }
+
+    /// Returns `false` for an id that no longer exists, as in [`TreeModel::contains`].
+    fn contains(&self, _id: Self::Id) -> bool {
+        true
+    }
+
+    /// Returns `false` for rows drawn without tree guides, as in [`TreeModel::draws_guides`].
+    fn draws_guides(&self, _id: Self::Id) -> bool {
+        true
+    }
+
+    /// Returns the share of a row to fill, as in [`TreeModel::fill_fraction`].
+    fn fill_fraction(&self, _id: Self::Id) -> Option<f32> {
+        None
+    }
+
+    /// Returns the number of rows pinned below the node, as in [`TreeModel::overlay_rows`].
+    fn overlay_rows(&self, _id: Self::Id) -> usize {
+        0
+    }
 }
 
 /// A node visibility filter.
@@ -0,0 +1,13 @@
+use ratatui::text::Line;
+
+use crate::model::TreeModel;
+
+/// Supplies the text of the synthetic rows pinned below nodes by
+/// [`TreeModel::overlay_rows`], such as an error message under a failing node.
+///
+/// Pass the provider to [`TreeListView::overlay_rows`](crate::TreeListView::overlay_rows).
+/// Rows without a provider stay blank.
+pub trait TreeOverlayRows<T: TreeModel> {
+    /// Returns overlay row `index` of `id`, counting from zero below the node.
+    fn overlay_row<'a>(&'a self, model: &'a T, id: T::Id, index: usize) -> Line<'a>;
+}
@@ -0,0 +1,240 @@
+use std::hash::Hash;
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
+
+use crate::glyphs::TreeLabelProvider;
+use crate::model::TreeModel;
+use crate::traversal::TreeWalk;
+
+/// Node locations spelled as label names joined by a separator.
+///
+/// Paths survive sessions in which ids are not stable. Names are compared verbatim, so a name
+/// that contains the separator cannot be resolved, and among equally named siblings the first one
+/// wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreePath<'a> {
+    separator: &'a str,
+}
+
+impl<'a> TreePath<'a> {
+    /// Creates a path format with a non-empty separator such as `"/"`.
+    #[must_use]
+    pub const fn new(separator: &'a str) -> Self {
+        Self { separator }
+    }
+
+    #[must_use]
+    pub const fn separator(self) -> &'a str {
+        self.separator
+    }
+
+    /// Returns the path to the first loaded occurrence of `id`.
+    pub fn path_of<T, L>(self, model: &T, labels: &L, id: T::Id) -> Option<String>
+    where
+        T: TreeModel,
+        L: TreeLabelProvider<T>,
+    {
+        let mut parents = FxHashMap::with_capacity_and_hasher(model.size_hint(), FxBuildHasher);
+        let mut found = false;
+        for node in TreeWalk::forest(model).once() {
+            parents.insert(node.id, node.parent);
+            if node.id == id {
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return None;
+        }
+
+        let mut names = Vec::new();
+        let mut cursor = Some(id);
+        while let Some(id) = cursor {
+            names.push(labels.label_parts(model, id).name);
+            cursor = parents.get(&id).copied().flatten();
+        }
+        names.reverse();
+        Some(names.join(self.separator))
+    }
+
+    /// Returns the path to the first loaded occurrence of every node, in one walk.
+    pub(crate) fn index<T, L>(self, model: &T, labels: &L) -> FxHashMap<T::Id, String>
+    where
+        T: TreeModel,
+        L: TreeLabelProvider<T>,
+    {
+        let mut paths: FxHashMap<T::Id, String> =
+            FxHashMap::with_capacity_and_hasher(model.size_hint(), FxBuildHasher);
+        for node in TreeWalk::forest(model).once() {
+            let name = labels.label_parts(model, node.id).name;
+            let path = match node.parent.and_then(|parent| paths.get(&parent)) {
+                Some(parent) => [parent.as_str(), self.separator, &name].concat(),
+                None => name.into_owned(),
+            };
+            paths.insert(node.id, path);
+        }
+        paths
+    }
+
+    /// Finds the node at `path` by following loaded children from the roots.
+    pub fn resolve<T, L>(self, model: &T, labels: &L, path: &str) -> Option<T::Id>
+    where
+        T: TreeModel,
+        L: TreeLabelProvider<T>,
+    {
+        if path.is_empty() || self.separator.is_empty() {
+            return None;
+        }
+        let mut segments = path.split(self.separator);
+        let first = segments.next()?;
+        let mut current = model
+            .roots()
+            .find(|root| labels.label_parts(model, *root).name == first)?;
+        for segment in segments {
+            current = model
+                .children(current)
+                .loaded_slice()
+                .iter()
+                .copied()
+                .find(|child| labels.label_parts(model, *child).name == segment)?;
+        }
+        Some(current)
+    }
+}
+
+/// A cheap, hashable id for a path interned by a [`PathInterner`].
+///
+/// Equal paths interned by the same interner get equal ids, so models whose natural identity is
+/// a path can use `PathId` as [`TreeModel::Id`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PathId(usize);
+
+/// Interns segment paths, such as file system paths, as [`PathId`]s.
+///
+/// Paths are stored as a trie, so interning a child of an interned path stores one segment.
+/// Ids are never freed or reused: renaming a node interns new paths and leaves the old ids valid,
+/// which lets [`Self::rebase`] translate them afterwards.
+#[derive(Clone, Debug)]
+pub struct PathInterner<S> {
+    nodes: Vec<(Option<PathId>, S)>,
+    ids: FxHashMap<(Option<PathId>, S), PathId>,
+}
+
+impl<S> Default for PathInterner<S> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: FxHashMap::default(),
+        }
+    }
+}
+
+impl<S: Clone + Eq + Hash> PathInterner<S> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `segment` below `parent`, or below no parent for a top-level path.
+    pub fn child(&mut self, parent: Option<PathId>, segment: S) -> PathId {
+        if let Some(id) = self.ids.get(&(parent, segment.clone())) {
+            return *id;
+        }
+        let id = PathId(self.nodes.len());
+        self.nodes.push((parent, segment.clone()));
+        self.ids.insert((parent, segment), id);
+        id
+    }
+
+    /// Interns a whole path, returning `None` for an empty one.
+    pub fn intern(&mut self, segments: impl IntoIterator<Item = S>) -> Option<PathId> {
+        segments
+            .into_iter()
+            .fold(None, |parent, segment| Some(self.child(parent, segment)))
+    }
+
+    /// Returns the id of an already interned path without interning it.
+    #[must_use]
+    pub fn get(&self, segments: &[S]) -> Option<PathId> {
+        segments.iter().try_fold(None, |parent, segment| {
+            self.ids.get(&(parent, segment.clone())).copied().map(Some)
+        })?
+    }
+
+    /// Returns the path without its last segment.
+    #[must_use]
+    pub fn parent(&self, id: PathId) -> Option<PathId> {
+        self.nodes.get(id.0).and_then(|(parent, _)| *parent)
+    }
+
+    /// Returns the last segment of the path.
+    #[must_use]
+    pub fn segment(&self, id: PathId) -> Option<&S> {
+        self.nodes.get(id.0).map(|(_, segment)| segment)
+    }
+
+    /// Returns every segment of the path, from the top level down.
+    #[must_use]
+    pub fn segments(&self, id: PathId) -> Vec<&S> {
+        let mut segments: Vec<&S> = self
+            .ancestors(id)
+            .filter_map(|id| self.segment(id))
+            .collect();
+        segments.reverse();
+        segments
+    }
+
+    /// Returns `true` when `ancestor` is `id` or one of its prefixes.
+    #[must_use]
+    pub fn starts_with(&self, id: PathId, ancestor: PathId) -> bool {
+        self.ancestors(id).any(|id| id == ancestor)
+    }
+
+    /// Interns the path of `id` with its last segment replaced by `segment`.
+    pub fn rename(&mut self, id: PathId, segment: S) -> PathId {
+        let parent = self.parent(id);
+        self.child(parent, segment)
+    }
+
+    /// Translates `id` from below `from` to below `to`, as after renaming or moving `from`.
+    ///
+    /// Ids outside `from` are returned unchanged, so the method can be passed to
+    /// [`TreeListViewSnapshot::map_ids`](crate::TreeListViewSnapshot::map_ids) to carry view
+    /// state across a rename.
+    pub fn rebase(&mut self, id: PathId, from: PathId, to: PathId) -> PathId {
+        let mut suffix = Vec::new();
+        let mut cursor = Some(id);
+        while let Some(current) = cursor {
+            if current == from {
+                return suffix
+                    .into_iter()
+                    .rev()
+                    .fold(to, |parent, segment| self.child(Some(parent), segment));
+            }
+            let Some((parent, segment)) = self.nodes.get(current.0) else {
+                break;
+            };
+            suffix.push(segment.clone());
+            cursor = *parent;
+        }
+        id
+    }
+
+    /// Returns the number of interned paths.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Iterates over `id` and its prefixes, from the longest down.
+    fn ancestors(&self, id: PathId) -> impl Iterator<Item = PathId> + '_ {
+        std::iter::successors(Some(id).filter(|id| id.0 < self.nodes.len()), |id| {
+            self.parent(*id)
+        })
+    }
+}
@@ -1,15 +1,27 @@
 /// The crate's most commonly used types.
 pub use crate::{
-    ColumnDef, ColumnWidth, IndexedTree, NoFilter, NoSort, ProjectedNode, TreeAction,
-    TreeChangeSet, TreeChildren, TreeColumnSet, TreeColumns, TreeEditAction, TreeEditCommand,
-    TreeEditRequest, TreeEditor, TreeEvent, TreeExpansionState, TreeFilter, TreeFilterConfig,
-    TreeGlyphs, TreeHit, TreeHitRegion, TreeHorizontalScroll, TreeInsertPosition, TreeIntent,
-    TreeLabelPrefix, TreeLabelProvider, TreeLabelRenderer, TreeListView, TreeListViewSnapshot,
-    TreeListViewState, TreeListViewStyle, TreeMarkState, TreeMatchState, TreeModel, TreeModelRef,
-    TreeQuery, TreeRevision, TreeRootVisibility, TreeRowContext, TreeRowNodeState,
-    TreeRowRenderState, TreeRowRendering, TreeSelectionFallback, TreeSelectionUpdate, TreeSort,
-    TreeViewAction, tree_label_line, tree_name_cell,
+    AdaptiveColumns, ColumnDef, ColumnWidth, FnTreeModel, GroupedId, GroupedTreeModel, IndexedTree,
+    NoFilter, NoSort, OwnedChildrenCache, OwnedTreeModel, PathId, PathInterner, ProjectedNode,
+    SortedChildrenCache, SortedTreeModel, SubtreeAgg, TreeAcceleration, TreeAction,
+    TreeCellFlagging, TreeCellOverflow, TreeChangeSet, TreeChildren, TreeClickKind, TreeColumnSet,
+    TreeColumnSqueeze, TreeColumns, TreeColumnsView, TreeDetailProvider, TreeEditAction,
+    TreeEditCommand, TreeEditError, TreeEditRequest, TreeEditor, TreeEvent, TreeExpandAllScope,
+    TreeExpansionState, TreeFilter, TreeFilterConfig, TreeFollowTail, TreeGlyphs, TreeHit,
+    TreeHitPart, TreeHitRegion, TreeHorizontalScroll, TreeInsertPosition, TreeIntent,
+    TreeLabelPrefix, TreeLabelProvider, TreeLabelRenderer, TreeLabelScrollbar, TreeLeafAlignment,
+    TreeLegendEntry, TreeListView, TreeListViewSnapshot, TreeListViewState,
+    TreeListViewStateBuilder, TreeListViewStyle, TreeMarkSource, TreeMarkState, TreeMatchState,
+    TreeMeasure, TreeMeter, TreeModel, TreeModelCheck, TreeModelOwned, TreeModelRef,
+    TreeOverlayRows, TreeOverlaySelection, TreeOverscroll, TreePath, TreePlaceholders,
+    TreePositionIndicator, TreeQuery, TreeRemapReport, TreeReview, TreeRevision,
+    TreeRootVisibility, TreeRowContext, TreeRowNodeState, TreeRowNumbers, TreeRowRenderState,
+    TreeRowRendering, TreeScrollLink, TreeScrollMetrics, TreeSearchBar, TreeSelectionFallback,
+    TreeSelectionUpdate, TreeSharedState, TreeSort, TreeTheme, TreeVersionedSnapshot,
+    TreeViewAction, VecTreeModel, tree_buffer_text, tree_label_line, tree_name_cell,
 };
 
-#[cfg(feature = "keymap")]
-pub use crate::{KeymapProfile, TreeKeyBindings};
+#[cfg(feature = "keys")]
+pub use crate::{
+    KeymapProfile, TreeKey, TreeKeyBindings, TreeKeyCode, TreeKeyDispatch, TreeKeyKind,
+    TreeKeyLayer, TreeKeyModifiers,
+};
@@ -3,10 +3,11 @@ use std::hash::Hash;
 use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use smallvec::SmallVec;
 
+use crate::collections::HybridMap;
 use crate::context::{TreeExpansionState, TreeMatchState};
 use crate::model::{
-    TreeChildren, TreeFilter, TreeFilterConfig, TreeModel, TreeQuery, TreeRevision,
-    TreeRootVisibility, TreeSort,
+    TreeChildren, TreeFilter, TreeFilterConfig, TreeModel, TreePlaceholders, TreeQuery,
+    TreeRevision, TreeRootVisibility, TreeSort,
 };
 use crate::traversal::TreePostorder;
 
@@ -21,6 +22,20 @@ impl<Id> OccurrencePath<Id> {
     }
 }
 
+impl<Id: Copy> OccurrencePath<Id> {
+    /// Returns the path of a sibling occurrence under the same parent.
+    pub fn with_sibling(&self, id: Id) -> Self {
+        let mut ids = self.ids.clone();
+        if let Some(last) = ids.last_mut() {
+            *last = id;
+        }
+        Self {
+            root_parent: self.root_parent,
+            ids,
+        }
+    }
+}
+
 /// A node in the flat visible tree projection.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ProjectedNode<Id> {
@@ -32,9 +47,65 @@ pub struct ProjectedNode<Id> {
     visible_child_count: usize,
     expansion: TreeExpansionState,
     match_state: TreeMatchState,
+    matched_descendants: usize,
+    placeholder: Option<TreePlaceholder>,
+    overlay: Option<usize>,
+}
+
+/// Why a placeholder row stands in for the children of an expanded node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TreePlaceholder {
+    /// The node's loaded children are empty.
+    Empty,
+    /// Every loaded child was filtered out.
+    NoMatches,
+    /// The node was expanded before its children finished loading.
+    Loading,
 }
 
 impl<Id: Copy> ProjectedNode<Id> {
+    const fn placeholder_row(
+        placeholder: TreePlaceholder,
+        parent: Id,
+        parent_index: usize,
+        parent_level: usize,
+    ) -> Self {
+        Self {
+            id: parent,
+            parent: Some(parent),
+            parent_index: Some(parent_index),
+            level: parent_level.saturating_add(1),
+            is_last_sibling: true,
+            visible_child_count: 0,
+            expansion: TreeExpansionState::Leaf,
+            match_state: TreeMatchState::Unfiltered,
+            matched_descendants: 0,
+            placeholder: Some(placeholder),
+            overlay: None,
+        }
+    }
+
+    const fn overlay_row(
+        overlay: usize,
+        owner: Id,
+        owner_index: usize,
+        owner_level: usize,
+    ) -> Self {
+        Self {
+            id: owner,
+            parent: Some(owner),
+            parent_index: Some(owner_index),
+            level: owner_level.saturating_add(1),
+            is_last_sibling: true,
+            visible_child_count: 0,
+            expansion: TreeExpansionState::Leaf,
+            match_state: TreeMatchState::Unfiltered,
+            matched_descendants: 0,
+            placeholder: None,
+            overlay: Some(overlay),
+        }
+    }
+
     #[must_use]
     pub const fn id(self) -> Id {
         self.id
@@ -69,6 +140,12 @@ impl<Id: Copy> ProjectedNode<Id> {
         self.visible_child_count
     }
 
+    /// Returns `true` for branches, including collapsed and not yet loaded ones.
+    #[must_use]
+    pub const fn has_children(self) -> bool {
+        !matches!(self.expansion, TreeExpansionState::Leaf)
+    }
+
     #[must_use]
     pub const fn expansion(self) -> TreeExpansionState {
         self.expansion
@@ -78,6 +155,40 @@ impl<Id: Copy> ProjectedNode<Id> {
     pub const fn match_state(self) -> TreeMatchState {
         self.match_state
     }
+
+    /// Returns the number of direct filter matches below this occurrence.
+    ///
+    /// Lets collapsed ancestors show how many matches they hide; `0` when unfiltered.
+    #[must_use]
+    pub const fn matched_descendants(self) -> usize {
+        self.matched_descendants
+    }
+
+    /// Returns the kind of a synthetic row enabled by
+    /// [`TreePlaceholders::Visible`](crate::TreePlaceholders::Visible).
+    ///
+    /// A placeholder row carries the id of the expanded node it belongs to, which is also its
+    /// parent, and is never selected. It is left out of the id index and of
+    /// [`TreeListViewState::visible_ids`](crate::TreeListViewState::visible_ids).
+    #[must_use]
+    pub const fn placeholder(self) -> Option<TreePlaceholder> {
+        self.placeholder
+    }
+
+    /// Returns the index of a row pinned below a node by
+    /// [`TreeModel::overlay_rows`](crate::TreeModel::overlay_rows).
+    ///
+    /// Like a placeholder, an overlay row carries the id of the node it belongs to and is left
+    /// out of the id index.
+    #[must_use]
+    pub const fn overlay(self) -> Option<usize> {
+        self.overlay
+    }
+
+    /// Returns `true` for placeholder and overlay rows, which stand for no node of their own.
+    pub(crate) const fn is_synthetic(self) -> bool {
+        self.placeholder.is_some() || self.overlay.is_some()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -88,6 +199,7 @@ struct ProjectionStamp {
     expansion: TreeRevision,
     filter_config: TreeFilterConfig,
     root_visibility: TreeRootVisibility,
+    placeholders: TreePlaceholders,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -105,23 +217,29 @@ impl PolicyStamp {
     }
 }
 
+/// Rows visited between checks of a cancellation callback.
+const CANCEL_CHECK_INTERVAL: usize = 1024;
+
 /// A cached flat projection shared by navigation and rendering.
 pub struct TreeProjection<Id> {
     nodes: Vec<ProjectedNode<Id>>,
-    index: FxHashMap<Id, usize>,
-    filter_memo: FxHashMap<Id, bool>,
+    index: HybridMap<Id, usize>,
+    /// Direct matches in each subtree, including the node itself.
+    filter_memo: FxHashMap<Id, usize>,
     direct_matches: FxHashSet<Id>,
     stamp: Option<ProjectionStamp>,
+    generation: TreeRevision,
 }
 
 impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
     pub(crate) fn with_capacity(capacity: usize) -> Self {
         Self {
             nodes: Vec::with_capacity(capacity),
-            index: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher),
+            index: HybridMap::with_capacity(capacity),
             filter_memo: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher),
             direct_matches: FxHashSet::with_capacity_and_hasher(capacity, FxBuildHasher),
             stamp: None,
+            generation: TreeRevision::INITIAL,
         }
     }
 
@@ -157,6 +275,37 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             .copied()
     }
 
+    /// Returns the tail stack of a row, as passed to [`crate::TreeRowContext::is_tail_stack`].
+    ///
+    /// Each entry tells whether the ancestor at that depth, or the row itself in the last entry,
+    /// is the last of its siblings. Root rows have an empty stack.
+    #[must_use]
+    pub fn tail_stack(&self, index: usize) -> Option<SmallVec<[bool; 32]>> {
+        let node = self.nodes.get(index).copied()?;
+        let mut tails = self.tail_stack_before(node);
+        if node.level > 0 {
+            tails.push(node.is_last_sibling);
+        }
+        Some(tails)
+    }
+
+    /// Returns the tail stack of a row's ancestors, excluding the row itself.
+    pub(crate) fn tail_stack_before(&self, node: ProjectedNode<Id>) -> SmallVec<[bool; 32]> {
+        let mut reversed = SmallVec::<[bool; 32]>::new();
+        let mut parent = node.parent_index;
+        while let Some(parent_index) = parent {
+            let Some(parent_node) = self.nodes.get(parent_index).copied() else {
+                break;
+            };
+            if parent_node.level > 0 {
+                reversed.push(parent_node.is_last_sibling);
+            }
+            parent = parent_node.parent_index;
+        }
+        reversed.reverse();
+        reversed
+    }
+
     pub(crate) fn is_current<T, F, S>(
         &self,
         model: &T,
@@ -169,13 +318,42 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
         self.stamp == Some(Self::stamp(model, query, expansion))
     }
 
+    /// Returns `true` when the projection is only behind the model revision.
+    pub(crate) fn is_current_except_model<T, F, S>(
+        &self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        expansion: TreeRevision,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+    {
+        self.stamp.is_some_and(|stamp| {
+            stamp
+                == ProjectionStamp {
+                    model: stamp.model,
+                    ..Self::stamp(model, query, expansion)
+                }
+        })
+    }
+
+    /// Returns an empty projection that continues this one's generation, for rebuilding aside.
+    pub(crate) fn successor(&self) -> Self {
+        let mut successor = Self::with_capacity(self.nodes.len());
+        successor.generation = self.generation;
+        successor
+    }
+
+    /// Rebuilds the rows, returning `false` when `cancel` aborted and left them incomplete.
     pub(crate) fn rebuild<T, F, S, E>(
         &mut self,
         model: &T,
         query: &TreeQuery<F, S>,
         expansion_revision: TreeRevision,
         is_expanded: E,
-    ) where
+        cancel: &dyn Fn() -> bool,
+    ) -> bool
+    where
         T: TreeModel<Id = Id>,
         F: TreeFilter<T>,
         S: TreeSort<T>,
@@ -183,11 +361,14 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
     {
         self.nodes.clear();
         self.index.clear();
+        self.stamp = None;
         self.reserve(model.size_hint());
 
         let filtering = matches!(query.filter_config(), TreeFilterConfig::Enabled { .. });
         if filtering {
-            self.compute_filter_matches(model, query.filter());
+            if !self.compute_filter_matches(model, query.filter(), cancel) {
+                return false;
+            }
         } else {
             self.filter_memo.clear();
             self.direct_matches.clear();
@@ -211,8 +392,13 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             }
         }
 
+        let mut visited = 0_usize;
         while let Some(frame) = stack.pop() {
-            if filtering && !self.filter_memo.get(&frame.id).copied().unwrap_or(false) {
+            visited = visited.wrapping_add(1);
+            if visited.is_multiple_of(CANCEL_CHECK_INTERVAL) && cancel() {
+                return false;
+            }
+            if filtering && !self.is_kept(frame.id) {
                 continue;
             }
 
@@ -225,32 +411,24 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             };
             Self::sort_ids(model, query.sort(), &mut visible_children);
 
-            let expansion = match children_state {
-                TreeChildren::Leaf => TreeExpansionState::Leaf,
-                TreeChildren::Unloaded => TreeExpansionState::Unloaded,
-                TreeChildren::Loading => TreeExpansionState::Loading,
-                TreeChildren::Loaded(_) if visible_children.is_empty() => TreeExpansionState::Leaf,
-                TreeChildren::Loaded(_) => match query.filter_config() {
-                    TreeFilterConfig::Enabled { auto_expand: true } => {
-                        TreeExpansionState::ForcedByFilter
-                    }
-                    TreeFilterConfig::Disabled
-                    | TreeFilterConfig::Enabled { auto_expand: false } => {
-                        if is_expanded(frame.parent, frame.id) {
-                            TreeExpansionState::Expanded
-                        } else {
-                            TreeExpansionState::Collapsed
-                        }
-                    }
-                },
-            };
-            let match_state = if !filtering {
-                TreeMatchState::Unfiltered
-            } else if self.direct_matches.contains(&frame.id) {
-                TreeMatchState::Direct
-            } else {
-                TreeMatchState::Ancestor
-            };
+            let mut expansion = Self::expansion(
+                children_state,
+                visible_children.is_empty(),
+                query.filter_config(),
+                || is_expanded(frame.parent, frame.id),
+            );
+            let placeholder = (visible_children.is_empty()
+                && query.placeholders() == TreePlaceholders::Visible
+                && is_expanded(frame.parent, frame.id))
+            .then(|| Self::placeholder(children_state))
+            .flatten();
+            if matches!(
+                placeholder,
+                Some(TreePlaceholder::Empty | TreePlaceholder::NoMatches)
+            ) {
+                expansion = TreeExpansionState::Expanded;
+            }
+            let (match_state, matched_descendants) = self.match_state(filtering, frame.id);
 
             let index = self.nodes.len();
             self.nodes.push(ProjectedNode {
@@ -262,8 +440,12 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
                 visible_child_count: visible_children.len(),
                 expansion,
                 match_state,
+                matched_descendants,
+                placeholder: None,
+                overlay: None,
             });
-            self.index.entry(frame.id).or_insert(index);
+            self.index.insert_if_absent(frame.id, index);
+            self.push_synthetic_rows(index, model.overlay_rows(frame.id), placeholder);
 
             if expansion.is_expanded() {
                 Self::push_children(
@@ -277,6 +459,93 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
         }
 
         self.stamp = Some(Self::stamp(model, query, expansion_revision));
+        self.generation.advance();
+        true
+    }
+
+    /// Pushes the overlay rows and the placeholder that follow the row at `owner`.
+    fn push_synthetic_rows(
+        &mut self,
+        owner: usize,
+        overlays: usize,
+        placeholder: Option<TreePlaceholder>,
+    ) {
+        let node = self.nodes[owner];
+        for overlay in 0..overlays {
+            self.nodes.push(ProjectedNode::overlay_row(
+                overlay, node.id, owner, node.level,
+            ));
+        }
+        if let Some(placeholder) = placeholder {
+            self.nodes.push(ProjectedNode::placeholder_row(
+                placeholder,
+                node.id,
+                owner,
+                node.level,
+            ));
+        }
+    }
+
+    /// Returns how a kept node relates to the filter and how many direct matches lie below it.
+    fn match_state(&self, filtering: bool, id: Id) -> (TreeMatchState, usize) {
+        let direct = self.direct_matches.contains(&id);
+        let match_state = if !filtering {
+            TreeMatchState::Unfiltered
+        } else if direct {
+            TreeMatchState::Direct
+        } else {
+            TreeMatchState::Ancestor
+        };
+        let matched_descendants = self
+            .filter_memo
+            .get(&id)
+            .map_or(0, |count| count - usize::from(direct));
+        (match_state, matched_descendants)
+    }
+
+    /// Returns the placeholder for an expanded node without visible children.
+    const fn placeholder(children: TreeChildren<'_, Id>) -> Option<TreePlaceholder> {
+        match children {
+            TreeChildren::Loaded([]) => Some(TreePlaceholder::Empty),
+            TreeChildren::Loaded(_) => Some(TreePlaceholder::NoMatches),
+            TreeChildren::Unloaded | TreeChildren::Loading => Some(TreePlaceholder::Loading),
+            TreeChildren::Leaf => None,
+        }
+    }
+
+    fn expansion(
+        children: TreeChildren<'_, Id>,
+        no_visible_children: bool,
+        filter_config: TreeFilterConfig,
+        is_expanded: impl FnOnce() -> bool,
+    ) -> TreeExpansionState {
+        match children {
+            TreeChildren::Leaf => TreeExpansionState::Leaf,
+            TreeChildren::Unloaded => TreeExpansionState::Unloaded,
+            TreeChildren::Loading => TreeExpansionState::Loading,
+            TreeChildren::Loaded(_) if no_visible_children => TreeExpansionState::Leaf,
+            TreeChildren::Loaded(_) => match filter_config {
+                TreeFilterConfig::Enabled {
+                    auto_expand: true, ..
+                } => TreeExpansionState::ForcedByFilter,
+                TreeFilterConfig::Disabled
+                | TreeFilterConfig::Enabled {
+                    auto_expand: false, ..
+                } => {
+                    if is_expanded() {
+                        TreeExpansionState::Expanded
+                    } else {
+                        TreeExpansionState::Collapsed
+                    }
+                }
+            },
+        }
+    }
+
+    /// Returns a counter advanced by every rebuild.
+    #[must_use]
+    pub const fn generation(&self) -> TreeRevision {
+        self.generation
     }
 
     fn stamp<T, F, S>(
@@ -294,6 +563,7 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             expansion,
             filter_config: query.filter_config(),
             root_visibility: query.root_visibility(),
+            placeholders: query.placeholders(),
         }
     }
 
@@ -302,7 +572,7 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             return;
         }
         reserve_to(&mut self.nodes, hint);
-        reserve_map_to(&mut self.index, hint);
+        self.index.reserve(hint);
         reserve_map_to(&mut self.filter_memo, hint);
         let extra = hint.saturating_sub(self.direct_matches.len());
         self.direct_matches.reserve(extra);
@@ -318,29 +588,93 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
             TreeFilterConfig::Enabled { .. } => children
                 .iter()
                 .copied()
-                .filter(|child| self.filter_memo.get(child).copied().unwrap_or(false))
+                .filter(|child| self.is_kept(*child))
                 .collect(),
         }
     }
 
-    fn compute_filter_matches<T, F>(&mut self, model: &T, filter: &F)
+    /// Returns every node this build's filter keeps, in displayed order as if every loaded branch
+    /// were expanded.
+    ///
+    /// A node shared by several parents is listed once, at its first occurrence, and the
+    /// subtrees below its later occurrences are skipped.
+    pub(crate) fn displayable_ids<T, F, S>(&self, model: &T, query: &TreeQuery<F, S>) -> Vec<Id>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        let mut roots: SmallVec<[Id; 8]> = model.roots().collect();
+        Self::sort_ids(model, query.sort(), &mut roots);
+        let mut stack: Vec<Id> = Vec::with_capacity(model.size_hint().min(1024));
+        match query.root_visibility() {
+            TreeRootVisibility::Visible => {
+                stack.extend(self.visible_children(query, &roots).into_iter().rev());
+            }
+            TreeRootVisibility::Hidden => {
+                for root in roots.iter().rev().copied() {
+                    let mut children =
+                        self.visible_children(query, model.children(root).loaded_slice());
+                    Self::sort_ids(model, query.sort(), &mut children);
+                    stack.extend(children.into_iter().rev());
+                }
+            }
+        }
+
+        let mut visited = FxHashSet::default();
+        let mut ids = Vec::with_capacity(model.size_hint());
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            ids.push(id);
+            let mut children = self.visible_children(query, model.children(id).loaded_slice());
+            Self::sort_ids(model, query.sort(), &mut children);
+            stack.extend(children.into_iter().rev());
+        }
+        ids
+    }
+
+    fn compute_filter_matches<T, F>(
+        &mut self,
+        model: &T,
+        filter: &F,
+        cancel: &dyn Fn() -> bool,
+    ) -> bool
     where
         T: TreeModel<Id = Id>,
         F: TreeFilter<T>,
     {
         self.filter_memo.clear();
         self.direct_matches.clear();
-        for node in TreePostorder::forest(model) {
+        for (visited, node) in TreePostorder::forest(model).enumerate() {
+            if visited.is_multiple_of(CANCEL_CHECK_INTERVAL) && cancel() {
+                return false;
+            }
             let direct = filter.is_match(model, node.id);
             if direct {
                 self.direct_matches.insert(node.id);
             }
-            let descendant = node
-                .children
-                .iter()
-                .any(|child| self.filter_memo.get(child).copied().unwrap_or(false));
-            self.filter_memo.insert(node.id, direct || descendant);
+            let descendants = node.children.iter().fold(0_usize, |count, child| {
+                count.saturating_add(self.filter_memo.get(child).copied().unwrap_or(0))
+            });
+            let count = descendants.saturating_add(usize::from(direct));
+            if count > 0 {
+                self.filter_memo.insert(node.id, count);
+            }
         }
+        true
+    }
+
+    /// Returns the filter configuration of the last build.
+    pub(crate) fn filter_config(&self) -> TreeFilterConfig {
+        self.stamp
+            .map_or(TreeFilterConfig::Disabled, |stamp| stamp.filter_config)
+    }
+
+    /// Returns `true` when the last filtered build kept `id`.
+    pub(crate) fn is_kept(&self, id: Id) -> bool {
+        self.filter_memo.contains_key(&id)
     }
 
     fn sort_ids<T, S>(model: &T, sort: &S, ids: &mut [Id])
@@ -353,6 +687,31 @@ impl<Id: Copy + Eq + Hash> TreeProjection<Id> {
         }
     }
 
+    /// Returns the ids of a row's visible siblings, nearest first, after or before the row.
+    ///
+    /// The walk stops where it leaves the parent's subtree.
+    pub(crate) fn siblings_from(
+        &self,
+        index: usize,
+        forward: bool,
+    ) -> impl Iterator<Item = Id> + '_ {
+        let node = self.nodes.get(index).copied();
+        let (level, parent_index) = node.map_or((0, None), |node| (node.level, node.parent_index));
+        let (after, before) = match node {
+            Some(_) if forward => (&self.nodes[index + 1..], &[][..]),
+            Some(_) => (&[][..], &self.nodes[..index]),
+            None => (&[][..], &[][..]),
+        };
+        after
+            .iter()
+            .chain(before.iter().rev())
+            .take_while(move |other| other.level >= level)
+            .filter(move |other| {
+                other.level == level && other.parent_index == parent_index && !other.is_synthetic()
+            })
+            .map(|other| other.id)
+    }
+
     pub(crate) fn occurrence_path(&self, index: usize) -> Option<OccurrencePath<Id>> {
         let mut ids = SmallVec::new();
         let mut cursor = Some(index);
@@ -0,0 +1,332 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Position, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+
+use crate::glyphs::TreeLabelProvider;
+#[cfg(feature = "keys")]
+use crate::key::{TreeKey, TreeKeyCode, TreeKeyKind, TreeKeyModifiers};
+use crate::model::{TreeFilter, TreeFilterConfig, TreeModel, TreeRevision};
+
+/// A filter that matches nodes whose label name contains a case-insensitive needle.
+#[derive(Clone, Debug)]
+pub struct TextFilter<L> {
+    labels: L,
+    needle: String,
+}
+
+impl<L> TextFilter<L> {
+    /// Creates a filter over the names produced by `labels`.
+    #[must_use]
+    pub fn new(labels: L, needle: &str) -> Self {
+        Self {
+            labels,
+            needle: needle.to_lowercase(),
+        }
+    }
+}
+
+impl<T, L> TreeFilter<T> for TextFilter<L>
+where
+    T: TreeModel,
+    L: TreeLabelProvider<T>,
+{
+    fn is_match(&self, model: &T, id: T::Id) -> bool {
+        let name = self.labels.label_parts(model, id).name;
+        name.to_lowercase().contains(&self.needle)
+    }
+}
+
+/// A single-line search input that pairs with the tree.
+///
+/// It owns the query text and its cursor, renders as a prompt followed by the text, and
+/// produces a [`TextFilter`] for [`TreeQuery::with_filter`](crate::TreeQuery::with_filter)
+/// together with [`Self::filter_config`] and [`Self::revision`].
+#[derive(Clone, Debug)]
+pub struct TreeSearchBar<'a> {
+    query: String,
+    cursor: usize,
+    revision: TreeRevision,
+    prompt: Span<'a>,
+    style: Style,
+    cursor_style: Style,
+}
+
+impl<'a> TreeSearchBar<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            cursor: 0,
+            revision: TreeRevision::INITIAL,
+            prompt: Span::raw("/"),
+            style: Style::default(),
+            cursor_style: Style::default(),
+        }
+    }
+
+    /// Replaces the text drawn before the query.
+    #[must_use]
+    pub fn with_prompt(mut self, prompt: impl Into<Span<'a>>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    #[must_use]
+    pub const fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Patches the cell under the cursor, for applications that hide the terminal cursor.
+    #[must_use]
+    pub const fn with_cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    #[must_use]
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the cursor as a byte offset into [`Self::query`].
+    #[must_use]
+    pub const fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the revision of the query text, which advances with every edit.
+    #[must_use]
+    pub const fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+
+    /// Replaces the query and moves the cursor to its end.
+    pub fn set_query(&mut self, query: impl Into<String>) -> bool {
+        let query = query.into();
+        self.cursor = query.len();
+        if self.query == query {
+            return false;
+        }
+        self.query = query;
+        self.revision.advance();
+        true
+    }
+
+    pub fn clear(&mut self) -> bool {
+        self.set_query("")
+    }
+
+    /// Returns a filter for the current query.
+    #[must_use]
+    pub fn filter<L>(&self, labels: L) -> TextFilter<L> {
+        TextFilter::new(labels, &self.query)
+    }
+
+    /// Enables filtering while the query is not empty.
+    #[must_use]
+    pub const fn filter_config(&self) -> TreeFilterConfig {
+        if self.query.is_empty() {
+            TreeFilterConfig::Disabled
+        } else {
+            TreeFilterConfig::enabled()
+        }
+    }
+
+    pub fn insert_char(&mut self, character: char) {
+        self.query.insert(self.cursor, character);
+        self.cursor += character.len_utf8();
+        self.revision.advance();
+    }
+
+    /// Deletes the character before the cursor.
+    pub fn delete_backward(&mut self) -> bool {
+        let Some(previous) = self.previous_boundary() else {
+            return false;
+        };
+        self.query.remove(previous);
+        self.cursor = previous;
+        self.revision.advance();
+        true
+    }
+
+    /// Deletes the character under the cursor.
+    pub fn delete_forward(&mut self) -> bool {
+        if self.cursor == self.query.len() {
+            return false;
+        }
+        self.query.remove(self.cursor);
+        self.revision.advance();
+        true
+    }
+
+    pub fn move_cursor_left(&mut self) -> bool {
+        self.previous_boundary()
+            .is_some_and(|previous| self.move_cursor_to(previous))
+    }
+
+    pub fn move_cursor_right(&mut self) -> bool {
+        self.next_boundary()
+            .is_some_and(|next| self.move_cursor_to(next))
+    }
+
+    pub const fn move_cursor_to_start(&mut self) -> bool {
+        self.move_cursor_to(0)
+    }
+
+    pub const fn move_cursor_to_end(&mut self) -> bool {
+        self.move_cursor_to(self.query.len())
+    }
+
+    #[cfg(feature = "keys")]
+    /// Edits the query with a key and returns `true` when the key was consumed.
+    ///
+    /// Printable characters, `Backspace`, `Delete`, `Left`, `Right`, `Home`, `End`, and the
+    /// `Ctrl+A`, `Ctrl+E`, and `Ctrl+U` shortcuts are consumed. Other keys, such as `Enter`,
+    /// `Esc`, and vertical arrows, are left for the application and the tree.
+    pub fn handle_key(&mut self, key: impl Into<TreeKey>) -> bool {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return false;
+        }
+        match (key.code, key.modifiers) {
+            (TreeKeyCode::Char(character), TreeKeyModifiers::NONE | TreeKeyModifiers::SHIFT) => {
+                self.insert_char(character);
+            }
+            (TreeKeyCode::Backspace, _) => {
+                self.delete_backward();
+            }
+            (TreeKeyCode::Delete, _) => {
+                self.delete_forward();
+            }
+            (TreeKeyCode::Left, _) => {
+                self.move_cursor_left();
+            }
+            (TreeKeyCode::Right, _) => {
+                self.move_cursor_right();
+            }
+            (TreeKeyCode::Home, _) | (TreeKeyCode::Char('a'), TreeKeyModifiers::CONTROL) => {
+                self.move_cursor_to_start();
+            }
+            (TreeKeyCode::End, _) | (TreeKeyCode::Char('e'), TreeKeyModifiers::CONTROL) => {
+                self.move_cursor_to_end();
+            }
+            (TreeKeyCode::Char('u'), TreeKeyModifiers::CONTROL) => {
+                self.clear();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns where the terminal cursor belongs when the bar is rendered into `area`, for
+    /// `Frame::set_cursor_position`.
+    #[must_use]
+    pub fn cursor_position(&self, area: Rect) -> Position {
+        let (_, offset) = self.visible_query(area.width);
+        Position::new(area.x.saturating_add(offset), area.y)
+    }
+
+    /// Returns the query suffix that keeps the cursor inside `width`, and the cursor's offset
+    /// from the left edge.
+    fn visible_query(&self, width: u16) -> (&str, u16) {
+        let prompt = u16::try_from(self.prompt.width()).unwrap_or(u16::MAX);
+        let room = usize::from(width.saturating_sub(prompt).saturating_sub(1));
+        let mut start = 0;
+        while Span::raw(&self.query[start..self.cursor]).width() > room {
+            start = self.query[start..]
+                .chars()
+                .next()
+                .map_or(self.cursor, |character| start + character.len_utf8());
+        }
+        let before = Span::raw(&self.query[start..self.cursor]).width();
+        let offset = prompt.saturating_add(u16::try_from(before).unwrap_or(u16::MAX));
+        (&self.query[start..], offset.min(width.saturating_sub(1)))
+    }
+
+    const fn move_cursor_to(&mut self, cursor: usize) -> bool {
+        let changed = self.cursor != cursor;
+        self.cursor = cursor;
+        changed
+    }
+
+    fn previous_boundary(&self) -> Option<usize> {
+        self.query[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(index, _)| index)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.query[self.cursor..]
+            .chars()
+            .next()
+            .map(|character| self.cursor + character.len_utf8())
+    }
+}
+
+impl Default for TreeSearchBar<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for &TreeSearchBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let (query, offset) = self.visible_query(area.width);
+        Line::from(vec![self.prompt.clone(), Span::raw(query)])
+            .style(self.style)
+            .render(area, buf);
+        if let Some(cell) = buf.cell_mut((area.x.saturating_add(offset), area.y)) {
+            cell.set_style(self.cursor_style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::IndexedTree;
+    use crate::glyphs::TreeLabelPrefix;
+
+    struct Names;
+
+    impl TreeLabelProvider<IndexedTree<'_>> for Names {
+        fn label_parts<'a>(&'a self, _: &'a IndexedTree<'_>, id: usize) -> TreeLabelPrefix<'a> {
+            TreeLabelPrefix::borrowed(["Alpha", "beta", "alphabet"][id])
+        }
+    }
+
+    #[test]
+    fn edits_track_the_cursor_and_filter_by_label() {
+        let mut bar = TreeSearchBar::new();
+        for character in "alé".chars() {
+            bar.insert_char(character);
+        }
+        assert!(bar.move_cursor_left());
+        assert!(bar.delete_backward());
+        assert_eq!((bar.query(), bar.cursor()), ("aé", 1));
+        assert!(bar.delete_forward());
+        assert!(!bar.delete_forward());
+        bar.insert_char('L');
+        assert_eq!(bar.query(), "aL");
+        assert!(bar.filter_config() != TreeFilterConfig::Disabled);
+
+        let children = vec![vec![1, 2], vec![], vec![]];
+        let tree = IndexedTree::new([0], &children, TreeRevision::INITIAL).expect("valid tree");
+        let filter = bar.filter(Names);
+        assert!(filter.is_match(&tree, 0));
+        assert!(!filter.is_match(&tree, 1));
+        assert!(filter.is_match(&tree, 2));
+
+        let revision = bar.revision();
+        assert!(bar.clear());
+        assert!(bar.revision() > revision);
+        assert_eq!(bar.filter_config(), TreeFilterConfig::Disabled);
+    }
+}
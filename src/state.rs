@@ -3,21 +3,49 @@ use std::ops::Deref;
 
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
+use ratatui::widgets::TableState;
+use smallvec::SmallVec;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::action::TreeEditRequest;
+use crate::collections::{HybridMap, HybridSet};
+use crate::columns::AdaptiveColumns;
 use crate::context::TreeMarkState;
 use crate::model::TreeRevision;
 use crate::projection::{ProjectedNode, TreeProjection};
 
-pub use hit::{TreeHit, TreeHitRegion};
+pub use acceleration::TreeAcceleration;
+pub use builder::TreeListViewStateBuilder;
+pub use hit::{TreeHit, TreeHitPart, TreeHitRegion};
+pub use link::{TreeScrollLink, TreeScrollMetrics};
+pub use migrate::{TreeSnapshotV1, TreeSnapshotV2, TreeSnapshotV3, TreeVersionedSnapshot};
+pub use paths::TreeRemapReport;
+pub use shared::TreeSharedState;
 
+mod acceleration;
 mod actions;
+mod anchor;
+mod builder;
+mod cell_flags;
+mod column_rows;
+mod confirm;
+mod consistency;
+mod details;
+mod flash;
 pub mod hit;
+mod link;
 mod marks;
+mod migrate;
+mod multi_select;
 mod navigation;
+mod paths;
+mod resize;
+mod review;
+mod shared;
+mod throttle;
+mod type_ahead;
 mod visibility;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -33,14 +61,14 @@ impl<Id> ExpansionPath<Id> {
 }
 
 struct RevisionedSet<T> {
-    values: FxHashSet<T>,
+    values: HybridSet<T>,
     revision: TreeRevision,
 }
 
 impl<T: Eq + Hash> RevisionedSet<T> {
     fn with_capacity(capacity: usize) -> Self {
         Self {
-            values: FxHashSet::with_capacity_and_hasher(capacity, FxBuildHasher),
+            values: HybridSet::with_capacity(capacity),
             revision: TreeRevision::INITIAL,
         }
     }
@@ -49,7 +77,7 @@ impl<T: Eq + Hash> RevisionedSet<T> {
         self.revision
     }
 
-    fn mutate(&mut self, mutation: impl FnOnce(&mut FxHashSet<T>) -> bool) -> bool {
+    fn mutate(&mut self, mutation: impl FnOnce(&mut HybridSet<T>) -> bool) -> bool {
         let changed = mutation(&mut self.values);
         if changed {
             self.revision.advance();
@@ -86,7 +114,7 @@ impl<T: Eq + Hash> RevisionedSet<T> {
         })
     }
 
-    fn replace(&mut self, values: FxHashSet<T>) -> bool {
+    fn replace(&mut self, values: HybridSet<T>) -> bool {
         self.mutate(|current| {
             if *current == values {
                 false
@@ -99,31 +127,143 @@ impl<T: Eq + Hash> RevisionedSet<T> {
 }
 
 impl<T> Deref for RevisionedSet<T> {
-    type Target = FxHashSet<T>;
+    type Target = HybridSet<T>;
 
     fn deref(&self) -> &Self::Target {
         &self.values
     }
 }
 
+/// Everything that affects the rendered frame, compared by
+/// [`TreeListViewState::take_render_dirty`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct RenderStamp<Id> {
+    projection: TreeRevision,
+    expansion: TreeRevision,
+    marks: TreeRevision,
+    multi_selected: TreeRevision,
+    cell_flags: TreeRevision,
+    anchor: Option<Id>,
+    selected: Option<Id>,
+    selected_row: Option<usize>,
+    selected_column: Option<usize>,
+    header_selected: bool,
+    offset: usize,
+    horizontal_offset: u16,
+    draw_lines: bool,
+    flashes: u64,
+    column_widths: u64,
+    pending_confirmation: Option<TreeEditRequest<Id>>,
+    details: Option<Id>,
+    pending_count: Option<usize>,
+    pending_chord: TreeRevision,
+}
+
+/// Whether the viewport follows rows appended at the end of the projection, like `tail -f`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeFollowTail {
+    #[default]
+    Disabled,
+    Enabled,
+}
+
+/// Whether the selection may rest on rows pinned below nodes by
+/// [`TreeModel::overlay_rows`](crate::TreeModel::overlay_rows).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeOverlaySelection {
+    /// Moving onto an overlay row continues to the next node row in the same direction.
+    #[default]
+    Skipped,
+    /// Overlay rows can be selected, but marks, anchors, and edits ignore them.
+    Selectable,
+}
+
+/// Whether [`ToggleMark`](crate::TreeViewAction::ToggleMark) flags single cells, turning the
+/// tree into a selection matrix such as features by targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeCellFlagging {
+    /// `ToggleMark` always marks the node.
+    #[default]
+    Disabled,
+    /// `ToggleMark` flags the selected cell while a data column is selected; row renderers read
+    /// the flags from [`TreeRowContext::is_cell_flagged`](crate::TreeRowContext::is_cell_flagged).
+    Enabled,
+}
+
+/// Which rows [`SelectNext`](crate::TreeViewAction::SelectNext) and
+/// [`SelectPrev`](crate::TreeViewAction::SelectPrev) step between, see
+/// [`TreeListViewState::set_review`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeReview {
+    /// Every row.
+    #[default]
+    Disabled,
+    /// Rows whose [mark](TreeListViewState::mark_state) is [`TreeMarkState::Marked`].
+    Marked,
+    /// Rows that match the filter themselves, or every row while nothing is filtered.
+    Matched,
+}
+
+/// Whether keys act on the rows or on the selected column's header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Rows,
+    Header,
+}
+
 /// Persistent view state and its derived caches.
 pub struct TreeListViewState<Id> {
     projection: TreeProjection<Id>,
     selected: Option<Id>,
     selected_row: Option<usize>,
     selection_needs_visibility: bool,
+    child_memory: HybridMap<Id, Id>,
+    scroll_policy_override: Option<crate::style::TreeScrollPolicy>,
     offset: usize,
+    follow_tail: TreeFollowTail,
+    overlay_selection: TreeOverlaySelection,
+    end_threshold: Option<usize>,
+    end_reported: Option<usize>,
+    viewport_height: usize,
+    rendered_len: usize,
     selected_column: Option<usize>,
     column_needs_visibility: bool,
+    focus: Focus,
     horizontal_offset: u16,
     expanded: RevisionedSet<ExpansionPath<Id>>,
     manual_marked: RevisionedSet<Id>,
-    mark_states: FxHashMap<Id, TreeMarkState>,
+    multi_selected: RevisionedSet<Id>,
+    cell_flags: RevisionedSet<(Id, usize)>,
+    cell_flagging: TreeCellFlagging,
+    review: TreeReview,
+    review_rows: TreeReview,
+    selection_anchor: Option<Id>,
+    anchor: Option<Id>,
+    mark_states: HybridMap<Id, TreeMarkState>,
     mark_stamp: Option<(TreeRevision, TreeRevision)>,
     draw_lines: bool,
+    flashes: SmallVec<[(Id, u16); 4]>,
+    flash_revision: u64,
+    column_widths: AdaptiveColumns,
+    column_widths_revision: u64,
+    recursive_budget: Option<usize>,
+    acceleration: Option<TreeAcceleration>,
+    acceleration_tracker: acceleration::AccelerationTracker,
+    confirmations: u8,
+    pending_confirmation: Option<TreeEditRequest<Id>>,
+    details: Option<Id>,
+    missing: consistency::MissingIds<Id>,
+    rendered_stamp: Option<RenderStamp<Id>>,
+    scroll_link: Option<TreeScrollLink>,
+    rebuild_throttle: throttle::RebuildThrottle,
+    shared_stamp: Option<shared::SharedStamp>,
+    type_ahead: type_ahead::TypeAhead,
+    #[cfg(feature = "keymap")]
+    last_click: Option<(ratatui::layout::Position, std::time::Instant)>,
+    column_rows: column_rows::ColumnRows,
     pub(crate) hit_map: hit::TreeHitMap,
     pub(crate) render_buffer: Buffer,
-    #[cfg(feature = "keymap")]
+    #[cfg(feature = "keys")]
     keymap: crate::keymap::TreeKeyBindings,
 }
 
@@ -142,18 +282,53 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
             selected: None,
             selected_row: None,
             selection_needs_visibility: false,
+            child_memory: HybridMap::with_capacity(0),
+            scroll_policy_override: None,
             offset: 0,
+            follow_tail: TreeFollowTail::Disabled,
+            overlay_selection: TreeOverlaySelection::Skipped,
+            end_threshold: None,
+            end_reported: None,
+            viewport_height: 0,
+            rendered_len: 0,
             selected_column: None,
             column_needs_visibility: false,
+            focus: Focus::Rows,
             horizontal_offset: 0,
             expanded: RevisionedSet::with_capacity(capacity),
             manual_marked: RevisionedSet::with_capacity(capacity),
-            mark_states: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher),
+            multi_selected: RevisionedSet::with_capacity(0),
+            cell_flags: RevisionedSet::with_capacity(0),
+            cell_flagging: TreeCellFlagging::Disabled,
+            review: TreeReview::Disabled,
+            review_rows: TreeReview::Marked,
+            selection_anchor: None,
+            anchor: None,
+            mark_states: HybridMap::with_capacity(capacity),
             mark_stamp: None,
             draw_lines: true,
+            flashes: SmallVec::new(),
+            flash_revision: 0,
+            column_widths: AdaptiveColumns::default(),
+            column_widths_revision: 0,
+            recursive_budget: None,
+            acceleration: None,
+            acceleration_tracker: acceleration::AccelerationTracker::default(),
+            confirmations: 0,
+            pending_confirmation: None,
+            details: None,
+            missing: consistency::MissingIds::default(),
+            rendered_stamp: None,
+            scroll_link: None,
+            rebuild_throttle: throttle::RebuildThrottle::default(),
+            shared_stamp: None,
+            type_ahead: type_ahead::TypeAhead::default(),
+            #[cfg(feature = "keymap")]
+            last_click: None,
+            column_rows: column_rows::ColumnRows::default(),
             hit_map: hit::TreeHitMap::default(),
             render_buffer: Buffer::empty(Rect::ZERO),
-            #[cfg(feature = "keymap")]
+            #[cfg(feature = "keys")]
             keymap: crate::keymap::TreeKeyBindings::new(),
         }
     }
@@ -188,6 +363,8 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
             offset: self.offset,
             horizontal_offset: self.horizontal_offset,
             draw_lines: self.draw_lines,
+            column_widths: self.column_widths.widths().to_vec(),
+            cell_flags: self.cell_flags.iter().copied().collect(),
         }
     }
 
@@ -210,6 +387,42 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.offset = snapshot.offset;
         self.horizontal_offset = snapshot.horizontal_offset;
         self.draw_lines = snapshot.draw_lines;
+        self.set_column_widths(AdaptiveColumns::from_widths(snapshot.column_widths));
+        self.cell_flags
+            .replace(snapshot.cell_flags.into_iter().collect());
+    }
+
+    /// Creates state from an application-owned [`TableState`], adopting its offset, selected row,
+    /// and selected column.
+    ///
+    /// The selected row indexes the first projection built for this state and is kept unless the
+    /// query's selection fallback is [`TreeSelectionFallback::Clear`].
+    ///
+    /// [`TreeSelectionFallback::Clear`]: crate::TreeSelectionFallback::Clear
+    #[must_use]
+    pub fn from_table_state(table: TableState) -> Self {
+        let mut state = Self::new();
+        state.offset = table.offset();
+        state.selected_row = table.selected();
+        state.selection_needs_visibility = table.selected().is_some();
+        state.selected_column = table.selected_column();
+        state.column_needs_visibility = table.selected_column().is_some();
+        state
+    }
+
+    /// Returns a [`TableState`] with the current offset, selected row, and selected column.
+    #[must_use]
+    pub fn table_state(&self) -> TableState {
+        TableState::new()
+            .with_offset(self.offset)
+            .with_selected(self.selected_row)
+            .with_selected_column(self.selected_column)
+    }
+
+    /// Splits the state into its table position and its persistent part.
+    #[must_use]
+    pub fn into_parts(self) -> (TableState, TreeListViewSnapshot<Id>) {
+        (self.table_state(), self.snapshot())
     }
 
     #[must_use]
@@ -221,6 +434,64 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.draw_lines = draw;
     }
 
+    /// Returns the acceleration applied by [`Self::handle_action_at`].
+    #[must_use]
+    pub const fn acceleration(&self) -> Option<TreeAcceleration> {
+        self.acceleration
+    }
+
+    /// Enables or disables acceleration of repeated navigation actions.
+    pub const fn set_acceleration(&mut self, acceleration: Option<TreeAcceleration>) {
+        self.acceleration = acceleration;
+        self.acceleration_tracker.reset();
+    }
+
+    /// Returns `true` when the state changed since the last render or call, then clears the flag.
+    ///
+    /// Covers selection, expansion, marks, scrolling, and prompts. Model and query changes are
+    /// detected once the projection is synchronized, for example by
+    /// [`Self::ensure_projection`] or [`Self::handle_action`].
+    pub fn take_render_dirty(&mut self) -> bool {
+        let stamp = self.render_stamp();
+        let dirty = self.rendered_stamp != Some(stamp);
+        self.rendered_stamp = Some(stamp);
+        dirty
+    }
+
+    pub(crate) const fn mark_rendered(&mut self) {
+        self.rendered_stamp = Some(self.render_stamp());
+    }
+
+    const fn render_stamp(&self) -> RenderStamp<Id> {
+        RenderStamp {
+            projection: self.projection.generation(),
+            expansion: self.expanded.revision(),
+            marks: self.manual_marked.revision(),
+            multi_selected: self.multi_selected.revision(),
+            cell_flags: self.cell_flags.revision(),
+            anchor: self.anchor,
+            selected: self.selected,
+            selected_row: self.selected_row,
+            selected_column: self.selected_column,
+            header_selected: self.is_header_selected(),
+            offset: self.offset,
+            horizontal_offset: self.horizontal_offset,
+            draw_lines: self.draw_lines,
+            flashes: self.flash_revision,
+            column_widths: self.column_widths_revision,
+            pending_confirmation: self.pending_confirmation,
+            details: self.details,
+            #[cfg(feature = "keys")]
+            pending_count: self.keymap.pending_count(),
+            #[cfg(not(feature = "keys"))]
+            pending_count: None,
+            #[cfg(feature = "keys")]
+            pending_chord: self.keymap.chord_revision(),
+            #[cfg(not(feature = "keys"))]
+            pending_chord: TreeRevision::INITIAL,
+        }
+    }
+
     pub(crate) fn is_expanded(&self, parent: Option<Id>, id: Id) -> bool {
         self.expanded.contains(&ExpansionPath::new(parent, id))
     }
@@ -229,6 +500,13 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.mark_states.get(&id).copied().unwrap_or_default()
     }
 
+    /// Returns the selected id unless the selection rests on a placeholder or overlay row, which
+    /// carries the id of the node it belongs to.
+    pub(crate) fn selected_target(&self) -> Option<Id> {
+        self.selected
+            .filter(|_| self.selected_node().is_none_or(|node| !node.is_synthetic()))
+    }
+
     pub(crate) fn selected_node(&self) -> Option<ProjectedNode<Id>> {
         let selected = self.selected?;
         self.selected_row
@@ -237,7 +515,21 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
             .filter(|node| node.id() == selected)
     }
 
-    #[cfg(feature = "keymap")]
+    #[cfg(feature = "keys")]
+    /// Returns the numeric prefix typed so far through [`Self::handle_key`].
+    #[must_use]
+    pub const fn pending_count(&self) -> Option<usize> {
+        self.keymap.pending_count()
+    }
+
+    #[cfg(feature = "keys")]
+    /// Returns the keys of a chord typed so far through [`Self::handle_key`].
+    #[must_use]
+    pub fn pending_chord(&self) -> &[crate::key::TreeKey] {
+        self.keymap.pending_chord()
+    }
+
+    #[cfg(feature = "keys")]
     /// Returns the mutable key bindings.
     pub const fn keymap_mut(&mut self) -> &mut crate::keymap::TreeKeyBindings {
         &mut self.keymap
@@ -252,6 +544,7 @@ impl<Id: Copy + Eq + Hash> Default for TreeListViewState<Id> {
 
 /// The serializable persistent part of view state.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "Id: Deserialize<'de>")))]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TreeListViewSnapshot<Id> {
     pub expanded: Vec<(Option<Id>, Id)>,
@@ -261,4 +554,49 @@ pub struct TreeListViewSnapshot<Id> {
     pub offset: usize,
     pub horizontal_offset: u16,
     pub draw_lines: bool,
+    /// [`AdaptiveColumns`] overrides by column index.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub column_widths: Vec<Option<u16>>,
+    /// Flagged `(node, column)` cells, see [`TreeCellFlagging`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cell_flags: Vec<(Id, usize)>,
+}
+
+impl<Id> TreeListViewSnapshot<Id> {
+    /// Converts every id with `map`, dropping entries whose id does not map.
+    ///
+    /// An expansion entry is dropped when either its node or its parent does not map.
+    #[must_use]
+    pub fn map_ids<U>(self, mut map: impl FnMut(Id) -> Option<U>) -> TreeListViewSnapshot<U> {
+        let expanded = self
+            .expanded
+            .into_iter()
+            .filter_map(|(parent, id)| {
+                let parent = match parent {
+                    Some(parent) => Some(map(parent)?),
+                    None => None,
+                };
+                Some((parent, map(id)?))
+            })
+            .collect();
+        TreeListViewSnapshot {
+            expanded,
+            manual_marked: self
+                .manual_marked
+                .into_iter()
+                .filter_map(&mut map)
+                .collect(),
+            selected: self.selected.and_then(&mut map),
+            cell_flags: self
+                .cell_flags
+                .into_iter()
+                .filter_map(|(id, column)| Some((map(id)?, column)))
+                .collect(),
+            selected_column: self.selected_column,
+            offset: self.offset,
+            horizontal_offset: self.horizontal_offset,
+            draw_lines: self.draw_lines,
+            column_widths: self.column_widths,
+        }
+    }
 }
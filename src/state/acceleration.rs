@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+use crate::action::TreeViewAction;
+
+/// Step growth for navigation actions repeated in quick succession, such as held arrow keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeAcceleration {
+    /// The maximum delay between two actions that still counts as a repeat.
+    pub interval: Duration,
+    /// Rows moved per action at each acceleration stage.
+    pub steps: [usize; 3],
+    /// The number of repeats needed to advance to the next stage.
+    pub repeats_per_stage: u32,
+}
+
+impl Default for TreeAcceleration {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(150),
+            steps: [1, 2, 5],
+            repeats_per_stage: 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccelerationTracker {
+    last: Option<(TreeViewAction, Instant)>,
+    repeats: u32,
+}
+
+impl AccelerationTracker {
+    /// Records an action and returns the number of rows it should move.
+    pub fn step(
+        &mut self,
+        config: Option<TreeAcceleration>,
+        action: TreeViewAction,
+        now: Instant,
+    ) -> usize {
+        let Some(config) = config.filter(|_| is_accelerated(action)) else {
+            self.reset();
+            return 1;
+        };
+        let repeated = self.last.is_some_and(|(last, at)| {
+            last == action && now.saturating_duration_since(at) <= config.interval
+        });
+        self.repeats = if repeated {
+            self.repeats.saturating_add(1)
+        } else {
+            0
+        };
+        self.last = Some((action, now));
+        let stage = (self.repeats / config.repeats_per_stage.max(1)) as usize;
+        config.steps[stage.min(config.steps.len() - 1)].max(1)
+    }
+
+    pub const fn reset(&mut self) {
+        self.last = None;
+        self.repeats = 0;
+    }
+}
+
+const fn is_accelerated(action: TreeViewAction) -> bool {
+    matches!(
+        action,
+        TreeViewAction::SelectPrev
+            | TreeViewAction::SelectNext
+            | TreeViewAction::ScrollViewUp
+            | TreeViewAction::ScrollViewDown
+    )
+}
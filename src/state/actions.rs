@@ -1,26 +1,33 @@
 use std::hash::Hash;
+use std::time::Instant;
 
-#[cfg(feature = "keymap")]
-use crossterm::event::KeyEvent;
+use smallvec::{SmallVec, smallvec};
+
+#[cfg(feature = "keys")]
+use crate::action::TreeKeyDispatch;
+#[cfg(feature = "keys")]
+use crate::key::{TreeKey, TreeKeyCode};
 
 use crate::action::{
     TreeAction, TreeEditAction, TreeEditRequest, TreeEvent, TreeIntent, TreeViewAction,
 };
 use crate::columns::TreeColumns;
 use crate::context::TreeExpansionState;
-use crate::edit::{TreeChangeSet, TreeEditCommand, TreeEditor, TreeSelectionUpdate};
-use crate::model::{TreeFilter, TreeModel, TreeQuery, TreeSort};
+use crate::edit::{TreeChangeSet, TreeEditCommand, TreeEditError, TreeEditor, TreeSelectionUpdate};
+use crate::model::{TreeExpandAllScope, TreeFilter, TreeModel, TreeQuery, TreeSort};
 
-use super::TreeListViewState;
+use super::{Focus, TreeListViewState};
 
 #[derive(Clone, Copy)]
-enum ExpansionAction {
+pub(super) enum ExpansionAction {
     Expand,
     Toggle,
 }
 
 impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
     /// Handles an action against the current projection.
+    ///
+    /// While an edit waits for confirmation, any action cancels it instead of running.
     pub fn handle_action<T, F, S, C, Custom>(
         &mut self,
         model: &T,
@@ -34,15 +41,160 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         S: TreeSort<T>,
         C: TreeColumns<T>,
     {
+        self.acceleration_tracker.reset();
+        self.handle_action_stepped(model, query, columns, action, 1)
+    }
+
+    /// Handles an action at a caller-supplied time, accelerating repeated navigation.
+    ///
+    /// When [`Self::set_acceleration`] is enabled, the same navigation action repeated within
+    /// the configured interval moves by progressively larger steps.
+    pub fn handle_action_at<T, F, S, C, Custom>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        action: TreeAction<Custom>,
+        now: Instant,
+    ) -> TreeEvent<Id, Custom>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        let step = match action {
+            TreeAction::View(view) => self.acceleration_tracker.step(self.acceleration, view, now),
+            TreeAction::Edit(_) | TreeAction::Custom(_) => {
+                self.acceleration_tracker.reset();
+                1
+            }
+        };
+        self.handle_action_stepped(model, query, columns, action, step)
+    }
+
+    /// Handles a scripted sequence of actions, returning one event per action.
+    ///
+    /// Rebuilds are deferred until an action reads the projection, and the projection is
+    /// synchronized once at the end, so restore flows, macros, and tests do not pay a rebuild
+    /// per action.
+    pub fn apply_actions<T, F, S, C, Custom>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        actions: impl IntoIterator<Item = TreeAction<Custom>>,
+    ) -> Vec<TreeEvent<Id, Custom>>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        self.acceleration_tracker.reset();
+        let events = actions
+            .into_iter()
+            .map(|action| self.run_action(model, query, columns, action, 1, true))
+            .collect();
+        self.ensure_projection(model, query);
+        events
+    }
+
+    /// Handles an action prefixed with an explicit count, as in Vim's `12j`.
+    ///
+    /// Vertical movement and scrolling move `count` rows, and reorder requests carry `count` for
+    /// the application to move the node by. Expansion actions first select the `count`-th branch
+    /// below the selection and then apply to it. Other actions run once.
+    pub fn handle_action_counted<T, F, S, C, Custom>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        action: TreeAction<Custom>,
+        count: usize,
+    ) -> TreeEvent<Id, Custom>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        self.acceleration_tracker.reset();
+        let TreeAction::View(
+            TreeViewAction::Expand
+            | TreeViewAction::Collapse
+            | TreeViewAction::ToggleNode
+            | TreeViewAction::ToggleRecursive,
+        ) = action
+        else {
+            return self.handle_action_stepped(model, query, columns, action, count.max(1));
+        };
         self.ensure_projection(model, query);
+        if self.pending_confirmation.is_some() {
+            return self.handle_action_stepped(model, query, columns, action, 1);
+        }
+        if !self.select_nth_next_branch(count) {
+            return TreeEvent::Unchanged;
+        }
+        match self.handle_action_stepped(model, query, columns, action, 1) {
+            TreeEvent::Unchanged => TreeEvent::Changed,
+            event => event,
+        }
+    }
+
+    fn handle_action_stepped<T, F, S, C, Custom>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        action: TreeAction<Custom>,
+        step: usize,
+    ) -> TreeEvent<Id, Custom>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        self.run_action(model, query, columns, action, step, false)
+    }
+
+    /// Runs one action. A `batched` action skips rebuilds that it does not need itself.
+    fn run_action<T, F, S, C, Custom>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        action: TreeAction<Custom>,
+        step: usize,
+        batched: bool,
+    ) -> TreeEvent<Id, Custom>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        if !batched || reads_projection(&action) {
+            self.ensure_projection(model, query);
+        }
+        if self.cancel_pending() {
+            return TreeEvent::Changed;
+        }
         let event = match action {
+            TreeAction::View(TreeViewAction::SelectHeaderRow) if columns.header_height() == 0 => {
+                TreeEvent::Unchanged
+            }
             TreeAction::View(action) => {
-                self.handle_view_action(model, columns.column_count(), action)
+                let (column_count, tree_column) =
+                    (columns.column_count(), columns.tree_column_index());
+                let expand_all = query.filter_config().expand_all_scope();
+                self.handle_view_action(model, column_count, tree_column, expand_all, action, step)
             }
-            TreeAction::Edit(action) => self.handle_edit_intent(action),
+            TreeAction::Edit(action) => self.handle_edit_intent(action, step),
             TreeAction::Custom(custom) => TreeEvent::Intent(TreeIntent::Custom(custom)),
         };
-        if matches!(event, TreeEvent::Changed) {
+        if matches!(event, TreeEvent::Changed) && !batched {
             self.ensure_projection(model, query);
         }
         event
@@ -50,31 +202,64 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
 
     /// Applies a command through the model, reconciles persistent state, and rebuilds the projection.
     ///
+    /// Pass the query used for input and rendering so that a filtered view stays in sync; there is
+    /// no separate unfiltered edit path.
+    ///
     /// # Errors
     ///
-    /// Returns the model-specific error from [`TreeEditor::apply`] without changing view state.
+    /// Returns [`TreeEditError`] without changing view state when a move would create a cycle,
+    /// a root would be detached, a target is not in the tree, or [`TreeEditor::apply`] fails.
     pub fn apply_edit<T, F, S>(
         &mut self,
         model: &mut T,
         query: &TreeQuery<F, S>,
         command: TreeEditCommand<Id>,
-    ) -> Result<TreeChangeSet<Id>, T::Error>
+    ) -> Result<TreeChangeSet<Id>, TreeEditError<Id, T::Error>>
     where
         T: TreeEditor<Id = Id>,
         F: TreeFilter<T>,
         S: TreeSort<T>,
     {
-        let changes = model.apply(command)?;
+        command.validate(model)?;
+        let changes = model.apply(command).map_err(TreeEditError::Model)?;
         self.reconcile_changes(&changes);
         if let TreeSelectionUpdate::Select(id) = changes.selection {
             self.expand_to(model, id);
         }
+        self.reset_rebuild_throttle();
         self.ensure_projection(model, query);
         Ok(changes)
     }
 
+    /// Keeps only the expansions, marks, cell flags, multi-selected ids, and anchor that satisfy
+    /// `keep`, returning `true` when anything was dropped.
+    ///
+    /// An expansion is dropped when either its node or its parent is rejected. Useful for bulk
+    /// pruning without a change set, such as `retain_state(|id| model.contains(id))`.
+    pub fn retain_state(&mut self, mut keep: impl FnMut(Id) -> bool) -> bool {
+        let mut changed = self
+            .expanded
+            .retain(|path| keep(path.id) && path.parent.is_none_or(&mut keep));
+        changed |= self.manual_marked.retain(|id| keep(*id));
+        changed |= self.multi_selected.retain(|id| keep(*id));
+        changed |= self.cell_flags.retain(|(id, _)| keep(*id));
+        if self.selection_anchor.is_some_and(|id| !keep(id)) {
+            self.selection_anchor = None;
+        }
+        if self.anchor.is_some_and(|id| !keep(id)) {
+            self.anchor = None;
+            changed = true;
+        }
+        if self.details.is_some_and(|id| !keep(id)) {
+            self.details = None;
+            changed = true;
+        }
+        changed
+    }
+
     /// Reconciles marks, expansion, and selection with an exact model change set.
     pub fn reconcile_changes(&mut self, changes: &TreeChangeSet<Id>) {
+        self.pending_confirmation = None;
         self.expanded.retain(|path| {
             !changes.removed.contains(&path.id)
                 && !path
@@ -85,6 +270,22 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
 
         self.manual_marked
             .retain(|id| !changes.removed.contains(id));
+        self.multi_selected
+            .retain(|id| !changes.removed.contains(id));
+        self.cell_flags
+            .retain(|(id, _)| !changes.removed.contains(id));
+        if self
+            .selection_anchor
+            .is_some_and(|anchor| changes.removed.contains(&anchor))
+        {
+            self.selection_anchor = None;
+        }
+        if self
+            .anchor
+            .is_some_and(|anchor| changes.removed.contains(&anchor))
+        {
+            self.anchor = None;
+        }
 
         match changes.selection {
             TreeSelectionUpdate::Keep => {}
@@ -101,18 +302,80 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         }
     }
 
+    /// Reveals a node the application has just added, for example while handling
+    /// [`TreeEditAction::AddChild`]: expands the path to it, rebuilds the projection, and selects
+    /// and scrolls to the node.
+    ///
+    /// Selects `parent` instead when the new node stays hidden, such as under a filter. Returns
+    /// `true` when the new node was selected.
+    pub fn on_node_added<T, F, S>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        parent: Option<Id>,
+        id: Id,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        self.pending_confirmation = None;
+        self.reset_rebuild_throttle();
+        if self.select_by_id(model, query, id) {
+            return true;
+        }
+        if let Some(parent) = parent {
+            let _ = self.select_by_id(model, query, parent);
+        }
+        false
+    }
+
+    /// Drops the state of a node the application has just removed and rebuilds the projection,
+    /// moving the selection by the query's fallback when it was on the node.
+    ///
+    /// Returns `true` when the selection changed.
+    pub fn on_node_removed<T, F, S>(&mut self, model: &T, query: &TreeQuery<F, S>, id: Id) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        self.reconcile_changes(&TreeChangeSet {
+            inserted: SmallVec::new(),
+            moved: SmallVec::new(),
+            removed: smallvec![id],
+            selection: TreeSelectionUpdate::Keep,
+        });
+        if self.details == Some(id) {
+            self.details = None;
+        }
+        self.reset_rebuild_throttle();
+        let selected = self.selected;
+        self.ensure_projection(model, query);
+        self.selected != selected
+    }
+
     fn handle_view_action<T, C>(
         &mut self,
         model: &T,
         column_count: usize,
+        tree_column: usize,
+        expand_all: TreeExpandAllScope,
         action: TreeViewAction,
+        step: usize,
     ) -> TreeEvent<Id, C>
     where
         T: TreeModel<Id = Id>,
     {
+        let step = isize::try_from(step).unwrap_or(isize::MAX);
+        let header_was_selected = self.is_header_selected();
+        if !keeps_header_selected(action) {
+            self.focus = Focus::Rows;
+        }
         let changed = match action {
-            TreeViewAction::SelectPrev => self.select_prev(),
-            TreeViewAction::SelectNext => self.select_next(),
+            TreeViewAction::SelectPrev => self.select_by(model, -step),
+            TreeViewAction::SelectNext => self.select_by(model, step),
             TreeViewAction::SelectParent => self.select_parent(),
             TreeViewAction::SelectFirstChild => self.select_first_child(),
             TreeViewAction::Expand => {
@@ -133,15 +396,20 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 return self.change_selected_expansion(ExpansionAction::Toggle);
             }
             TreeViewAction::ToggleRecursive => return self.toggle_selected_recursive(model),
-            TreeViewAction::ExpandAll => self.expand_all(model),
+            TreeViewAction::ExpandAll => match expand_all {
+                TreeExpandAllScope::Filtered => self.expand_all_filtered(model),
+                TreeExpandAllScope::Model => self.expand_all(model),
+            },
             TreeViewAction::CollapseAll => self.collapse_all(),
+            TreeViewAction::CollapseToSelection => self.collapse_to_selection(),
             TreeViewAction::ToggleGuides => {
                 self.draw_lines = !self.draw_lines;
                 true
             }
-            TreeViewAction::ToggleMark => self
-                .selected
-                .is_some_and(|selected| self.toggle_marked(selected)),
+            TreeViewAction::ShowDetails => self.show_details(),
+            TreeViewAction::SetAnchor => self.toggle_anchor(),
+            TreeViewAction::ToggleReview => self.toggle_review(),
+            TreeViewAction::ToggleMark => self.toggle_selected_mark(tree_column),
             TreeViewAction::SelectFirst => self.select_first(),
             TreeViewAction::SelectLast => self.select_last(),
             TreeViewAction::SelectColumnLeft => self.select_column_left(column_count),
@@ -153,15 +421,37 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 (column_count > 0).then_some(column_count.saturating_sub(1)),
                 column_count,
             ),
-            TreeViewAction::ScrollViewUp => self.scroll_view_by(-1),
-            TreeViewAction::ScrollViewDown => self.scroll_view_by(1),
+            TreeViewAction::PageUp => self.page_by(-step),
+            TreeViewAction::PageDown => self.page_by(step),
+            TreeViewAction::ScrollViewUp => self.scroll_view_by(-step),
+            TreeViewAction::ScrollViewDown => self.scroll_view_by(step),
             TreeViewAction::ScrollLeft => self.scroll_horizontal_by(-1),
             TreeViewAction::ScrollRight => self.scroll_horizontal_by(1),
-        };
-        changed_event(changed)
+            TreeViewAction::ScrollColumnsLeft => self.scroll_columns_by(-1),
+            TreeViewAction::ScrollColumnsRight => self.scroll_columns_by(1),
+            TreeViewAction::GrowColumn => self.resize_selected_column(step),
+            TreeViewAction::ShrinkColumn => self.resize_selected_column(-step),
+            TreeViewAction::ExtendSelectionUp => self.extend_selection(-step),
+            TreeViewAction::ExtendSelectionDown => self.extend_selection(step),
+            TreeViewAction::SelectHeaderRow => {
+                if !header_was_selected && self.selected_column.is_some() {
+                    self.focus = Focus::Header;
+                }
+                false
+            }
+        } || header_was_selected != self.is_header_selected();
+        self.selected_column
+            .filter(|_| changed && self.is_header_selected())
+            .map_or_else(
+                || changed_event(changed),
+                |column| TreeEvent::HeaderSelected { column },
+            )
     }
 
-    fn change_selected_expansion<C>(&mut self, action: ExpansionAction) -> TreeEvent<Id, C> {
+    pub(super) fn change_selected_expansion<C>(
+        &mut self,
+        action: ExpansionAction,
+    ) -> TreeEvent<Id, C> {
         let Some(node) = self.selected_node() else {
             return TreeEvent::Unchanged;
         };
@@ -174,7 +464,17 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 self.set_expanded(node.id(), node.parent(), false);
                 TreeEvent::Changed
             }
-            TreeExpansionState::Unloaded => TreeEvent::Intent(TreeIntent::LoadChildren(node.id())),
+            TreeExpansionState::Unloaded => {
+                let toggle = matches!(action, ExpansionAction::Toggle);
+                if toggle && self.set_expanded(node.id(), node.parent(), false) {
+                    return TreeEvent::Changed;
+                }
+                self.set_expanded(node.id(), node.parent(), true);
+                TreeEvent::Intent(TreeIntent::LoadChildren(node.id()))
+            }
+            TreeExpansionState::Loading if matches!(action, ExpansionAction::Toggle) => {
+                changed_event(self.set_expanded(node.id(), node.parent(), false))
+            }
             TreeExpansionState::Leaf
             | TreeExpansionState::Expanded
             | TreeExpansionState::ForcedByFilter
@@ -186,15 +486,24 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         let Some(node) = self.selected_node() else {
             return false;
         };
-        matches!(node.expansion(), TreeExpansionState::Expanded)
-            && self.set_expanded(node.id(), node.parent(), false)
+        matches!(
+            node.expansion(),
+            TreeExpansionState::Expanded
+                | TreeExpansionState::Unloaded
+                | TreeExpansionState::Loading
+        ) && self.set_expanded(node.id(), node.parent(), false)
     }
 
     fn expand_or_select_first_child<C>(&mut self) -> TreeEvent<Id, C> {
         let event = self.change_selected_expansion(ExpansionAction::Expand);
         match event {
             TreeEvent::Unchanged => changed_event(self.select_first_child()),
-            TreeEvent::Changed | TreeEvent::Intent(_) => event,
+            TreeEvent::Changed
+            | TreeEvent::Intent(_)
+            | TreeEvent::CellClicked { .. }
+            | TreeEvent::HeaderClicked { .. }
+            | TreeEvent::HeaderSelected { .. }
+            | TreeEvent::ModelInconsistent(_) => event,
         }
     }
 
@@ -210,15 +519,18 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 let expand = matches!(node.expansion(), TreeExpansionState::Collapsed);
                 changed_event(self.set_expanded_recursive(model, node.id(), node.parent(), expand))
             }
-            TreeExpansionState::Unloaded => TreeEvent::Intent(TreeIntent::LoadChildren(node.id())),
+            TreeExpansionState::Unloaded => {
+                self.set_expanded(node.id(), node.parent(), true);
+                TreeEvent::Intent(TreeIntent::LoadChildren(node.id()))
+            }
             TreeExpansionState::Leaf
             | TreeExpansionState::ForcedByFilter
             | TreeExpansionState::Loading => TreeEvent::Unchanged,
         }
     }
 
-    fn handle_edit_intent<C>(&self, action: TreeEditAction) -> TreeEvent<Id, C> {
-        let Some(node) = self.selected_node() else {
+    fn handle_edit_intent<C>(&mut self, action: TreeEditAction, step: usize) -> TreeEvent<Id, C> {
+        let Some(node) = self.selected_node().filter(|node| !node.is_synthetic()) else {
             return TreeEvent::Unchanged;
         };
         let request = match action {
@@ -229,6 +541,7 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 TreeEditRequest::ReorderUp {
                     node: node.id(),
                     parent,
+                    count: step,
                 }
             }
             TreeEditAction::ReorderDown => {
@@ -238,6 +551,7 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 TreeEditRequest::ReorderDown {
                     node: node.id(),
                     parent,
+                    count: step,
                 }
             }
             TreeEditAction::AddChild => TreeEditRequest::AddChild { parent: node.id() },
@@ -255,17 +569,21 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
             TreeEditAction::Yank => TreeEditRequest::Yank { node: node.id() },
             TreeEditAction::Paste => TreeEditRequest::Paste { parent: node.id() },
         };
-        TreeEvent::Intent(TreeIntent::Edit(request))
+        self.request_confirmation(action, request)
     }
 
-    #[cfg(feature = "keymap")]
-    /// Resolves a crossterm event into an action and handles it.
+    #[cfg(feature = "keys")]
+    /// Resolves a key event into an action and handles it.
+    ///
+    /// Digits typed before an action form a count handled by [`Self::handle_action_counted`].
+    /// Keys without a binding report [`TreeEvent::Unchanged`]; [`Self::dispatch_key`] returns them
+    /// for fallthrough instead.
     pub fn handle_key<T, F, S, C>(
         &mut self,
         model: &T,
         query: &TreeQuery<F, S>,
         columns: &C,
-        key: KeyEvent,
+        key: impl Into<TreeKey>,
     ) -> TreeEvent<Id>
     where
         T: TreeModel<Id = Id>,
@@ -276,14 +594,42 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.handle_key_with(model, query, columns, key, |_| None::<()>)
     }
 
-    #[cfg(feature = "keymap")]
+    #[cfg(feature = "keys")]
+    /// A version of [`handle_key`](Self::handle_key) that accelerates held navigation keys.
+    pub fn handle_key_at<T, F, S, C>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        key: impl Into<TreeKey>,
+        now: Instant,
+    ) -> TreeEvent<Id>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        let key = key.into();
+        let pending = self.key_prefix_pending();
+        let dispatch = self.dispatch_by(key, |state| {
+            let (action, count) = state.keymap.resolve_bound_counted_at(key, now)?;
+            Some(match count {
+                None => state.handle_action_at(model, query, columns, action, now),
+                Some(count) => state.handle_action_counted(model, query, columns, action, count),
+            })
+        });
+        Self::key_event(dispatch, pending)
+    }
+
+    #[cfg(feature = "keys")]
     /// A version of [`handle_key`](Self::handle_key) with custom mapping.
     pub fn handle_key_with<T, F, S, C, Custom, R>(
         &mut self,
         model: &T,
         query: &TreeQuery<F, S>,
         columns: &C,
-        key: KeyEvent,
+        key: impl Into<TreeKey>,
         custom: R,
     ) -> TreeEvent<Id, Custom>
     where
@@ -291,18 +637,153 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         F: TreeFilter<T>,
         S: TreeSort<T>,
         C: TreeColumns<T>,
-        R: Fn(KeyEvent) -> Option<Custom>,
+        R: Fn(TreeKey) -> Option<Custom>,
     {
-        let Some(action) = self.keymap.resolve_with(key, custom) else {
-            return TreeEvent::Unchanged;
-        };
-        self.handle_action(model, query, columns, action)
+        let pending = self.key_prefix_pending();
+        let dispatch = self.dispatch_key_with(model, query, columns, key, custom);
+        Self::key_event(dispatch, pending)
+    }
+
+    #[cfg(feature = "keys")]
+    /// A version of [`handle_key`](Self::handle_key) that returns keys without a binding, so
+    /// several widgets sharing a key loop can pass a key on until one consumes it.
+    pub fn dispatch_key<T, F, S, C>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        key: impl Into<TreeKey>,
+    ) -> TreeKeyDispatch<Id>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+    {
+        self.dispatch_key_with(model, query, columns, key, |_| None::<()>)
+    }
+
+    #[cfg(feature = "keys")]
+    /// A version of [`dispatch_key`](Self::dispatch_key) with custom mapping.
+    pub fn dispatch_key_with<T, F, S, C, Custom, R>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        columns: &C,
+        key: impl Into<TreeKey>,
+        custom: R,
+    ) -> TreeKeyDispatch<Id, Custom>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        C: TreeColumns<T>,
+        R: Fn(TreeKey) -> Option<Custom>,
+    {
+        let key = key.into();
+        self.dispatch_by(key, |state| {
+            let (action, count) = state.keymap.resolve_counted_with(key, custom)?;
+            Some(match count {
+                None => state.handle_action(model, query, columns, action),
+                Some(count) => state.handle_action_counted(model, query, columns, action, count),
+            })
+        })
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns `true` while a numeric prefix or the start of a chord is pending.
+    #[cfg(feature = "keys")]
+    fn key_prefix_pending(&self) -> bool {
+        self.keymap.pending_count().is_some() || !self.keymap.pending_chord().is_empty()
+    }
+
+    /// Routes `key` to the detail popup or a pending confirmation, then to `handle`, which
+    /// resolves and handles it or returns `None` for a key without a binding.
+    #[cfg(feature = "keys")]
+    fn dispatch_by<Custom>(
+        &mut self,
+        key: TreeKey,
+        handle: impl FnOnce(&mut Self) -> Option<TreeEvent<Id, Custom>>,
+    ) -> TreeKeyDispatch<Id, Custom> {
+        if let Some(event) = self.resolve_details_key(key) {
+            return TreeKeyDispatch::Handled(event);
+        }
+        if let Some(event) = self.resolve_pending_key(key) {
+            return TreeKeyDispatch::Handled(event);
+        }
+        let pending = self.key_prefix_pending();
+        if let Some(event) = handle(self) {
+            return TreeKeyDispatch::Handled(event);
+        }
+        // Digits, chord keys, and `Esc` that edited the pending prefix were consumed.
+        if self.key_prefix_pending() || (pending && key.code == TreeKeyCode::Esc) {
+            TreeKeyDispatch::Handled(TreeEvent::Changed)
+        } else {
+            TreeKeyDispatch::Unhandled(key)
+        }
+    }
+
+    /// Reports an unhandled key as a change when it discarded a pending prefix.
+    #[cfg(feature = "keys")]
+    fn key_event<Custom>(
+        dispatch: TreeKeyDispatch<Id, Custom>,
+        pending: bool,
+    ) -> TreeEvent<Id, Custom> {
+        match dispatch {
+            TreeKeyDispatch::Handled(event) => event,
+            TreeKeyDispatch::Unhandled(_) => changed_event(pending),
+        }
     }
 }
-const fn changed_event<Id, Custom>(changed: bool) -> TreeEvent<Id, Custom> {
+
+pub(super) const fn changed_event<Id, Custom>(changed: bool) -> TreeEvent<Id, Custom> {
     if changed {
         TreeEvent::Changed
     } else {
         TreeEvent::Unchanged
     }
 }
+
+/// Returns `false` for actions that only touch ids, flags, and columns, which a batch can run
+/// against a stale projection.
+///
+/// [`ToggleMark`](TreeViewAction::ToggleMark) and [`SetAnchor`](TreeViewAction::SetAnchor) read
+/// the projection to skip synthetic rows.
+const fn reads_projection<C>(action: &TreeAction<C>) -> bool {
+    !matches!(
+        action,
+        TreeAction::Custom(_)
+            | TreeAction::View(
+                TreeViewAction::CollapseAll
+                    | TreeViewAction::ToggleGuides
+                    | TreeViewAction::ShowDetails
+                    | TreeViewAction::ToggleReview
+                    | TreeViewAction::SelectColumnLeft
+                    | TreeViewAction::SelectColumnRight
+                    | TreeViewAction::SelectFirstColumn
+                    | TreeViewAction::SelectLastColumn
+                    | TreeViewAction::GrowColumn
+                    | TreeViewAction::ShrinkColumn
+                    | TreeViewAction::SelectHeaderRow
+            )
+    )
+}
+
+/// Returns `true` for actions that keep a focused column header focused.
+const fn keeps_header_selected(action: TreeViewAction) -> bool {
+    matches!(
+        action,
+        TreeViewAction::SelectHeaderRow
+            | TreeViewAction::SelectColumnLeft
+            | TreeViewAction::SelectColumnRight
+            | TreeViewAction::SelectFirstColumn
+            | TreeViewAction::SelectLastColumn
+            | TreeViewAction::ScrollLeft
+            | TreeViewAction::ScrollRight
+            | TreeViewAction::ScrollColumnsLeft
+            | TreeViewAction::ScrollColumnsRight
+            | TreeViewAction::GrowColumn
+            | TreeViewAction::ShrinkColumn
+    )
+}
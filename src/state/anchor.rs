@@ -0,0 +1,41 @@
+use std::hash::Hash;
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the node pinned as a comparison baseline with
+    /// [`SetAnchor`](crate::TreeViewAction::SetAnchor).
+    ///
+    /// This is unrelated to the [`selection_anchor`](Self::selection_anchor) that range
+    /// selections extend from.
+    #[must_use]
+    pub const fn anchor(&self) -> Option<Id> {
+        self.anchor
+    }
+
+    pub fn set_anchor(&mut self, anchor: Option<Id>) -> bool {
+        let changed = self.anchor != anchor;
+        self.anchor = anchor;
+        changed
+    }
+
+    /// Returns how many rows the selection lies below the anchor, negative when it lies above.
+    ///
+    /// Returns `None` when there is no anchor or either node is hidden.
+    #[must_use]
+    pub fn anchor_distance(&self) -> Option<isize> {
+        let anchor = self.projection.index_of(self.anchor?)?;
+        let selected = self.selected_index()?;
+        let signed = |index: usize| isize::try_from(index).unwrap_or(isize::MAX);
+        Some(signed(selected) - signed(anchor))
+    }
+
+    /// Pins the selected node as the anchor, or unpins it when it already is the anchor.
+    pub(super) fn toggle_anchor(&mut self) -> bool {
+        let Some(selected) = self.selected_target() else {
+            return false;
+        };
+        let anchor = (self.anchor != Some(selected)).then_some(selected);
+        self.set_anchor(anchor)
+    }
+}
@@ -0,0 +1,110 @@
+use std::hash::Hash;
+
+use crate::model::{TreeChildren, TreeFilter, TreeModel, TreeQuery, TreeSort};
+
+use super::TreeListViewState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InitialSelection<Id> {
+    None,
+    Root,
+    Id(Id),
+}
+
+/// Initial view state for common setups, created by [`TreeListViewState::builder`].
+///
+/// [`Self::build`] applies marks and expansion before building the projection and selects last,
+/// so the selection lands on a row that exists and is scrolled into view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeListViewStateBuilder<Id> {
+    expand_depth: usize,
+    selection: InitialSelection<Id>,
+    marks: Vec<Id>,
+    draw_lines: bool,
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewStateBuilder<Id> {
+    /// Expands loaded branches in the first `depth` levels; `1` expands only the roots.
+    #[must_use]
+    pub const fn expand_depth(mut self, depth: usize) -> Self {
+        self.expand_depth = depth;
+        self
+    }
+
+    /// Selects the first row.
+    #[must_use]
+    pub const fn select_root(mut self) -> Self {
+        self.selection = InitialSelection::Root;
+        self
+    }
+
+    /// Expands the path to `id` and selects it.
+    #[must_use]
+    pub const fn select_id(mut self, id: Id) -> Self {
+        self.selection = InitialSelection::Id(id);
+        self
+    }
+
+    /// Manually marks `ids`.
+    #[must_use]
+    pub fn marks(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.marks.extend(ids);
+        self
+    }
+
+    #[must_use]
+    pub const fn draw_lines(mut self, draw: bool) -> Self {
+        self.draw_lines = draw;
+        self
+    }
+
+    /// Creates the state with its projection and mark states built for `model` and `query`.
+    #[must_use]
+    pub fn build<T, F, S>(self, model: &T, query: &TreeQuery<F, S>) -> TreeListViewState<Id>
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        let mut state = TreeListViewState::with_capacity(model.size_hint());
+        state.set_draw_lines(self.draw_lines);
+        let _ = state.set_marks(self.marks, true);
+        let mut pending: Vec<_> = model.roots().map(|id| (None, id, 0_usize)).collect();
+        pending.reverse();
+        while let Some((parent, id, level)) = pending.pop() {
+            if level >= self.expand_depth {
+                continue;
+            }
+            if let TreeChildren::Loaded(children) = model.children(id) {
+                let _ = state.set_expanded(id, parent, true);
+                pending.extend(
+                    children
+                        .iter()
+                        .rev()
+                        .map(|child| (Some(id), *child, level + 1)),
+                );
+            }
+        }
+        let _ = state.ensure_projection(model, query);
+        state.ensure_mark_states(model);
+        let _ = match self.selection {
+            InitialSelection::None => false,
+            InitialSelection::Root => state.select_first(),
+            InitialSelection::Id(id) => state.select_by_id(model, query, id),
+        };
+        state
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Starts an initial state with nothing expanded, selected, or marked, and guides drawn.
+    #[must_use]
+    pub const fn builder() -> TreeListViewStateBuilder<Id> {
+        TreeListViewStateBuilder {
+            expand_depth: 0,
+            selection: InitialSelection::None,
+            marks: Vec::new(),
+            draw_lines: true,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::hash::Hash;
+
+use super::{TreeCellFlagging, TreeListViewState};
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    #[must_use]
+    pub const fn cell_flagging(&self) -> TreeCellFlagging {
+        self.cell_flagging
+    }
+
+    /// Sets whether [`ToggleMark`](crate::TreeViewAction::ToggleMark) flags the selected cell
+    /// while a data column is selected.
+    pub fn set_cell_flagging(&mut self, flagging: TreeCellFlagging) -> bool {
+        let changed = self.cell_flagging != flagging;
+        self.cell_flagging = flagging;
+        changed
+    }
+
+    #[must_use]
+    pub fn is_cell_flagged(&self, id: Id, column: usize) -> bool {
+        self.cell_flags.contains(&(id, column))
+    }
+
+    /// Sets the flag of one cell, independent of the node's mark.
+    pub fn set_cell_flag(&mut self, id: Id, column: usize, flagged: bool) -> bool {
+        self.cell_flags.set_membership((id, column), flagged)
+    }
+
+    pub fn toggle_cell_flag(&mut self, id: Id, column: usize) -> bool {
+        let flagged = !self.is_cell_flagged(id, column);
+        self.set_cell_flag(id, column, flagged)
+    }
+
+    /// Returns every flagged `(node, column)` cell in no particular order.
+    pub fn cell_flags(&self) -> impl Iterator<Item = (Id, usize)> + '_ {
+        self.cell_flags.iter().copied()
+    }
+
+    pub fn clear_cell_flags(&mut self) -> bool {
+        self.cell_flags.clear()
+    }
+
+    /// Toggles the selected cell's flag when flagging is enabled and a data column is selected,
+    /// and the selected node's mark otherwise.
+    pub(super) fn toggle_selected_mark(&mut self, tree_column: usize) -> bool {
+        let Some(selected) = self.selected_target() else {
+            return false;
+        };
+        match self.selected_column {
+            Some(column)
+                if column != tree_column && self.cell_flagging == TreeCellFlagging::Enabled =>
+            {
+                self.toggle_cell_flag(selected, column)
+            }
+            _ => self.toggle_marked(selected),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::hash::Hash;
+
+use crate::model::TreeRevision;
+use crate::projection::TreeProjection;
+
+use super::TreeListViewState;
+
+/// Projection rows grouped by parent row, rebuilt once per projection generation.
+///
+/// The lists of [`TreeColumnsView`](crate::TreeColumnsView) read their rows from here instead of
+/// scanning the projection for every pane on every frame.
+#[derive(Debug, Default)]
+pub struct ColumnRows {
+    generation: Option<TreeRevision>,
+    /// Where each parent's rows start in `rows`: slot 0 holds the roots and slot `i + 1` the
+    /// children of row `i`, with one trailing end slot.
+    starts: Vec<usize>,
+    rows: Vec<usize>,
+}
+
+impl ColumnRows {
+    fn refresh<Id: Copy + Eq + Hash>(&mut self, projection: &TreeProjection<Id>) {
+        let generation = projection.generation();
+        if self.generation == Some(generation) {
+            return;
+        }
+        self.generation = Some(generation);
+
+        let nodes = projection.nodes();
+        let slot = |parent_index: Option<usize>| parent_index.map_or(0, |index| index + 1);
+        self.starts.clear();
+        self.starts.resize(nodes.len() + 2, 0);
+        for node in nodes {
+            self.starts[slot(node.parent_index()) + 1] += 1;
+        }
+        for index in 1..self.starts.len() {
+            self.starts[index] += self.starts[index - 1];
+        }
+        self.rows.clear();
+        self.rows.resize(nodes.len(), 0);
+        let mut next = self.starts.clone();
+        for (index, node) in nodes.iter().enumerate() {
+            let cursor = &mut next[slot(node.parent_index())];
+            self.rows[*cursor] = index;
+            *cursor += 1;
+        }
+    }
+
+    /// Returns the rows under `parent_index` in display order, or the roots for `None`.
+    pub fn children(&self, parent_index: Option<usize>) -> &[usize] {
+        let slot = parent_index.map_or(0, |index| index + 1);
+        match (self.starts.get(slot), self.starts.get(slot + 1)) {
+            (Some(&start), Some(&end)) => &self.rows[start..end],
+            _ => &[],
+        }
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Groups the current projection's rows by parent unless its generation is already grouped.
+    pub(crate) fn ensure_column_rows(&mut self) {
+        self.column_rows.refresh(&self.projection);
+    }
+
+    /// Returns the rows under `parent_index` as of the latest [`Self::ensure_column_rows`].
+    pub(crate) fn column_rows(&self, parent_index: Option<usize>) -> &[usize] {
+        self.column_rows.children(parent_index)
+    }
+}
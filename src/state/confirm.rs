@@ -0,0 +1,81 @@
+use std::hash::Hash;
+
+#[cfg(feature = "keys")]
+use crate::key::{TreeKey, TreeKeyCode, TreeKeyKind};
+
+use crate::action::{TreeEditAction, TreeEditRequest, TreeEvent, TreeIntent};
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns `true` when `action` waits for confirmation before emitting its intent.
+    #[must_use]
+    pub const fn requires_confirmation(&self, action: TreeEditAction) -> bool {
+        self.confirmations & confirmation_bit(action) != 0
+    }
+
+    /// Makes `action` wait for confirmation, for example to guard [`TreeEditAction::Delete`].
+    pub const fn set_requires_confirmation(
+        &mut self,
+        action: TreeEditAction,
+        required: bool,
+    ) -> bool {
+        let confirmations = if required {
+            self.confirmations | confirmation_bit(action)
+        } else {
+            self.confirmations & !confirmation_bit(action)
+        };
+        let changed = confirmations != self.confirmations;
+        self.confirmations = confirmations;
+        changed
+    }
+
+    /// Returns the edit request waiting for confirmation.
+    #[must_use]
+    pub const fn pending_confirmation(&self) -> Option<TreeEditRequest<Id>> {
+        self.pending_confirmation
+    }
+
+    /// Emits the pending edit request as an intent.
+    pub const fn confirm_pending<C>(&mut self) -> TreeEvent<Id, C> {
+        match self.pending_confirmation.take() {
+            Some(request) => TreeEvent::Intent(TreeIntent::Edit(request)),
+            None => TreeEvent::Unchanged,
+        }
+    }
+
+    /// Drops the pending edit request.
+    pub const fn cancel_pending(&mut self) -> bool {
+        self.pending_confirmation.take().is_some()
+    }
+
+    pub(super) const fn request_confirmation<C>(
+        &mut self,
+        action: TreeEditAction,
+        request: TreeEditRequest<Id>,
+    ) -> TreeEvent<Id, C> {
+        if self.requires_confirmation(action) {
+            self.pending_confirmation = Some(request);
+            TreeEvent::Changed
+        } else {
+            TreeEvent::Intent(TreeIntent::Edit(request))
+        }
+    }
+
+    #[cfg(feature = "keys")]
+    /// Resolves a pending confirmation: `y` confirms and any other key cancels.
+    pub(super) fn resolve_pending_key<C>(&mut self, key: TreeKey) -> Option<TreeEvent<Id, C>> {
+        if self.pending_confirmation.is_none() || key.kind == TreeKeyKind::Release {
+            return None;
+        }
+        if let TreeKeyCode::Char('y' | 'Y') = key.code {
+            return Some(self.confirm_pending());
+        }
+        self.cancel_pending();
+        Some(TreeEvent::Changed)
+    }
+}
+
+const fn confirmation_bit(action: TreeEditAction) -> u8 {
+    1 << action as u8
+}
@@ -0,0 +1,55 @@
+use std::hash::Hash;
+
+use smallvec::SmallVec;
+
+use crate::action::TreeEvent;
+use crate::model::TreeRevision;
+
+use super::TreeListViewState;
+
+/// Missing ids found while rendering one projection generation.
+pub(super) struct MissingIds<Id> {
+    generation: Option<TreeRevision>,
+    ids: SmallVec<[Id; 4]>,
+    reported: usize,
+}
+
+impl<Id> Default for MissingIds<Id> {
+    fn default() -> Self {
+        Self {
+            generation: None,
+            ids: SmallVec::new(),
+            reported: 0,
+        }
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns [`TreeEvent::ModelInconsistent`] for the next missing node found by a render under
+    /// [`TreeModelCheck::Verify`], or `None` once every one has been reported.
+    ///
+    /// Each missing id is reported once per projection, so polling after every frame does not
+    /// repeat it until the model is rebuilt and the id is rendered again.
+    ///
+    /// [`TreeModelCheck::Verify`]: crate::TreeModelCheck::Verify
+    pub fn take_model_event<C>(&mut self) -> Option<TreeEvent<Id, C>> {
+        let id = self.missing.ids.get(self.missing.reported).copied()?;
+        self.missing.reported += 1;
+        Some(TreeEvent::ModelInconsistent(id))
+    }
+
+    pub(crate) fn record_missing(&mut self, ids: impl IntoIterator<Item = Id>) {
+        let generation = self.projection.generation();
+        if self.missing.generation != Some(generation) {
+            self.missing = MissingIds {
+                generation: Some(generation),
+                ..MissingIds::default()
+            };
+        }
+        for id in ids {
+            if !self.missing.ids.contains(&id) {
+                self.missing.ids.push(id);
+            }
+        }
+    }
+}
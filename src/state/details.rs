@@ -0,0 +1,42 @@
+use std::hash::Hash;
+
+#[cfg(feature = "keys")]
+use crate::action::TreeEvent;
+#[cfg(feature = "keys")]
+use crate::key::{TreeKey, TreeKeyKind};
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the node whose detail popup is open.
+    #[must_use]
+    pub const fn details_node(&self) -> Option<Id> {
+        self.details
+    }
+
+    /// Opens the detail popup for the selected node.
+    pub fn show_details(&mut self) -> bool {
+        let Some(selected) = self.selected else {
+            return false;
+        };
+        let changed = self.details != Some(selected);
+        self.details = Some(selected);
+        changed
+    }
+
+    pub const fn hide_details(&mut self) -> bool {
+        self.details.take().is_some()
+    }
+
+    #[cfg(feature = "keys")]
+    /// Dismisses an open detail popup, consuming the key that closed it.
+    pub(super) const fn resolve_details_key<C>(
+        &mut self,
+        key: TreeKey,
+    ) -> Option<TreeEvent<Id, C>> {
+        if matches!(key.kind, TreeKeyKind::Release) || !self.hide_details() {
+            return None;
+        }
+        Some(TreeEvent::Changed)
+    }
+}
@@ -0,0 +1,65 @@
+use std::hash::Hash;
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Highlights `id` with [`flash_style`](crate::TreeListViewStyle::flash_style) for the next
+    /// `duration_ticks` calls to [`Self::tick`], for example to show where a paste or jump
+    /// landed. Flashing a row again restarts its countdown; a zero duration clears it.
+    pub fn flash(&mut self, id: Id, duration_ticks: u16) -> bool {
+        let changed = self.set_flash(id, duration_ticks);
+        self.bump_flashes(changed)
+    }
+
+    fn set_flash(&mut self, id: Id, duration_ticks: u16) -> bool {
+        let position = self.flashes.iter().position(|(flashed, _)| *flashed == id);
+        match (position, duration_ticks) {
+            (None, 0) => false,
+            (Some(index), 0) => {
+                self.flashes.swap_remove(index);
+                true
+            }
+            (Some(index), ticks) => {
+                let changed = self.flashes[index].1 != ticks;
+                self.flashes[index].1 = ticks;
+                changed
+            }
+            (None, ticks) => {
+                self.flashes.push((id, ticks));
+                true
+            }
+        }
+    }
+
+    pub(super) fn advance_flashes(&mut self) -> bool {
+        let before = self.flashes.len();
+        self.flashes.retain(|(_, ticks)| {
+            *ticks = ticks.saturating_sub(1);
+            *ticks > 0
+        });
+        let expired = self.flashes.len() != before;
+        self.bump_flashes(expired)
+    }
+
+    /// Returns the ticks left before the flash on `id` expires.
+    #[must_use]
+    pub fn flash_remaining(&self, id: Id) -> Option<u16> {
+        self.flashes
+            .iter()
+            .find_map(|(flashed, ticks)| (*flashed == id).then_some(*ticks))
+    }
+
+    /// Clears all flash highlights.
+    pub fn clear_flashes(&mut self) -> bool {
+        let changed = !self.flashes.is_empty();
+        self.flashes.clear();
+        self.bump_flashes(changed)
+    }
+
+    const fn bump_flashes(&mut self, changed: bool) -> bool {
+        if changed {
+            self.flash_revision = self.flash_revision.wrapping_add(1);
+        }
+        changed
+    }
+}
@@ -1,9 +1,20 @@
 use std::hash::Hash;
+#[cfg(feature = "keymap")]
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "keymap")]
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::{Position, Rect};
 use smallvec::SmallVec;
 
+use crate::action::{TreeClickKind, TreeEvent};
+
 use super::TreeListViewState;
+#[cfg(feature = "keymap")]
+use super::actions::{ExpansionAction, changed_event};
+
+#[cfg(feature = "keymap")]
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
 
 /// The region of the latest rendering that contains a coordinate.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -41,12 +52,31 @@ impl<Id> TreeHit<Id> {
     }
 }
 
+/// The part of a row under a coordinate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeHitPart {
+    /// The expansion glyph of a branch in the tree column.
+    Expander,
+    /// The tree column after the expansion glyph.
+    Label,
+    /// A data column, or the guides before a node.
+    Cell,
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct ColumnHitBox {
     pub start: u16,
     pub width: u16,
 }
 
+/// A list pane of [`TreeColumnsView`](crate::TreeColumnsView) showing the rows under one parent.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PaneHitBox {
+    pub area: Rect,
+    pub parent_index: Option<usize>,
+    pub offset: usize,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct TreeHitMap {
     pub table: Rect,
@@ -58,6 +88,10 @@ pub struct TreeHitMap {
     pub horizontal_offset: u16,
     pub selection_width: u16,
     pub columns: SmallVec<[ColumnHitBox; 8]>,
+    pub tree_column: usize,
+    pub indent_width: u16,
+    pub expander_width: u16,
+    pub panes: SmallVec<[PaneHitBox; 3]>,
 }
 
 impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
@@ -78,6 +112,22 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         {
             return Some(TreeHit::HorizontalScrollbar);
         }
+        if let Some(pane) = self
+            .hit_map
+            .panes
+            .iter()
+            .find(|pane| contains(pane.area, position))
+        {
+            let line = pane
+                .offset
+                .saturating_add(position.y.saturating_sub(pane.area.y) as usize);
+            let index = *self.column_rows(pane.parent_index).get(line)?;
+            return self.projection.nodes().get(index).map(|node| TreeHit::Row {
+                id: node.id(),
+                index,
+                column: None,
+            });
+        }
         if !contains(self.hit_map.table, position) {
             return None;
         }
@@ -104,16 +154,141 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         })
     }
 
-    fn hit_column(&self, x: u16) -> Option<usize> {
-        let local_x = x.saturating_sub(self.hit_map.table.x);
-        if local_x < self.hit_map.selection_width {
+    /// Classifies the part of a row under a coordinate from the latest render geometry.
+    ///
+    /// Assumes the tree column is laid out by [`tree_label_line`](crate::tree_label_line).
+    #[must_use]
+    pub fn hit_test_part(&self, position: Position) -> Option<TreeHitPart> {
+        let Some(TreeHit::Row {
+            index,
+            column: Some(column),
+            ..
+        }) = self.hit_test(position)
+        else {
             return None;
+        };
+        if column != self.hit_map.tree_column {
+            return Some(TreeHitPart::Cell);
+        }
+        let node = self.projection.nodes().get(index)?;
+        let cell_x = self
+            .virtual_x(position.x)?
+            .saturating_sub(self.hit_map.columns.get(column)?.start);
+        let level = u16::try_from(node.level()).unwrap_or(u16::MAX);
+        let guides = level.saturating_mul(self.hit_map.indent_width);
+        let glyph_start = if level > 0 {
+            guides.saturating_add(1)
+        } else {
+            0
+        };
+        let glyph_end = glyph_start.saturating_add(self.hit_map.expander_width);
+        Some(if cell_x < glyph_start {
+            TreeHitPart::Cell
+        } else if cell_x < glyph_end && node.has_children() {
+            TreeHitPart::Expander
+        } else {
+            TreeHitPart::Label
+        })
+    }
+
+    /// Selects the clicked row and reports the clicked cell, or reports the clicked header.
+    ///
+    /// Clicks on the selection symbol only select the row. A pending confirmation is cancelled
+    /// instead.
+    pub fn handle_click<C>(&mut self, position: Position, kind: TreeClickKind) -> TreeEvent<Id, C> {
+        if self.cancel_pending() {
+            return TreeEvent::Changed;
         }
-        let virtual_x = local_x.saturating_add(self.hit_map.horizontal_offset);
+        let (id, index, column) = match self.hit_test(position) {
+            Some(TreeHit::Row { id, index, column }) => (id, index, column),
+            Some(TreeHit::Header {
+                column: Some(column),
+            }) => return TreeEvent::HeaderClicked { column, kind },
+            _ => return TreeEvent::Unchanged,
+        };
+        let changed = self.select_index(Some(index));
+        match column {
+            Some(column) => TreeEvent::CellClicked { id, column, kind },
+            None if changed => TreeEvent::Changed,
+            None => TreeEvent::Unchanged,
+        }
+    }
+
+    #[cfg(feature = "keymap")]
+    /// Handles a crossterm mouse event against the latest rendered layout.
+    ///
+    /// See [`Self::handle_mouse_at`].
+    pub fn handle_mouse<C>(&mut self, mouse: MouseEvent) -> TreeEvent<Id, C> {
+        self.handle_mouse_at(mouse, Instant::now())
+    }
+
+    #[cfg(feature = "keymap")]
+    /// Handles a crossterm mouse event at a caller-supplied time.
+    ///
+    /// A left click on a branch's expansion glyph selects and toggles the branch, reporting
+    /// [`TreeIntent::LoadChildren`](crate::TreeIntent::LoadChildren) for an unloaded one. Two
+    /// other left clicks on the same cell within half a second form a double click, and the
+    /// wheel scrolls the view.
+    pub fn handle_mouse_at<C>(&mut self, mouse: MouseEvent, now: Instant) -> TreeEvent<Id, C> {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left)
+                if self.hit_test_part(position) == Some(TreeHitPart::Expander) =>
+            {
+                self.last_click = None;
+                self.toggle_expander_at(position)
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let double = self.last_click.is_some_and(|(last, at)| {
+                    last == position && now.saturating_duration_since(at) <= DOUBLE_CLICK_INTERVAL
+                });
+                self.last_click = (!double).then_some((position, now));
+                let kind = if double {
+                    TreeClickKind::Double
+                } else {
+                    TreeClickKind::Single
+                };
+                self.handle_click(position, kind)
+            }
+            MouseEventKind::Down(MouseButton::Right) => {
+                self.handle_click(position, TreeClickKind::Right)
+            }
+            MouseEventKind::ScrollUp => changed_event(self.scroll_view_by(-1)),
+            MouseEventKind::ScrollDown => changed_event(self.scroll_view_by(1)),
+            _ => TreeEvent::Unchanged,
+        }
+    }
+
+    #[cfg(feature = "keymap")]
+    fn toggle_expander_at<C>(&mut self, position: Position) -> TreeEvent<Id, C> {
+        if self.cancel_pending() {
+            return TreeEvent::Changed;
+        }
+        let Some(TreeHit::Row { index, .. }) = self.hit_test(position) else {
+            return TreeEvent::Unchanged;
+        };
+        let selected = self.select_index(Some(index));
+        match self.change_selected_expansion(ExpansionAction::Toggle) {
+            TreeEvent::Unchanged => changed_event(selected),
+            event => event,
+        }
+    }
+
+    fn hit_column(&self, x: u16) -> Option<usize> {
+        let virtual_x = self.virtual_x(x)?;
         self.hit_map.columns.iter().position(|column| {
             virtual_x >= column.start && virtual_x < column.start.saturating_add(column.width)
         })
     }
+
+    /// Maps a screen column to the scrolled table, excluding the selection symbol.
+    const fn virtual_x(&self, x: u16) -> Option<u16> {
+        let local_x = x.saturating_sub(self.hit_map.table.x);
+        if local_x < self.hit_map.selection_width {
+            return None;
+        }
+        Some(local_x.saturating_add(self.hit_map.horizontal_offset))
+    }
 }
 
 const fn contains(area: Rect, position: Position) -> bool {
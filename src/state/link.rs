@@ -0,0 +1,75 @@
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use ratatui::layout::Rect;
+
+use super::TreeListViewState;
+
+/// Vertical scroll metrics published after each render.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeScrollMetrics {
+    /// The projection index of the first visible row.
+    pub offset: usize,
+    /// The number of rows that fit in the viewport.
+    pub viewport_height: usize,
+    /// The number of rows in the projection.
+    pub total_rows: usize,
+    /// The screen area occupied by rows, excluding the header.
+    pub rows_area: Rect,
+}
+
+impl TreeScrollMetrics {
+    /// Returns the projection indices of the visible rows.
+    #[must_use]
+    pub fn visible_rows(&self) -> std::ops::Range<usize> {
+        let end = self
+            .offset
+            .saturating_add(self.viewport_height)
+            .min(self.total_rows);
+        self.offset.min(end)..end
+    }
+}
+
+/// A shared handle through which other widgets follow the tree's scroll position.
+///
+/// Clones observe the same metrics, so a side gutter can render per-row data in lockstep.
+#[derive(Clone, Debug, Default)]
+pub struct TreeScrollLink(Arc<Mutex<TreeScrollMetrics>>);
+
+impl TreeScrollLink {
+    /// Returns the metrics of the latest render.
+    #[must_use]
+    pub fn metrics(&self) -> TreeScrollMetrics {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn publish(&self, metrics: TreeScrollMetrics) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = metrics;
+    }
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the scroll metrics of the latest render.
+    #[must_use]
+    pub const fn scroll_metrics(&self) -> TreeScrollMetrics {
+        TreeScrollMetrics {
+            offset: self.offset,
+            viewport_height: self.viewport_height,
+            total_rows: self.rendered_len,
+            rows_area: self.hit_map.rows,
+        }
+    }
+
+    /// Returns a handle updated with [`Self::scroll_metrics`] after every render.
+    pub fn scroll_link(&mut self) -> TreeScrollLink {
+        self.scroll_link
+            .get_or_insert_with(TreeScrollLink::default)
+            .clone()
+    }
+
+    pub(crate) fn publish_scroll(&self) {
+        if let Some(link) = &self.scroll_link {
+            link.publish(self.scroll_metrics());
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use std::hash::Hash;
 
 use crate::context::TreeMarkState;
-use crate::model::TreeModel;
-use crate::traversal::TreePostorder;
+use crate::model::{TreeChildren, TreeFilter, TreeModel};
+use crate::traversal::{TreePostorder, TreeWalk};
 
 use super::TreeListViewState;
 
@@ -66,6 +66,41 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.manual_marked.set_membership(id, marked)
     }
 
+    /// Sets the manual mark of every node in `ids`, for example to restore a persisted selection.
+    ///
+    /// Mark states are rebuilt once, however many ids change.
+    pub fn set_marks<I: IntoIterator<Item = Id>>(&mut self, ids: I, marked: bool) -> bool {
+        self.manual_marked.mutate(|values| {
+            ids.into_iter().fold(false, |changed, id| {
+                let toggled = if marked {
+                    values.insert(id)
+                } else {
+                    values.remove(&id)
+                };
+                changed | toggled
+            })
+        })
+    }
+
+    /// Marks every loaded node matching `filter`, optionally skipping branches.
+    ///
+    /// Unloaded and loading branches are not leaves. Ancestors of matches are only marked if they
+    /// match too.
+    pub fn mark_all_filtered<T, F>(&mut self, model: &T, filter: &F, leaves_only: bool) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+    {
+        let matches = TreeWalk::forest(model)
+            .filter(|node| {
+                !leaves_only
+                    || matches!(node.children, TreeChildren::Leaf | TreeChildren::Loaded([]))
+            })
+            .filter(|node| filter.is_match(model, node.id))
+            .map(|node| node.id);
+        self.set_marks(matches, true)
+    }
+
     /// Toggles a node's manual mark.
     pub fn toggle_marked(&mut self, id: Id) -> bool {
         let marked = !self.manual_marked.contains(&id);
@@ -0,0 +1,158 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::TreeListViewSnapshot;
+
+/// The first snapshot format, written before column width overrides were saved.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeSnapshotV1<Id> {
+    pub expanded: Vec<(Option<Id>, Id)>,
+    pub manual_marked: Vec<Id>,
+    pub selected: Option<Id>,
+    pub selected_column: Option<usize>,
+    pub offset: usize,
+    pub horizontal_offset: u16,
+    pub draw_lines: bool,
+}
+
+/// The second snapshot format, which added column width overrides.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeSnapshotV2<Id> {
+    pub expanded: Vec<(Option<Id>, Id)>,
+    pub manual_marked: Vec<Id>,
+    pub selected: Option<Id>,
+    pub selected_column: Option<usize>,
+    pub offset: usize,
+    pub horizontal_offset: u16,
+    pub draw_lines: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub column_widths: Vec<Option<u16>>,
+}
+
+/// The third snapshot format, which added flagged cells.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "Id: Deserialize<'de>")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeSnapshotV3<Id> {
+    pub expanded: Vec<(Option<Id>, Id)>,
+    pub manual_marked: Vec<Id>,
+    pub selected: Option<Id>,
+    pub selected_column: Option<usize>,
+    pub offset: usize,
+    pub horizontal_offset: u16,
+    pub draw_lines: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub column_widths: Vec<Option<u16>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cell_flags: Vec<(Id, usize)>,
+}
+
+/// A snapshot tagged with its format version, for applications that persist view state across
+/// upgrades of this crate.
+///
+/// Serialized with a `"version"` field next to the snapshot's own fields, so a file written by
+/// an older release still deserializes into its variant after an upgrade, and
+/// [`Self::into_current`] migrates it. Snapshots saved bare, before versioning, deserialize as
+/// [`TreeListViewSnapshot`] and convert with `into`.
+///
+/// Every variant holds a frozen struct; a change to the snapshot's fields adds a new version
+/// instead of editing an old one.
+///
+/// The internally tagged representation needs a self-describing format such as JSON, RON or
+/// YAML; formats like bincode cannot deserialize it.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "version"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeVersionedSnapshot<Id> {
+    #[cfg_attr(feature = "serde", serde(rename = "1"))]
+    V1(TreeSnapshotV1<Id>),
+    #[cfg_attr(feature = "serde", serde(rename = "2"))]
+    V2(TreeSnapshotV2<Id>),
+    #[cfg_attr(feature = "serde", serde(rename = "3"))]
+    V3(TreeSnapshotV3<Id>),
+}
+
+impl<Id> TreeVersionedSnapshot<Id> {
+    /// Migrates the snapshot to the current format, one version at a time.
+    #[must_use]
+    pub fn into_current(self) -> TreeListViewSnapshot<Id> {
+        match self {
+            Self::V1(snapshot) => TreeSnapshotV3::from(TreeSnapshotV2::from(snapshot)).into(),
+            Self::V2(snapshot) => TreeSnapshotV3::from(snapshot).into(),
+            Self::V3(snapshot) => snapshot.into(),
+        }
+    }
+}
+
+impl<Id> From<TreeSnapshotV1<Id>> for TreeSnapshotV2<Id> {
+    fn from(snapshot: TreeSnapshotV1<Id>) -> Self {
+        Self {
+            expanded: snapshot.expanded,
+            manual_marked: snapshot.manual_marked,
+            selected: snapshot.selected,
+            selected_column: snapshot.selected_column,
+            offset: snapshot.offset,
+            horizontal_offset: snapshot.horizontal_offset,
+            draw_lines: snapshot.draw_lines,
+            column_widths: Vec::new(),
+        }
+    }
+}
+
+impl<Id> From<TreeSnapshotV2<Id>> for TreeSnapshotV3<Id> {
+    fn from(snapshot: TreeSnapshotV2<Id>) -> Self {
+        Self {
+            expanded: snapshot.expanded,
+            manual_marked: snapshot.manual_marked,
+            selected: snapshot.selected,
+            selected_column: snapshot.selected_column,
+            offset: snapshot.offset,
+            horizontal_offset: snapshot.horizontal_offset,
+            draw_lines: snapshot.draw_lines,
+            column_widths: snapshot.column_widths,
+            cell_flags: Vec::new(),
+        }
+    }
+}
+
+impl<Id> From<TreeSnapshotV3<Id>> for TreeListViewSnapshot<Id> {
+    fn from(snapshot: TreeSnapshotV3<Id>) -> Self {
+        Self {
+            expanded: snapshot.expanded,
+            manual_marked: snapshot.manual_marked,
+            selected: snapshot.selected,
+            selected_column: snapshot.selected_column,
+            offset: snapshot.offset,
+            horizontal_offset: snapshot.horizontal_offset,
+            draw_lines: snapshot.draw_lines,
+            column_widths: snapshot.column_widths,
+            cell_flags: snapshot.cell_flags,
+        }
+    }
+}
+
+impl<Id> From<TreeListViewSnapshot<Id>> for TreeVersionedSnapshot<Id> {
+    fn from(snapshot: TreeListViewSnapshot<Id>) -> Self {
+        Self::V3(TreeSnapshotV3 {
+            expanded: snapshot.expanded,
+            manual_marked: snapshot.manual_marked,
+            selected: snapshot.selected,
+            selected_column: snapshot.selected_column,
+            offset: snapshot.offset,
+            horizontal_offset: snapshot.horizontal_offset,
+            draw_lines: snapshot.draw_lines,
+            column_widths: snapshot.column_widths,
+            cell_flags: snapshot.cell_flags,
+        })
+    }
+}
+
+impl<Id> TreeListViewSnapshot<Id> {
+    /// Converts a first-format snapshot, keeping the computed column widths.
+    #[must_use]
+    pub fn from_legacy(legacy: TreeSnapshotV1<Id>) -> Self {
+        TreeVersionedSnapshot::V1(legacy).into_current()
+    }
+}
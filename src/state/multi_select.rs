@@ -0,0 +1,77 @@
+use std::hash::Hash;
+
+use crate::collections::HybridSet;
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the ids to act on in bulk: the multi-selection, or else the selected node.
+    ///
+    /// Unlike marks, the multi-selection does not propagate to ancestors. Ids come in no
+    /// particular order and may include nodes that a collapse or filter has since hidden.
+    pub fn selection_ids(&self) -> impl Iterator<Item = Id> + '_ {
+        let cursor = self.multi_selected.is_empty().then_some(self.selected);
+        self.multi_selected.iter().copied().chain(cursor.flatten())
+    }
+
+    #[must_use]
+    pub fn is_in_selection(&self, id: Id) -> bool {
+        self.multi_selected.contains(&id)
+    }
+
+    /// Returns the node that range selections extend from.
+    #[must_use]
+    pub const fn selection_anchor(&self) -> Option<Id> {
+        self.selection_anchor
+    }
+
+    /// Replaces the multi-selection with the visible rows from `anchor` to `head`, in either
+    /// order, and moves the cursor to `head`.
+    ///
+    /// Returns `false` without changes when either node is not visible.
+    pub fn select_range(&mut self, anchor: Id, head: Id) -> bool {
+        let (Some(start), Some(end)) = (
+            self.projection.index_of(anchor),
+            self.projection.index_of(head),
+        ) else {
+            return false;
+        };
+        let rows = start.min(end)..=start.max(end);
+        let ids: HybridSet<Id> = self.projection.nodes()[rows]
+            .iter()
+            .filter(|node| !node.is_synthetic())
+            .map(|node| node.id())
+            .collect();
+        self.selection_anchor = Some(anchor);
+        let moved = self.select_index(Some(end));
+        self.multi_selected.replace(ids) | moved
+    }
+
+    /// Adds `id` to the multi-selection or removes it, and makes it the range anchor.
+    pub fn toggle_in_selection(&mut self, id: Id) -> bool {
+        self.selection_anchor = Some(id);
+        let selected = !self.multi_selected.contains(&id);
+        self.multi_selected.set_membership(id, selected)
+    }
+
+    /// Moves the cursor by `amount` rows and selects the range from the anchor to it, starting
+    /// the range at the cursor when there is no visible anchor.
+    pub fn extend_selection(&mut self, amount: isize) -> bool {
+        let Some(anchor) = self
+            .selection_anchor
+            .filter(|anchor| self.projection.index_of(*anchor).is_some())
+            .or(self.selected)
+        else {
+            return self.select_relative(amount);
+        };
+        let moved = self.select_relative(amount);
+        self.selected
+            .map_or(moved, |head| self.select_range(anchor, head) | moved)
+    }
+
+    /// Empties the multi-selection and forgets the anchor.
+    pub fn clear_selection_set(&mut self) -> bool {
+        self.selection_anchor = None;
+        self.multi_selected.clear()
+    }
+}
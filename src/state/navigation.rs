@@ -1,9 +1,12 @@
 use std::hash::Hash;
 
+use smallvec::SmallVec;
+
+use crate::context::TreeExpansionState;
 use crate::projection::ProjectedNode;
-use crate::style::TreeScrollPolicy;
+use crate::style::{TreeOverscroll, TreeScrollPolicy};
 
-use super::TreeListViewState;
+use super::{Focus, TreeFollowTail, TreeListViewState, TreeOverlaySelection};
 
 impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
     /// Возвращает идентификатор выбранной строки.
@@ -64,13 +67,62 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.select_index(Some(index))
     }
 
+    /// Moves the selection by `amount` rows, clamping at both ends.
+    ///
+    /// Starts at the last row for negative amounts and at the first row otherwise when nothing is
+    /// selected.
+    pub fn select_relative(&mut self, amount: isize) -> bool {
+        if self.projection.is_empty() {
+            return self.set_selection(None);
+        }
+        let last = self.projection.len() - 1;
+        let index = match self.selected_index() {
+            None if amount.is_negative() => last,
+            None => 0,
+            Some(index) if amount.is_negative() => index.saturating_sub(amount.unsigned_abs()),
+            Some(index) => index.saturating_add(amount.cast_unsigned()).min(last),
+        };
+        self.select_index(Some(index))
+    }
+
+    /// Selects the `nth` branch row below the selection, counting from one.
+    ///
+    /// Leaves the selection unchanged and returns `false` when fewer branches follow it.
+    pub fn select_nth_next_branch(&mut self, nth: usize) -> bool {
+        let start = self
+            .selected_index()
+            .map_or(0, |index| index.saturating_add(1));
+        let branch = self
+            .projection
+            .nodes()
+            .iter()
+            .enumerate()
+            .skip(start)
+            .filter(|(_, node)| !matches!(node.expansion(), TreeExpansionState::Leaf))
+            .nth(nth.saturating_sub(1))
+            .map(|(index, _)| index);
+        branch.is_some() && self.set_selection(branch)
+    }
+
     /// Selects the visible parent.
     pub fn select_parent(&mut self) -> bool {
-        let parent = self.selected_node().and_then(ProjectedNode::parent_index);
-        parent.is_some() && self.set_selection(parent)
+        let Some(node) = self.selected_node() else {
+            return false;
+        };
+        let Some(parent) = node.parent_index() else {
+            return false;
+        };
+        if let Some(parent_id) = node.parent() {
+            self.child_memory.insert(parent_id, node.id());
+        }
+        self.set_selection(Some(parent))
     }
 
-    /// Selects the first visible direct child.
+    /// Selects the child last left through [`Self::select_parent`] when it is still visible,
+    /// otherwise the first visible direct child, whether or not it has children of its own.
+    ///
+    /// A parent's remembered child is forgotten once a rebuilt projection no longer shows the
+    /// parent.
     pub fn select_first_child(&mut self) -> bool {
         let Some(index) = self.selected_index() else {
             return false;
@@ -78,12 +130,28 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         let Some(parent) = self.projection.nodes().get(index).copied() else {
             return false;
         };
-        let child = self
+        let mut descendants = self
             .projection
             .nodes()
-            .get(index.saturating_add(1))
-            .filter(|candidate| candidate.level() == parent.level().saturating_add(1))
-            .map(|_| index.saturating_add(1));
+            .get(index.saturating_add(1)..)
+            .unwrap_or_default()
+            .iter()
+            .take_while(|candidate| candidate.level() > parent.level());
+        let remembered = self.child_memory.get(&parent.id()).copied();
+        let child = remembered
+            .and_then(|remembered| {
+                descendants.position(|candidate| {
+                    candidate.parent_index() == Some(index) && candidate.id() == remembered
+                })
+            })
+            .or_else(|| {
+                self.projection
+                    .nodes()
+                    .get(index.saturating_add(1))
+                    .filter(|candidate| candidate.level() == parent.level().saturating_add(1))
+                    .map(|_| 0)
+            })
+            .map(|offset| index.saturating_add(1).saturating_add(offset));
         child.is_some() && self.set_selection(child)
     }
 
@@ -93,6 +161,29 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.selected_node().and_then(ProjectedNode::parent)
     }
 
+    /// Returns the ancestors of the selected row from its root down, for breadcrumbs or a path
+    /// bar in another pane.
+    ///
+    /// Follows the parent links of the current projection without walking the model, so it is
+    /// cheap to call every frame. A hidden synthetic root is included.
+    #[must_use]
+    pub fn ancestors_of_selection(&self) -> SmallVec<[Id; 16]> {
+        let nodes = self.projection.nodes();
+        let mut ancestors = SmallVec::new();
+        let mut cursor = self.selected_node();
+        while let Some(node) = cursor {
+            cursor = node
+                .parent_index()
+                .and_then(|index| nodes.get(index).copied());
+            if cursor.is_none() {
+                ancestors.extend(node.parent());
+            }
+            ancestors.extend(cursor.map(ProjectedNode::id));
+        }
+        ancestors.reverse();
+        ancestors
+    }
+
     #[must_use]
     pub fn selected_level(&self) -> Option<usize> {
         self.selected_node().map(ProjectedNode::level)
@@ -108,8 +199,18 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.projection.is_empty()
     }
 
+    /// Returns the visible rows in display order.
+    #[must_use]
+    pub fn visible_nodes(&self) -> &[ProjectedNode<Id>] {
+        self.projection.nodes()
+    }
+
     pub fn visible_ids(&self) -> impl Iterator<Item = Id> + '_ {
-        self.projection.nodes().iter().map(|node| node.id())
+        self.projection
+            .nodes()
+            .iter()
+            .filter(|node| !node.is_synthetic())
+            .map(|node| node.id())
     }
 
     #[must_use]
@@ -147,6 +248,98 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.set_offset(offset)
     }
 
+    /// Moves the selection and the viewport by `pages` times the last rendered viewport height,
+    /// keeping the selection on the same viewport row where possible.
+    ///
+    /// Before the first render, a page is one row. Pages ignore
+    /// [review mode](Self::set_review) and may select rows it would step over.
+    pub fn page_by(&mut self, pages: isize) -> bool {
+        let page = isize::try_from(self.viewport_height.max(1)).unwrap_or(isize::MAX);
+        let rows = page.saturating_mul(pages);
+        let scrolled = self.scroll_view_by(rows);
+        self.select_relative(rows) || scrolled
+    }
+
+    #[must_use]
+    pub const fn follow_tail(&self) -> TreeFollowTail {
+        self.follow_tail
+    }
+
+    /// Keeps the last row pinned at the bottom while the viewport is scrolled to the end.
+    ///
+    /// Scrolling up suspends following until the viewport reaches the end again.
+    pub const fn set_follow_tail(&mut self, follow: TreeFollowTail) {
+        self.follow_tail = follow;
+    }
+
+    #[must_use]
+    pub const fn overlay_selection(&self) -> TreeOverlaySelection {
+        self.overlay_selection
+    }
+
+    /// Sets whether overlay rows can be selected. Takes effect on the next selection change.
+    pub fn set_overlay_selection(&mut self, selection: TreeOverlaySelection) -> bool {
+        let changed = self.overlay_selection != selection;
+        self.overlay_selection = selection;
+        changed
+    }
+
+    /// Returns how many rows before the end [`Self::take_end_reached`] reports `true`, or `None`
+    /// when it never does.
+    #[must_use]
+    pub const fn end_threshold(&self) -> Option<usize> {
+        self.end_threshold
+    }
+
+    /// Makes [`Self::take_end_reached`] report when the selection or the last rendered viewport
+    /// gets within `threshold` rows of the last row, for example to request the next page of a
+    /// paged source.
+    pub fn set_end_threshold(&mut self, threshold: Option<usize>) -> bool {
+        let changed = self.end_threshold != threshold;
+        self.end_threshold = threshold;
+        self.end_reported = None;
+        changed
+    }
+
+    /// Returns `true` when the selection or the last rendered viewport is within the
+    /// [end threshold](Self::set_end_threshold) of the last row, so the application may fetch
+    /// more rows.
+    ///
+    /// Poll it after handling input or rendering. It reports once until the projection changes
+    /// length or the view moves away from the end.
+    #[must_use]
+    pub fn take_end_reached(&mut self) -> bool {
+        let Some(threshold) = self.end_threshold else {
+            return false;
+        };
+        let Some(last) = self.projection.len().checked_sub(1) else {
+            return false;
+        };
+        let viewport_bottom = self
+            .offset
+            .saturating_add(self.viewport_height)
+            .saturating_sub(1);
+        let bottom = self.selected_row.unwrap_or(0).max(viewport_bottom);
+        if bottom.saturating_add(threshold) < last {
+            self.end_reported = None;
+            return false;
+        }
+        if self.end_reported == Some(self.projection.len()) {
+            return false;
+        }
+        self.end_reported = Some(self.projection.len());
+        true
+    }
+
+    /// Scrolls the last rendered viewport to the end of the projection.
+    pub fn scroll_to_tail(&mut self) -> bool {
+        let offset = self
+            .projection
+            .len()
+            .saturating_sub(self.viewport_height.max(1));
+        self.set_offset(offset)
+    }
+
     #[must_use]
     pub const fn horizontal_offset(&self) -> u16 {
         self.horizontal_offset
@@ -169,6 +362,43 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.set_horizontal_offset(offset)
     }
 
+    /// Returns the leftmost column visible in the latest render, including a partially scrolled
+    /// one.
+    #[must_use]
+    pub fn first_visible_column(&self) -> Option<usize> {
+        let left = self
+            .horizontal_offset
+            .saturating_add(self.hit_map.selection_width);
+        self.hit_map
+            .columns
+            .iter()
+            .position(|column| column.start.saturating_add(column.width) > left)
+    }
+
+    /// Scrolls horizontally by whole columns, aligning a column with the left edge.
+    ///
+    /// Uses the column layout of the latest render; a partially scrolled column counts as one
+    /// step to the left.
+    pub fn scroll_columns_by(&mut self, amount: isize) -> bool {
+        let Some(first) = self.first_visible_column() else {
+            return false;
+        };
+        let columns = &self.hit_map.columns;
+        let left = self
+            .horizontal_offset
+            .saturating_add(self.hit_map.selection_width);
+        let target = if amount.is_negative() && columns[first].start < left {
+            first.saturating_sub(amount.unsigned_abs().saturating_sub(1))
+        } else {
+            first.saturating_add_signed(amount)
+        };
+        let target = target.min(columns.len().saturating_sub(1));
+        let offset = columns[target]
+            .start
+            .saturating_sub(self.hit_map.selection_width);
+        self.set_horizontal_offset(offset)
+    }
+
     pub(crate) fn clamp_horizontal_offset(&mut self, maximum: u16) {
         self.horizontal_offset = self.horizontal_offset.min(maximum);
     }
@@ -178,6 +408,15 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.selected_column
     }
 
+    /// Returns `true` while [`TreeViewAction::SelectHeaderRow`] keeps the selected column's
+    /// header focused.
+    ///
+    /// [`TreeViewAction::SelectHeaderRow`]: crate::TreeViewAction::SelectHeaderRow
+    #[must_use]
+    pub const fn is_header_selected(&self) -> bool {
+        matches!(self.focus, Focus::Header) && self.selected_column.is_some()
+    }
+
     pub fn select_column(&mut self, column: Option<usize>, column_count: usize) -> bool {
         let column = column.filter(|column| *column < column_count);
         let changed = self.selected_column != column;
@@ -214,7 +453,9 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         &mut self,
         viewport_height: usize,
         policy: TreeScrollPolicy,
+        overscroll: TreeOverscroll,
     ) {
+        let policy = self.scroll_policy_override.take().unwrap_or(policy);
         if !self.selection_needs_visibility {
             return;
         }
@@ -234,16 +475,56 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
             TreeScrollPolicy::CenterOnSelect => {
                 self.offset = selected.saturating_sub(height / 2);
             }
+            TreeScrollPolicy::PageJump => {
+                if selected < self.offset {
+                    let pages = (self.offset - selected).div_ceil(height);
+                    self.offset = self.offset.saturating_sub(pages.saturating_mul(height));
+                } else {
+                    let pages = (selected - self.offset) / height;
+                    self.offset = self.offset.saturating_add(pages.saturating_mul(height));
+                }
+            }
+        }
+        self.offset = self.offset.min(self.max_offset(height, overscroll));
+        self.selection_needs_visibility = false;
+    }
+
+    /// Returns `true` when the tail is followed and the last render showed the final row.
+    pub(crate) const fn is_following_tail(&self) -> bool {
+        matches!(self.follow_tail, TreeFollowTail::Enabled)
+            && self.offset.saturating_add(self.viewport_height) >= self.rendered_len
+    }
+
+    /// Pins the last row to the bottom when following the tail.
+    pub(crate) fn pin_tail(&mut self, viewport_height: usize) {
+        if self.is_following_tail() {
+            self.offset = self.projection.len().saturating_sub(viewport_height.max(1));
         }
+    }
+
+    /// Records the viewport used for tail following and page-sized movement.
+    pub(crate) const fn record_viewport(&mut self, viewport_height: usize) {
+        self.viewport_height = viewport_height;
+        self.rendered_len = self.projection.len();
+    }
+
+    pub(crate) fn clamp_offset_to_viewport(
+        &mut self,
+        viewport_height: usize,
+        overscroll: TreeOverscroll,
+    ) {
         self.offset = self
             .offset
-            .min(self.projection.len().saturating_sub(height));
-        self.selection_needs_visibility = false;
+            .min(self.max_offset(viewport_height, overscroll));
     }
 
-    pub(crate) fn clamp_offset_to_viewport(&mut self, viewport_height: usize) {
-        let maximum = self.projection.len().saturating_sub(viewport_height.max(1));
-        self.offset = self.offset.min(maximum);
+    /// Returns the largest offset that `viewport_height` rows may scroll to.
+    fn max_offset(&self, viewport_height: usize, overscroll: TreeOverscroll) -> usize {
+        let len = self.projection.len();
+        match overscroll {
+            TreeOverscroll::Disabled => len.saturating_sub(viewport_height.max(1)),
+            TreeOverscroll::Enabled => len.saturating_sub(1),
+        }
     }
 
     pub(crate) const fn ensure_column_visible(
@@ -265,7 +546,12 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
     }
 
     fn set_selection(&mut self, selected_row: Option<usize>) -> bool {
-        let selected_row = selected_row.filter(|&index| index < self.projection.len());
+        let selected_row = selected_row
+            .filter(|&index| index < self.projection.len())
+            .and_then(|index| {
+                let downwards = self.selected_row.is_none_or(|row| index > row);
+                self.selectable_row(index, downwards)
+            });
         let selected = selected_row
             .and_then(|index| self.projection.nodes().get(index))
             .map(|node| node.id());
@@ -277,4 +563,26 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         }
         changed
     }
+
+    /// Moves `index` off a placeholder row, or an overlay row unless overlays are selectable,
+    /// preferring the given direction. Returns `None` when no row can be selected.
+    pub(super) fn selectable_row(&self, index: usize, downwards: bool) -> Option<usize> {
+        let nodes = self.projection.nodes();
+        let selectable = |row: &usize| {
+            let node = nodes[*row];
+            node.placeholder().is_none()
+                && (node.overlay().is_none()
+                    || self.overlay_selection == TreeOverlaySelection::Selectable)
+        };
+        if index >= nodes.len() || selectable(&index) {
+            return Some(index);
+        }
+        let below = || (index..nodes.len()).find(selectable);
+        let above = || (0..index).rev().find(selectable);
+        if downwards {
+            below().or_else(above)
+        } else {
+            above().or_else(below)
+        }
+    }
 }
@@ -0,0 +1,128 @@
+use std::hash::Hash;
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
+use smallvec::SmallVec;
+
+use crate::glyphs::TreeLabelProvider;
+use crate::model::TreeModel;
+use crate::path::TreePath;
+use crate::traversal::TreeWalk;
+
+use super::{TreeListViewSnapshot, TreeListViewState};
+
+/// How many expansion, mark, and selection entries [`TreeListViewState::rebuild_preserving`]
+/// carried over to the new model.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeRemapReport {
+    pub remapped: usize,
+    pub dropped: usize,
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Captures the persistent part of the state keyed by label paths instead of ids.
+    ///
+    /// Unlike [`Self::snapshot`], the result stays meaningful after the model is rebuilt with
+    /// regenerated ids. Nodes that are not reachable through loaded children are dropped.
+    #[must_use]
+    pub fn snapshot_paths<T, L>(
+        &self,
+        model: &T,
+        labels: &L,
+        paths: TreePath<'_>,
+    ) -> TreeListViewSnapshot<String>
+    where
+        T: TreeModel<Id = Id>,
+        L: TreeLabelProvider<T>,
+    {
+        let index = paths.index(model, labels);
+        self.snapshot().map_ids(|id| index.get(&id).cloned())
+    }
+
+    /// Restores a snapshot taken by [`Self::snapshot_paths`], skipping paths that no longer
+    /// resolve.
+    pub fn restore_paths<T, L>(
+        &mut self,
+        model: &T,
+        labels: &L,
+        paths: TreePath<'_>,
+        snapshot: TreeListViewSnapshot<String>,
+    ) where
+        T: TreeModel<Id = Id>,
+        L: TreeLabelProvider<T>,
+    {
+        self.restore(snapshot.map_ids(|path| paths.resolve(model, labels, &path)));
+    }
+
+    /// Carries expansion, marks, and selection from `old` over to `new` after the model was
+    /// replaced wholesale, for example by a file watcher that rebuilds the tree on change.
+    ///
+    /// Each old node maps to the node below its mapped parent with the same id, or else to the
+    /// first one with the same label name, so regenerated ids survive when labels do. Nodes whose
+    /// parent did not map are dropped. Pair it with [`Self::set_min_rebuild_interval`] to
+    /// debounce bursts of changes.
+    pub fn rebuild_preserving<T, L>(&mut self, old: &T, new: &T, labels: &L) -> TreeRemapReport
+    where
+        T: TreeModel<Id = Id>,
+        L: TreeLabelProvider<T>,
+    {
+        let mut mapped: FxHashMap<Id, Id> =
+            FxHashMap::with_capacity_and_hasher(old.size_hint(), FxBuildHasher);
+        let mut siblings: FxHashMap<Option<Id>, FxHashMap<String, Id>> = FxHashMap::default();
+        for node in TreeWalk::forest(old) {
+            if mapped.contains_key(&node.id) {
+                continue;
+            }
+            let parent = match node.parent {
+                Some(parent) => match mapped.get(&parent) {
+                    Some(parent) => Some(*parent),
+                    None => continue,
+                },
+                None => None,
+            };
+            let candidates: SmallVec<[Id; 16]> = parent.map_or_else(
+                || new.roots().collect(),
+                |parent| {
+                    new.children(parent)
+                        .loaded_slice()
+                        .iter()
+                        .copied()
+                        .collect()
+                },
+            );
+            let target = if candidates.contains(&node.id) {
+                Some(node.id)
+            } else {
+                // Collected in reverse so the first sibling with a name wins.
+                let names = siblings.entry(parent).or_insert_with(|| {
+                    candidates
+                        .iter()
+                        .rev()
+                        .map(|id| (labels.label_parts(new, *id).name.into_owned(), *id))
+                        .collect()
+                });
+                names
+                    .get(labels.label_parts(old, node.id).name.as_ref())
+                    .copied()
+            };
+            if let Some(target) = target {
+                mapped.insert(node.id, target);
+            }
+        }
+
+        let snapshot = self.snapshot();
+        let before = snapshot_entries(&snapshot);
+        let snapshot = snapshot.map_ids(|id| mapped.get(&id).copied());
+        let remapped = snapshot_entries(&snapshot);
+        self.restore(snapshot);
+        TreeRemapReport {
+            remapped,
+            dropped: before - remapped,
+        }
+    }
+}
+
+fn snapshot_entries<Id>(snapshot: &TreeListViewSnapshot<Id>) -> usize {
+    snapshot.expanded.len()
+        + snapshot.manual_marked.len()
+        + usize::from(snapshot.selected.is_some())
+}
@@ -0,0 +1,53 @@
+use std::hash::Hash;
+
+use crate::columns::AdaptiveColumns;
+
+use super::TreeListViewState;
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the column widths set through resizing, which replace the computed ones.
+    #[must_use]
+    pub const fn column_widths(&self) -> &AdaptiveColumns {
+        &self.column_widths
+    }
+
+    /// Replaces every column width override.
+    pub fn set_column_widths(&mut self, widths: AdaptiveColumns) -> bool {
+        let changed = self.column_widths != widths;
+        self.column_widths = widths;
+        self.bump_column_widths(changed)
+    }
+
+    /// Overrides the width of one column, or restores its computed width with `None`.
+    pub fn set_column_width(&mut self, column: usize, width: Option<u16>) -> bool {
+        let changed = self.column_widths.set_width(column, width);
+        self.bump_column_widths(changed)
+    }
+
+    /// Widens the selected column by `amount` cells, or narrows it when negative, starting from
+    /// its width in the latest render. A column never shrinks below one cell.
+    pub fn resize_selected_column(&mut self, amount: isize) -> bool {
+        let Some(column) = self.selected_column else {
+            return false;
+        };
+        let Some(width) = self.column_widths.width(column).or_else(|| {
+            self.hit_map
+                .columns
+                .get(column)
+                .map(|hit_box| hit_box.width)
+        }) else {
+            return false;
+        };
+        let amount = amount.clamp(isize::from(i16::MIN), isize::from(i16::MAX));
+        let amount = i16::try_from(amount).unwrap_or_default();
+        let width = width.saturating_add_signed(amount).max(1);
+        self.set_column_width(column, Some(width))
+    }
+
+    const fn bump_column_widths(&mut self, changed: bool) -> bool {
+        if changed {
+            self.column_widths_revision = self.column_widths_revision.wrapping_add(1);
+        }
+        changed
+    }
+}
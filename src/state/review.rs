@@ -0,0 +1,73 @@
+use std::hash::Hash;
+
+use crate::context::{TreeMarkState, TreeMatchState};
+use crate::model::TreeModel;
+
+use super::{TreeListViewState, TreeReview};
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns which rows [`SelectNext`](crate::TreeViewAction::SelectNext) and
+    /// [`SelectPrev`](crate::TreeViewAction::SelectPrev) step between.
+    #[must_use]
+    pub const fn review(&self) -> TreeReview {
+        self.review
+    }
+
+    /// Makes [`SelectNext`](crate::TreeViewAction::SelectNext) and
+    /// [`SelectPrev`](crate::TreeViewAction::SelectPrev) step over every row that is not marked
+    /// or matching, for reviewing a subset of a large tree.
+    ///
+    /// Paging, jumps such as [`SelectFirst`](crate::TreeViewAction::SelectFirst), and mouse
+    /// selection still land on any row, so a page moves by the viewport height either way.
+    ///
+    /// [`ToggleReview`](crate::TreeViewAction::ToggleReview) switches between
+    /// [`TreeReview::Disabled`] and the last other mode set here, [`TreeReview::Marked`] at first.
+    pub fn set_review(&mut self, review: TreeReview) -> bool {
+        if review != TreeReview::Disabled {
+            self.review_rows = review;
+        }
+        let changed = self.review != review;
+        self.review = review;
+        changed
+    }
+
+    pub(super) fn toggle_review(&mut self) -> bool {
+        let review = match self.review {
+            TreeReview::Disabled => self.review_rows,
+            TreeReview::Marked | TreeReview::Matched => TreeReview::Disabled,
+        };
+        self.set_review(review)
+    }
+
+    /// Moves the selection by `amount` rows, counting only reviewed rows while review is on.
+    pub(super) fn select_by<T: TreeModel<Id = Id>>(&mut self, model: &T, amount: isize) -> bool {
+        match self.review {
+            TreeReview::Disabled => return self.select_relative(amount),
+            TreeReview::Marked => self.ensure_mark_states(model),
+            TreeReview::Matched => {}
+        }
+        let nodes = self.projection.nodes();
+        let reviewed = |index: &usize| {
+            let node = nodes[*index];
+            !node.is_synthetic()
+                && match self.review {
+                    TreeReview::Disabled => true,
+                    TreeReview::Marked => {
+                        self.mark_state_cached(node.id()) == TreeMarkState::Marked
+                    }
+                    TreeReview::Matched => node.match_state() != TreeMatchState::Ancestor,
+                }
+        };
+        let count = amount.unsigned_abs();
+        let target = if amount.is_negative() {
+            let end = self.selected_index().unwrap_or(nodes.len());
+            (0..end).rev().filter(reviewed).take(count).last()
+        } else {
+            let start = self
+                .selected_index()
+                .map_or(0, |index| index.saturating_add(1));
+            (start..nodes.len()).filter(reviewed).take(count).last()
+        };
+        target.is_some() && self.select_index(target)
+    }
+}
@@ -0,0 +1,85 @@
+use std::hash::Hash;
+
+use crate::collections::HybridSet;
+use crate::model::TreeRevision;
+
+use super::{ExpansionPath, TreeListViewState};
+
+/// Expansion and marks shared by several views of the same model.
+///
+/// Each [`TreeListViewState`] keeps its own selection and scrolling and exchanges the shared part
+/// through [`TreeListViewState::sync_shared`], typically before handling input and rendering.
+#[derive(Clone, Debug)]
+pub struct TreeSharedState<Id> {
+    expanded: HybridSet<ExpansionPath<Id>>,
+    marked: HybridSet<Id>,
+    revision: TreeRevision,
+}
+
+impl<Id: Copy + Eq + Hash> TreeSharedState<Id> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            expanded: HybridSet::default(),
+            marked: HybridSet::default(),
+            revision: TreeRevision::INITIAL,
+        }
+    }
+
+    /// Returns the revision advanced by every change published by a view.
+    #[must_use]
+    pub const fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+}
+
+impl<Id: Copy + Eq + Hash> Default for TreeSharedState<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Local and shared revisions observed by the last synchronization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct SharedStamp {
+    shared: TreeRevision,
+    expansion: TreeRevision,
+    marks: TreeRevision,
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Exchanges expansion and marks with `shared`.
+    ///
+    /// Local changes since the previous call are published; otherwise changes published by other
+    /// views are adopted. When both sides changed, the local state wins. The first view to sync
+    /// with a new shared state seeds it. Returns `true` when this state adopted shared changes.
+    pub fn sync_shared(&mut self, shared: &mut TreeSharedState<Id>) -> bool {
+        let local_changed =
+            self.shared_stamp
+                .map_or(shared.revision == TreeRevision::INITIAL, |stamp| {
+                    stamp.expansion != self.expanded.revision()
+                        || stamp.marks != self.manual_marked.revision()
+                });
+        let adopted = if local_changed {
+            shared.expanded = self.expanded.values.clone();
+            shared.marked = self.manual_marked.values.clone();
+            shared.revision.advance();
+            false
+        } else if self
+            .shared_stamp
+            .is_none_or(|stamp| stamp.shared != shared.revision)
+        {
+            let expanded = self.expanded.replace(shared.expanded.clone());
+            let marked = self.manual_marked.replace(shared.marked.clone());
+            expanded || marked
+        } else {
+            false
+        };
+        self.shared_stamp = Some(SharedStamp {
+            shared: shared.revision,
+            expansion: self.expanded.revision(),
+            marks: self.manual_marked.revision(),
+        });
+        adopted
+    }
+}
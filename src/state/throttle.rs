@@ -0,0 +1,63 @@
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use crate::model::{TreeModel, TreeQuery};
+
+use super::TreeListViewState;
+
+/// Coalesces projection rebuilds caused by frequent model revisions.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct RebuildThrottle {
+    interval: Duration,
+    now: Option<Instant>,
+    last_rebuild: Option<Instant>,
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Returns the minimum time between rebuilds triggered only by model revisions.
+    #[must_use]
+    pub const fn min_rebuild_interval(&self) -> Duration {
+        self.rebuild_throttle.interval
+    }
+
+    /// Coalesces rebuilds for streaming models, keeping the last projection in the meantime.
+    ///
+    /// Query and expansion changes still rebuild immediately, as do [`Self::apply_edit`] calls.
+    /// Throttling takes effect once the application reports time through [`Self::tick`].
+    pub fn set_min_rebuild_interval(&mut self, interval: Duration) -> bool {
+        let changed = self.rebuild_throttle.interval != interval;
+        self.rebuild_throttle.interval = interval;
+        changed
+    }
+
+    /// Advances the clock used by the rebuild throttle and counts down
+    /// [flash highlights](Self::flash), typically once per frame. Returns `true` when a flash
+    /// expired, so the caller knows a redraw is due.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        self.rebuild_throttle.now = Some(now);
+        self.advance_flashes()
+    }
+
+    pub(super) fn defers_rebuild<T: TreeModel<Id = Id>, F, S>(
+        &self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+    ) -> bool {
+        let throttle = self.rebuild_throttle;
+        let (Some(now), Some(last)) = (throttle.now, throttle.last_rebuild) else {
+            return false;
+        };
+        now.saturating_duration_since(last) < throttle.interval
+            && self
+                .projection
+                .is_current_except_model(model, query, self.expanded.revision())
+    }
+
+    pub(super) const fn record_rebuild(&mut self) {
+        self.rebuild_throttle.last_rebuild = self.rebuild_throttle.now;
+    }
+
+    pub(super) const fn reset_rebuild_throttle(&mut self) {
+        self.rebuild_throttle.last_rebuild = None;
+    }
+}
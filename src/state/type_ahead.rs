@@ -0,0 +1,128 @@
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "keys")]
+use crate::key::{TreeKey, TreeKeyCode, TreeKeyKind, TreeKeyModifiers};
+
+use crate::glyphs::TreeLabelProvider;
+use crate::model::{TreeFilter, TreeModel, TreeQuery, TreeSort};
+
+use super::TreeListViewState;
+
+/// The pause after which the next typed character starts a new prefix.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The lowercased prefix typed so far and when its last character arrived.
+#[derive(Debug, Default)]
+pub(super) struct TypeAhead {
+    prefix: String,
+    last: Option<Instant>,
+}
+
+impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
+    /// Adds `character` to the type-ahead prefix and selects the next visible node whose label
+    /// name starts with it, ignoring case.
+    ///
+    /// The prefix restarts after a one-second pause or [`Self::clear_type_ahead`]. A prefix of
+    /// one repeated character searches after the selection, so typing `a` again cycles between
+    /// nodes starting with `a`; a longer prefix keeps the selection while it still matches.
+    /// Returns `true` when the selection changed.
+    pub fn type_ahead<T, F, S, L>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        labels: &L,
+        character: char,
+        now: Instant,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        L: TreeLabelProvider<T>,
+    {
+        let expired = self
+            .type_ahead
+            .last
+            .is_some_and(|last| now.saturating_duration_since(last) > TYPE_AHEAD_TIMEOUT);
+        if expired {
+            self.type_ahead.prefix.clear();
+        }
+        self.type_ahead.last = Some(now);
+        self.type_ahead.prefix.extend(character.to_lowercase());
+        self.ensure_projection(model, query);
+
+        let typed = self.type_ahead.prefix.as_str();
+        let mut characters = typed.char_indices();
+        let cycling = characters
+            .next()
+            .map(|(_, first)| (first, characters.all(|(_, next)| next == first)))
+            .filter(|&(_, repeated)| repeated)
+            .map(|(first, _)| first.len_utf8());
+        let (prefix, skip) = cycling.map_or((typed, 0), |first| (&typed[..first], 1));
+        let nodes = self.projection.nodes();
+        let start = self.selected_row.map_or(0, |row| row + skip);
+        let found = (0..nodes.len())
+            .map(|step| (start + step) % nodes.len())
+            .find(|&row| {
+                let node = nodes[row];
+                !node.is_synthetic()
+                    && labels
+                        .label_parts(model, node.id())
+                        .name
+                        .to_lowercase()
+                        .starts_with(prefix)
+            });
+        found.is_some_and(|row| self.select_index(Some(row)))
+    }
+
+    /// Returns the type-ahead prefix, lowercased, or `None` when nothing has been typed.
+    ///
+    /// The prefix is kept after its timeout until the next character replaces it.
+    #[must_use]
+    pub fn type_ahead_prefix(&self) -> Option<&str> {
+        Some(self.type_ahead.prefix.as_str()).filter(|prefix| !prefix.is_empty())
+    }
+
+    /// Ends type-ahead, as when the user presses `Esc`.
+    pub fn clear_type_ahead(&mut self) -> bool {
+        self.type_ahead.last = None;
+        let changed = !self.type_ahead.prefix.is_empty();
+        self.type_ahead.prefix.clear();
+        changed
+    }
+
+    #[cfg(feature = "keys")]
+    /// Feeds a key to [`Self::type_ahead`] and returns `true` when the key was consumed.
+    ///
+    /// Printable characters extend the prefix, and `Esc` ends type-ahead when it is active.
+    /// Other keys are left for the key bindings, so applications route keys here only while
+    /// their type-to-select mode is on.
+    pub fn handle_type_ahead_key<T, F, S, L>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        labels: &L,
+        key: impl Into<TreeKey>,
+        now: Instant,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        L: TreeLabelProvider<T>,
+    {
+        let key = key.into();
+        if key.kind == TreeKeyKind::Release {
+            return false;
+        }
+        match (key.code, key.modifiers) {
+            (TreeKeyCode::Char(character), TreeKeyModifiers::NONE | TreeKeyModifiers::SHIFT) => {
+                self.type_ahead(model, query, labels, character, now);
+                true
+            }
+            (TreeKeyCode::Esc, _) => self.clear_type_ahead(),
+            _ => false,
+        }
+    }
+}
@@ -3,44 +3,102 @@ use std::hash::Hash;
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use smallvec::SmallVec;
 
+use crate::collections::HybridSet;
 use crate::context::TreeExpansionState;
+use crate::glyphs::TreeLabelProvider;
 use crate::model::{
-    TreeChildren, TreeFilter, TreeModel, TreeQuery, TreeSelectionFallback, TreeSort,
+    TreeChildren, TreeFilter, TreeFilterConfig, TreeModel, TreeQuery, TreeSelectionFallback,
+    TreeSort,
 };
 use crate::projection::{OccurrencePath, ProjectedNode};
-use crate::traversal::TreeWalk;
+use crate::style::TreeScrollPolicy;
+use crate::traversal::{TreePostorder, TreeWalk};
 
-use super::{ExpansionPath, TreeListViewState};
+use super::{ExpansionPath, RevisionedSet, TreeListViewState};
 
 impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
     /// Synchronizes the projection with model, query, and expansion revisions.
     ///
-    /// Returns `true` when the projection was rebuilt.
+    /// Returns `true` when the projection was rebuilt. Model-only changes may be deferred by
+    /// [`Self::set_min_rebuild_interval`].
     pub fn ensure_projection<T, F, S>(&mut self, model: &T, query: &TreeQuery<F, S>) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        self.sync_projection(model, query, None)
+    }
+
+    /// Like [`Self::ensure_projection`], but polls `cancel` during the rebuild and abandons it
+    /// once that returns `true`, for example when a newer filter query is already waiting.
+    ///
+    /// A cancelled rebuild keeps the previous projection and selection, returns `false`, and is
+    /// retried by the next call.
+    pub fn ensure_projection_cancellable<T, F, S>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        cancel: &dyn Fn() -> bool,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+    {
+        self.sync_projection(model, query, Some(cancel))
+    }
+
+    fn sync_projection<T, F, S>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        cancel: Option<&dyn Fn() -> bool>,
+    ) -> bool
     where
         T: TreeModel<Id = Id>,
         F: TreeFilter<T>,
         S: TreeSort<T>,
     {
         let expansion_revision = self.expanded.revision();
-        if self.projection.is_current(model, query, expansion_revision) {
+        if self.projection.is_current(model, query, expansion_revision)
+            || self.defers_rebuild(model, query)
+        {
             return false;
         }
 
+        let following_tail = self.is_following_tail();
         let old_index = self.selected_row;
         let old_path = old_index.and_then(|index| self.projection.occurrence_path(index));
+        let old_siblings = old_index.map_or_else(SmallVec::new, |index| {
+            self.sibling_candidates(index, query.selection_fallback())
+        });
         let expanded = &self.expanded;
-        self.projection
-            .rebuild(model, query, expansion_revision, |parent, id| {
-                expanded.contains(&ExpansionPath::new(parent, id))
-            });
+        let is_expanded = |parent, id| expanded.contains(&ExpansionPath::new(parent, id));
+        if let Some(cancel) = cancel {
+            let mut next = self.projection.successor();
+            if !next.rebuild(model, query, expansion_revision, is_expanded, cancel) {
+                return false;
+            }
+            self.projection = next;
+        } else {
+            self.projection
+                .rebuild(model, query, expansion_revision, is_expanded, &|| false);
+        }
         self.restore_selection_after_rebuild(
             old_index,
             old_path.as_ref(),
+            &old_siblings,
             query.selection_fallback(),
         );
-        self.selection_needs_visibility = self.selected.is_some();
+        let projection = &self.projection;
+        self.child_memory
+            .retain(|parent, _| projection.index_of(*parent).is_some());
+        if !following_tail {
+            self.selection_needs_visibility = self.selected.is_some();
+        }
         self.clamp_offsets();
+        self.record_rebuild();
         true
     }
 
@@ -65,6 +123,41 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         }
     }
 
+    /// Selects the first node whose label name satisfies `predicate`, in the order the query's
+    /// filter and sort display the rows, for a "go to node named X" command.
+    ///
+    /// Loaded children inside collapsed branches are searched too. Only the ancestors of the
+    /// first match are expanded to reveal it. Returns the number of matching nodes, counting a
+    /// node shared by several parents once, which is zero when nothing was selected.
+    pub fn select_first_matching<T, F, S, L, P>(
+        &mut self,
+        model: &T,
+        query: &TreeQuery<F, S>,
+        labels: &L,
+        mut predicate: P,
+    ) -> usize
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+        S: TreeSort<T>,
+        L: TreeLabelProvider<T>,
+        P: FnMut(&str) -> bool,
+    {
+        self.ensure_projection(model, query);
+        let mut first = None;
+        let mut count = 0;
+        for id in self.projection.displayable_ids(model, query) {
+            if predicate(&labels.label_parts(model, id).name) {
+                first.get_or_insert(id);
+                count += 1;
+            }
+        }
+        if let Some(first) = first {
+            self.select_by_id(model, query, first);
+        }
+        count
+    }
+
     /// Expands every loaded ancestor of a node.
     pub fn expand_to<T: TreeModel<Id = Id>>(&mut self, model: &T, target: Id) -> bool {
         let hint = model.size_hint();
@@ -104,11 +197,89 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
 
     /// Expands every loaded branch in the forest.
     pub fn expand_all<T: TreeModel<Id = Id>>(&mut self, model: &T) -> bool {
-        self.expanded.mutate(|expanded| {
+        Self::expand_loaded(&mut self.expanded, model, |_| true)
+    }
+
+    /// Expands the loaded branches that lead to matches of the last filtered projection, or
+    /// every loaded branch when that projection was not filtered.
+    ///
+    /// Branches hidden by the filter stay collapsed, so clearing the filter reveals only the
+    /// paths that were found.
+    pub fn expand_all_filtered<T: TreeModel<Id = Id>>(&mut self, model: &T) -> bool {
+        if self.projection.filter_config() == TreeFilterConfig::Disabled {
+            return self.expand_all(model);
+        }
+        let projection = &self.projection;
+        let kept = |id| projection.is_kept(id);
+        Self::expand_loaded(&mut self.expanded, model, kept)
+    }
+
+    /// Expands the loaded branches leading to every marked node, so the whole marked set is
+    /// visible at once, for example after restoring marks from a snapshot into a collapsed tree.
+    ///
+    /// Marked nodes are expanded only when marks lie below them. Other branches keep their
+    /// expansion.
+    pub fn reveal_marked<T: TreeModel<Id = Id>>(&mut self, model: &T) -> bool {
+        if self.manual_marked.is_empty() {
+            return false;
+        }
+        let marked = &self.manual_marked;
+        Self::expand_paths_to(&mut self.expanded, model, |id| marked.contains(&id))
+    }
+
+    /// Persists the expansion that `filter` forces under `config`, so clearing the filter keeps
+    /// the found branches open.
+    ///
+    /// Every loaded branch leading to a match is expanded, as auto-expansion shows it, without
+    /// rebuilding the projection. Returns `false` without changes unless `config` auto-expands.
+    pub fn commit_filtered_expansion<T, F>(
+        &mut self,
+        model: &T,
+        filter: &F,
+        config: TreeFilterConfig,
+    ) -> bool
+    where
+        T: TreeModel<Id = Id>,
+        F: TreeFilter<T>,
+    {
+        if !matches!(
+            config,
+            TreeFilterConfig::Enabled {
+                auto_expand: true,
+                ..
+            }
+        ) {
+            return false;
+        }
+        Self::expand_paths_to(&mut self.expanded, model, |id| filter.is_match(model, id))
+    }
+
+    /// Expands the loaded branches leading to every node accepted by `pred`.
+    fn expand_paths_to<T: TreeModel<Id = Id>>(
+        expanded: &mut RevisionedSet<ExpansionPath<Id>>,
+        model: &T,
+        pred: impl Fn(Id) -> bool,
+    ) -> bool {
+        let mut kept = HybridSet::with_capacity(model.size_hint());
+        for node in TreePostorder::forest(model) {
+            if pred(node.id) || node.children.iter().any(|child| kept.contains(child)) {
+                kept.insert(node.id);
+            }
+        }
+        Self::expand_loaded(expanded, model, |id| kept.contains(&id))
+    }
+
+    fn expand_loaded<T: TreeModel<Id = Id>>(
+        expanded: &mut RevisionedSet<ExpansionPath<Id>>,
+        model: &T,
+        kept: impl Fn(Id) -> bool,
+    ) -> bool {
+        expanded.mutate(|expanded| {
             let mut changed = false;
             for node in TreeWalk::forest(model) {
                 if let TreeChildren::Loaded(children) = node.children
-                    && !children.is_empty()
+                    && kept(node.id)
+                    && children.iter().any(|child| kept(*child))
                 {
                     changed |= expanded.insert(ExpansionPath::new(node.parent, node.id));
                 }
@@ -122,6 +293,29 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.expanded.clear()
     }
 
+    /// Collapses every branch except the ancestors of the selected occurrence and centers it on
+    /// the next render.
+    ///
+    /// Returns `false` without recentering when only those ancestors were expanded already.
+    pub fn collapse_to_selection(&mut self) -> bool {
+        let Some(index) = self.selected_row else {
+            return false;
+        };
+        let mut paths = HybridSet::default();
+        let nodes = self.projection.nodes();
+        let mut cursor = nodes.get(index).and_then(|node| node.parent_index());
+        while let Some(node) = cursor.and_then(|index| nodes.get(index)) {
+            paths.insert(ExpansionPath::new(node.parent(), node.id()));
+            cursor = node.parent_index();
+        }
+        if !self.expanded.replace(paths) {
+            return false;
+        }
+        self.scroll_policy_override = Some(TreeScrollPolicy::CenterOnSelect);
+        self.selection_needs_visibility = true;
+        true
+    }
+
     /// Sets the expansion state of a specific path.
     pub fn set_expanded(&mut self, id: Id, parent: Option<Id>, expanded: bool) -> bool {
         let path = ExpansionPath::new(parent, id);
@@ -145,6 +339,22 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         self.expanded.iter().map(|path| (path.parent, path.id))
     }
 
+    /// Returns the largest subtree that recursive toggling walks, or `None` without a limit.
+    #[must_use]
+    pub const fn recursive_expansion_budget(&self) -> Option<usize> {
+        self.recursive_budget
+    }
+
+    /// Limits recursive toggling to subtrees of at most `budget` nodes.
+    ///
+    /// Larger subtrees only toggle the node itself, so a huge tree never stalls a frame. Only the
+    /// first `budget + 1` nodes are visited to decide.
+    pub fn set_recursive_expansion_budget(&mut self, budget: Option<usize>) -> bool {
+        let changed = self.recursive_budget != budget;
+        self.recursive_budget = budget;
+        changed
+    }
+
     pub(crate) fn set_expanded_recursive<T: TreeModel<Id = Id>>(
         &mut self,
         model: &T,
@@ -152,6 +362,11 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         parent: Option<Id>,
         expand: bool,
     ) -> bool {
+        if let Some(budget) = self.recursive_budget
+            && TreeWalk::subtree(model, parent, root).nth(budget).is_some()
+        {
+            return self.set_expanded(root, parent, expand);
+        }
         self.expanded.mutate(|expanded| {
             let mut changed = false;
             for node in TreeWalk::subtree(model, parent, root) {
@@ -169,10 +384,30 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
         })
     }
 
+    /// Lists the siblings a vanished selection may move to, in order of preference.
+    fn sibling_candidates(
+        &self,
+        index: usize,
+        fallback: TreeSelectionFallback,
+    ) -> SmallVec<[Id; 8]> {
+        let forward = match fallback {
+            TreeSelectionFallback::NextSibling => true,
+            TreeSelectionFallback::PreviousSibling => false,
+            TreeSelectionFallback::ParentThenNearest
+            | TreeSelectionFallback::Nearest
+            | TreeSelectionFallback::Clear => return SmallVec::new(),
+        };
+        self.projection
+            .siblings_from(index, forward)
+            .chain(self.projection.siblings_from(index, !forward))
+            .collect()
+    }
+
     fn restore_selection_after_rebuild(
         &mut self,
         old_index: Option<usize>,
         old_path: Option<&OccurrencePath<Id>>,
+        old_siblings: &[Id],
         fallback: TreeSelectionFallback,
     ) {
         if let Some(path) = old_path {
@@ -189,7 +424,17 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
                 return;
             }
 
-            if matches!(fallback, TreeSelectionFallback::ParentThenNearest) {
+            for sibling in old_siblings {
+                if let Some(index) = self.projection.index_of_path(&path.with_sibling(*sibling)) {
+                    self.select_rebuilt_row(Some(index));
+                    return;
+                }
+            }
+
+            if !matches!(
+                fallback,
+                TreeSelectionFallback::Nearest | TreeSelectionFallback::Clear
+            ) {
                 for end in (1..path.len()).rev() {
                     if let Some(index) = self.projection.index_of_path_prefix(path, end) {
                         self.select_rebuilt_row(Some(index));
@@ -207,16 +452,19 @@ impl<Id: Copy + Eq + Hash> TreeListViewState<Id> {
 
         let selected_row = match fallback {
             TreeSelectionFallback::Clear => None,
-            TreeSelectionFallback::Nearest | TreeSelectionFallback::ParentThenNearest => old_index
-                .and_then(|index| {
-                    let index = index.min(self.projection.len().saturating_sub(1));
-                    self.projection.nodes().get(index).map(|_| index)
-                }),
+            TreeSelectionFallback::Nearest
+            | TreeSelectionFallback::ParentThenNearest
+            | TreeSelectionFallback::NextSibling
+            | TreeSelectionFallback::PreviousSibling => old_index.and_then(|index| {
+                let index = index.min(self.projection.len().saturating_sub(1));
+                self.projection.nodes().get(index).map(|_| index)
+            }),
         };
         self.select_rebuilt_row(selected_row);
     }
 
     fn select_rebuilt_row(&mut self, selected_row: Option<usize>) {
+        let selected_row = selected_row.and_then(|index| self.selectable_row(index, false));
         self.selected = selected_row
             .and_then(|index| self.projection.nodes().get(index))
             .map(|node| node.id());
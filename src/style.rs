@@ -1,6 +1,9 @@
-use ratatui::style::Style;
+use ratatui::layout::Alignment;
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::Line;
-use ratatui::widgets::Borders;
+use ratatui::widgets::{Block, BorderType, Borders, TitlePosition};
+
+use crate::glyphs::TreeLeafAlignment;
 
 /// Policy for keeping the selection in the vertical viewport.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -8,6 +11,8 @@ pub enum TreeScrollPolicy {
     #[default]
     KeepInView,
     CenterOnSelect,
+    /// Scrolls by whole viewports once the selection crosses an edge, like a pager.
+    PageJump,
 }
 
 /// Strategy for building table rows.
@@ -18,6 +23,16 @@ pub enum TreeRowRendering {
     Virtualized,
 }
 
+/// Whether the viewport may scroll past the last row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeOverscroll {
+    #[default]
+    Disabled,
+    /// Allows up to one viewport minus a row of blank space below the last row, so the last rows
+    /// can be scrolled to the top, like an editor's virtual space.
+    Enabled,
+}
+
 /// Horizontal layout policy.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TreeHorizontalScroll {
@@ -26,29 +41,244 @@ pub enum TreeHorizontalScroll {
     Enabled,
 }
 
+/// Row numbers drawn in a gutter before the highlight symbol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeRowNumbers {
+    #[default]
+    Hidden,
+    /// One-based row positions in the projection.
+    Absolute,
+    /// Distances from the selected row, which shows its absolute position as in Vim.
+    Relative,
+}
+
+/// Scroll information drawn in the bottom-right corner of the block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreePositionIndicator {
+    #[default]
+    Hidden,
+    /// How far the viewport has scrolled towards the last row, such as `42%`.
+    Percent,
+    /// The selected row and the row count, such as `120/3400`.
+    Position,
+}
+
+/// A thumb in the bottom border under the tree column showing how much of the widest visible
+/// label the column clips.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeLabelScrollbar {
+    #[default]
+    Hidden,
+    /// Drawn while a visible label is wider than the tree column, as measured by
+    /// [`TreeLabelRenderer::width`](crate::TreeLabelRenderer::width).
+    ///
+    /// Label providers measure their labels, but custom renderers that do not override `width`
+    /// return `None` and never show the bar. The thumb skips border cells taken by a bottom
+    /// title, the legend, a prompt, or the position indicator.
+    Visible,
+}
+
+/// How rows guard against ids that the model no longer contains.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeModelCheck {
+    /// Rows call the model for every projected id.
+    #[default]
+    Trust,
+    /// Rows whose id fails [`TreeModel::contains`](crate::TreeModel::contains) show
+    /// `missing_node` in `error_style` without calling the label, cell, or fill callbacks, and
+    /// are reported through
+    /// [`take_model_event`](crate::TreeListViewState::take_model_event).
+    Verify,
+}
+
+/// Semantic styles that [`TreeListViewStyle::from_theme`] spreads over every style knob.
+///
+/// Switching a theme restyles the whole view at once, and knobs added later derive from the
+/// same roles instead of staying unstyled. The default theme leaves everything unstyled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeTheme {
+    /// The border and the detail popup.
+    pub border: Style,
+    /// The selected row and cell.
+    pub selection: Style,
+    /// Secondary highlights: the selected column, the selected cell on top of `selection`, and
+    /// row backgrounds painted by fill fractions.
+    pub hover: Style,
+    /// Tree guides.
+    pub guides: Style,
+    /// Marked rows; partially marked rows combine it with `disabled`.
+    pub marks: Style,
+    /// Direct filter matches.
+    pub matches: Style,
+    /// Secondary content: ancestors kept by a filter, row numbers, placeholders, and detail keys.
+    pub disabled: Style,
+    /// Flash highlights, confirmation prompts, and rows of missing nodes.
+    pub flash: Style,
+    pub scrollbar: Style,
+}
+
+impl TreeTheme {
+    /// A theme for dark terminal backgrounds.
+    #[must_use]
+    pub const fn dark() -> Self {
+        Self {
+            border: Style::new().fg(Color::Gray),
+            selection: Style::new()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+            hover: Style::new().add_modifier(Modifier::UNDERLINED),
+            guides: Style::new().fg(Color::DarkGray),
+            marks: Style::new().fg(Color::Yellow),
+            matches: Style::new().fg(Color::Cyan),
+            disabled: Style::new().fg(Color::DarkGray),
+            flash: Style::new().fg(Color::Black).bg(Color::Yellow),
+            scrollbar: Style::new().fg(Color::Gray),
+        }
+    }
+
+    /// A theme for light terminal backgrounds.
+    #[must_use]
+    pub const fn light() -> Self {
+        Self {
+            border: Style::new().fg(Color::DarkGray),
+            selection: Style::new()
+                .fg(Color::Black)
+                .bg(Color::LightBlue)
+                .add_modifier(Modifier::BOLD),
+            hover: Style::new().add_modifier(Modifier::UNDERLINED),
+            guides: Style::new().fg(Color::Gray),
+            marks: Style::new().fg(Color::Magenta),
+            matches: Style::new().fg(Color::Blue),
+            disabled: Style::new().fg(Color::Gray),
+            flash: Style::new().fg(Color::Black).bg(Color::LightYellow),
+            scrollbar: Style::new().fg(Color::DarkGray),
+        }
+    }
+
+    /// A theme that relies on modifiers and bright colors rather than subtle shades.
+    #[must_use]
+    pub const fn high_contrast() -> Self {
+        Self {
+            border: Style::new().fg(Color::White),
+            selection: Style::new().add_modifier(Modifier::REVERSED.union(Modifier::BOLD)),
+            hover: Style::new().add_modifier(Modifier::UNDERLINED),
+            guides: Style::new().fg(Color::White),
+            marks: Style::new()
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            matches: Style::new()
+                .fg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD),
+            disabled: Style::new().fg(Color::Gray),
+            flash: Style::new()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            scrollbar: Style::new().fg(Color::White),
+        }
+    }
+}
+
 /// Visual tree configuration.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TreeListViewStyle<'a> {
     pub title: Option<Line<'a>>,
+    /// A second title on the bottom border, independent of `title_position`.
+    pub bottom_title: Option<Line<'a>>,
+    pub title_alignment: Alignment,
+    pub title_position: TitlePosition,
     pub block_style: Style,
     pub border_style: Style,
     pub highlight_style: Style,
     pub column_highlight_style: Style,
     pub cell_highlight_style: Style,
     pub marked_style: Style,
+    /// Patched over rows in the multi-selection, see
+    /// [`select_range`](crate::TreeListViewState::select_range).
+    pub multi_selection_style: Style,
+    /// Patched over the row pinned with [`SetAnchor`](crate::TreeViewAction::SetAnchor).
+    pub anchor_style: Style,
     pub partial_mark_style: Style,
+    /// Patched over `marked_style` for rows marked only because all their children are.
+    pub inherited_mark_style: Style,
     pub direct_match_style: Style,
     pub ancestor_match_style: Style,
     pub line_style: Style,
+    pub confirmation_style: Style,
+    /// The footer describing a column whose header is focused with
+    /// [`SelectHeaderRow`](crate::TreeViewAction::SelectHeaderRow).
+    pub header_help_style: Style,
+    /// The labels of the footer [legend](crate::TreeListView::legend).
+    pub legend_style: Style,
+    pub row_number_style: Style,
+    /// The background fill painted behind rows with a
+    /// [`fill_fraction`](crate::TreeModel::fill_fraction).
+    pub fill_style: Style,
+    /// Patched over rows highlighted with [`flash`](crate::TreeListViewState::flash).
+    pub flash_style: Style,
+    /// The popup opened by [`ShowDetails`](crate::TreeViewAction::ShowDetails).
+    pub details_style: Style,
+    pub details_key_style: Style,
+    /// Placeholder rows enabled by [`TreePlaceholders::Visible`](crate::TreePlaceholders::Visible).
+    pub placeholder_style: Style,
+    /// Rows pinned below nodes by [`TreeModel::overlay_rows`](crate::TreeModel::overlay_rows).
+    pub overlay_style: Style,
+    pub empty_placeholder: &'a str,
+    pub no_matches_placeholder: &'a str,
+    /// Shown under expanded nodes whose children are unloaded or loading.
+    pub loading_placeholder: &'a str,
+    pub scrollbar_style: Style,
+    /// Rows of missing nodes found by [`TreeModelCheck::Verify`].
+    pub error_style: Style,
+    pub missing_node: &'a str,
     pub highlight_symbol: &'a str,
     pub borders: Borders,
+    pub border_type: BorderType,
     pub column_spacing: u16,
     pub row_rendering: TreeRowRendering,
     pub horizontal_scroll: TreeHorizontalScroll,
     pub scroll_policy: TreeScrollPolicy,
+    pub overscroll: TreeOverscroll,
+    pub row_numbers: TreeRowNumbers,
+    pub position_indicator: TreePositionIndicator,
+    pub label_scrollbar: TreeLabelScrollbar,
+    pub leaf_alignment: TreeLeafAlignment,
+    pub model_check: TreeModelCheck,
 }
 
 impl TreeListViewStyle<'_> {
+    /// Creates a style whose colors all come from `theme`, keeping the default layout.
+    #[must_use]
+    pub fn from_theme(theme: TreeTheme) -> Self {
+        Self {
+            border_style: theme.border,
+            highlight_style: theme.selection,
+            column_highlight_style: theme.hover,
+            cell_highlight_style: theme.selection.patch(theme.hover),
+            marked_style: theme.marks,
+            multi_selection_style: theme.selection.patch(theme.hover),
+            anchor_style: theme.hover,
+            partial_mark_style: theme.marks.patch(theme.disabled),
+            inherited_mark_style: theme.disabled,
+            direct_match_style: theme.matches,
+            ancestor_match_style: theme.disabled,
+            line_style: theme.guides,
+            confirmation_style: theme.flash,
+            header_help_style: theme.hover,
+            legend_style: theme.disabled,
+            row_number_style: theme.disabled,
+            fill_style: theme.hover,
+            flash_style: theme.flash,
+            details_style: theme.border,
+            details_key_style: theme.disabled,
+            placeholder_style: theme.disabled,
+            overlay_style: theme.disabled,
+            scrollbar_style: theme.scrollbar,
+            error_style: theme.flash,
+            ..Self::default()
+        }
+    }
+
     /// Creates a style without an outer border.
     #[must_use]
     pub fn borderless() -> Self {
@@ -59,26 +289,74 @@ impl TreeListViewStyle<'_> {
     }
 }
 
+impl<'a> TreeListViewStyle<'a> {
+    pub(crate) fn block(&self) -> Block<'a> {
+        let mut block = Block::default()
+            .borders(self.borders)
+            .border_type(self.border_type)
+            .style(self.block_style)
+            .border_style(self.border_style)
+            .title_alignment(self.title_alignment)
+            .title_position(self.title_position);
+        if let Some(title) = self.title.clone() {
+            block = block.title(title);
+        }
+        if let Some(title) = self.bottom_title.clone() {
+            block = block.title_bottom(title);
+        }
+        block
+    }
+}
+
 impl Default for TreeListViewStyle<'_> {
     fn default() -> Self {
         Self {
             title: None,
+            bottom_title: None,
+            title_alignment: Alignment::Left,
+            title_position: TitlePosition::Top,
             block_style: Style::default(),
             border_style: Style::default(),
             highlight_style: Style::default(),
             column_highlight_style: Style::default(),
             cell_highlight_style: Style::default(),
             marked_style: Style::default(),
+            multi_selection_style: Style::default(),
+            anchor_style: Style::default(),
             partial_mark_style: Style::default(),
+            inherited_mark_style: Style::default(),
             direct_match_style: Style::default(),
             ancestor_match_style: Style::default(),
             line_style: Style::default(),
+            confirmation_style: Style::default(),
+            header_help_style: Style::default(),
+            legend_style: Style::default(),
+            row_number_style: Style::default(),
+            fill_style: Style::default(),
+            flash_style: Style::default(),
+            details_style: Style::default(),
+            details_key_style: Style::default(),
+            placeholder_style: Style::default(),
+            overlay_style: Style::default(),
+            empty_placeholder: "<empty>",
+            no_matches_placeholder: "no matches",
+            loading_placeholder: "loading…",
+            scrollbar_style: Style::default(),
+            error_style: Style::default(),
+            missing_node: "<missing>",
             highlight_symbol: ">> ",
             borders: Borders::ALL,
+            border_type: BorderType::Plain,
             column_spacing: 1,
             row_rendering: TreeRowRendering::Virtualized,
             horizontal_scroll: TreeHorizontalScroll::Enabled,
             scroll_policy: TreeScrollPolicy::KeepInView,
+            overscroll: TreeOverscroll::Disabled,
+            row_numbers: TreeRowNumbers::Hidden,
+            position_indicator: TreePositionIndicator::Hidden,
+            label_scrollbar: TreeLabelScrollbar::Hidden,
+            leaf_alignment: TreeLeafAlignment::Compact,
+            model_check: TreeModelCheck::Trust,
         }
     }
 }
@@ -1,3 +1,5 @@
+use rustc_hash::FxHashSet;
+
 use crate::model::{TreeChildren, TreeModel};
 
 type PostorderFrame<'a, Id> = (Id, Option<&'a [Id]>);
@@ -11,6 +13,7 @@ pub struct TreeWalkNode<'a, Id> {
 pub struct TreeWalk<'a, T: TreeModel> {
     model: &'a T,
     stack: Vec<(Option<T::Id>, T::Id)>,
+    visited: Option<FxHashSet<T::Id>>,
 }
 
 impl<'a, T: TreeModel> TreeWalk<'a, T> {
@@ -18,15 +21,27 @@ impl<'a, T: TreeModel> TreeWalk<'a, T> {
         let mut stack = Vec::with_capacity(model.size_hint().min(1024));
         stack.extend(model.roots().map(|id| (None, id)));
         stack.reverse();
-        Self { model, stack }
+        Self {
+            model,
+            stack,
+            visited: None,
+        }
     }
 
     pub fn subtree(model: &'a T, parent: Option<T::Id>, root: T::Id) -> Self {
         Self {
             model,
             stack: vec![(parent, root)],
+            visited: None,
         }
     }
+
+    /// Yields only the first occurrence of every node and skips the subtrees below repeated
+    /// ones, so shared children of a DAG are walked once.
+    pub fn once(mut self) -> Self {
+        self.visited = Some(FxHashSet::default());
+        self
+    }
 }
 
 impl<'a, T: TreeModel> Iterator for TreeWalk<'a, T> {
@@ -34,7 +49,16 @@ impl<'a, T: TreeModel> Iterator for TreeWalk<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let (parent, id) = self.stack.pop()?;
+        let (parent, id) = loop {
+            let (parent, id) = self.stack.pop()?;
+            if self
+                .visited
+                .as_mut()
+                .is_none_or(|visited| visited.insert(id))
+            {
+                break (parent, id);
+            }
+        };
         let children = self.model.children(id);
         self.stack.extend(
             children
@@ -0,0 +1,523 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use smallvec::{SmallVec, smallvec};
+
+use crate::edit::{
+    TreeChangeSet, TreeEditCommand, TreeEditor, TreeInsertPosition, TreeSelectionUpdate,
+};
+use crate::model::{TreeChildren, TreeModel, TreeRevision};
+
+/// An error produced by a [`VecTreeModel`] mutation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VecTreeError {
+    /// The id was never issued or its node was removed.
+    UnknownNode(usize),
+    /// A `Before` or `After` anchor is not among the destination siblings.
+    MissingAnchor(usize),
+    /// Moving `node` under `parent` would place it inside its own subtree.
+    Cycle { node: usize, parent: usize },
+}
+
+impl Display for VecTreeError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownNode(node) => write!(formatter, "unknown node: {node}"),
+            Self::MissingAnchor(anchor) => {
+                write!(formatter, "insertion anchor is not a sibling: {anchor}")
+            }
+            Self::Cycle { node, parent } => {
+                write!(
+                    formatter,
+                    "node {node} cannot be moved under its descendant {parent}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for VecTreeError {}
+
+struct VecTreeNode<T> {
+    value: T,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// An arena-backed tree that owns its values and implements both [`TreeModel`] and
+/// [`TreeEditor`].
+///
+/// Ids are arena indices that are never reused, so widget state keyed by a removed id cannot
+/// attach to a newer node. Every mutation advances the [revision](TreeModel::revision). A node
+/// detached with [`TreeEditCommand::Detach`] stays in the arena without a parent until it is
+/// moved back or removed.
+pub struct VecTreeModel<T> {
+    nodes: Vec<Option<VecTreeNode<T>>>,
+    roots: Vec<usize>,
+    len: usize,
+    revision: TreeRevision,
+}
+
+impl<T> VecTreeModel<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            len: 0,
+            revision: TreeRevision::INITIAL,
+        }
+    }
+
+    /// Returns the number of nodes, including detached ones.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub fn get(&self, id: usize) -> Option<&T> {
+        self.node(id).map(|node| &node.value)
+    }
+
+    /// Returns the value for in-place changes and advances the revision, since filters and
+    /// sorts may read it.
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        let node = self.nodes.get_mut(id)?.as_mut()?;
+        self.revision.advance();
+        Some(&mut node.value)
+    }
+
+    /// Returns the node's parent, or `None` for roots, detached nodes, and unknown ids.
+    #[must_use]
+    pub fn parent(&self, id: usize) -> Option<usize> {
+        self.node(id)?.parent
+    }
+
+    /// Inserts a new node under `parent`, or among the roots when `parent` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VecTreeError`] when `parent` is unknown or the position's anchor is not one of
+    /// its children.
+    pub fn insert(
+        &mut self,
+        parent: Option<usize>,
+        position: TreeInsertPosition<usize>,
+        value: T,
+    ) -> Result<usize, VecTreeError> {
+        let index = self.resolve(parent, &position)?;
+        let id = self.nodes.len();
+        self.nodes.push(Some(VecTreeNode {
+            value,
+            parent,
+            children: Vec::new(),
+        }));
+        self.len += 1;
+        self.link(id, parent, index);
+        self.revision.advance();
+        Ok(id)
+    }
+
+    /// Removes the node with its whole subtree and returns the node's value.
+    ///
+    /// Use [`TreeEditCommand::Delete`] through
+    /// [`TreeListViewState::apply_edit`](crate::TreeListViewState::apply_edit) instead to
+    /// reconcile widget state with every removed id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VecTreeError::UnknownNode`] when `id` is unknown.
+    pub fn remove(&mut self, id: usize) -> Result<T, VecTreeError> {
+        if self.node(id).is_none() {
+            return Err(VecTreeError::UnknownNode(id));
+        }
+        self.unlink(id);
+        let node = self
+            .nodes
+            .get_mut(id)
+            .and_then(Option::take)
+            .ok_or(VecTreeError::UnknownNode(id))?;
+        let mut pending = node.children;
+        while let Some(descendant) = pending.pop() {
+            if let Some(node) = self.nodes[descendant].take() {
+                pending.extend(node.children);
+                self.len -= 1;
+            }
+        }
+        self.len -= 1;
+        self.revision.advance();
+        Ok(node.value)
+    }
+
+    /// Moves the node under `parent`, or among the roots when `parent` is `None`. Detached
+    /// nodes are reattached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VecTreeError`] when a node is unknown, the anchor is not a child of `parent`,
+    /// or `parent` lies inside the moved subtree.
+    pub fn reparent(
+        &mut self,
+        id: usize,
+        parent: Option<usize>,
+        position: TreeInsertPosition<usize>,
+    ) -> Result<(), VecTreeError> {
+        self.move_nodes(&[id], parent, &position)
+    }
+
+    /// Replaces the node's value and returns the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VecTreeError::UnknownNode`] when `id` is unknown.
+    pub fn rename(&mut self, id: usize, value: T) -> Result<T, VecTreeError> {
+        let node = self
+            .nodes
+            .get_mut(id)
+            .and_then(Option::as_mut)
+            .ok_or(VecTreeError::UnknownNode(id))?;
+        self.revision.advance();
+        Ok(std::mem::replace(&mut node.value, value))
+    }
+
+    fn node(&self, id: usize) -> Option<&VecTreeNode<T>> {
+        self.nodes.get(id)?.as_ref()
+    }
+
+    fn children_of(&self, id: usize) -> &[usize] {
+        self.node(id).map_or(&[], |node| &node.children)
+    }
+
+    /// Inserts `id` at `index` among the children of `parent`, or the roots when it is `None`.
+    fn link(&mut self, id: usize, parent: Option<usize>, index: usize) {
+        let siblings = match parent {
+            Some(parent) => match self.nodes.get_mut(parent).and_then(Option::as_mut) {
+                Some(node) => &mut node.children,
+                None => return,
+            },
+            None => &mut self.roots,
+        };
+        siblings.insert(index, id);
+        if let Some(node) = self.nodes[id].as_mut() {
+            node.parent = parent;
+        }
+    }
+
+    /// Resolves `position` among the children of `parent`, or the roots when it is `None`.
+    fn resolve(
+        &self,
+        parent: Option<usize>,
+        position: &TreeInsertPosition<usize>,
+    ) -> Result<usize, VecTreeError> {
+        let siblings = match parent {
+            Some(parent) => {
+                &self
+                    .node(parent)
+                    .ok_or(VecTreeError::UnknownNode(parent))?
+                    .children
+            }
+            None => &self.roots,
+        };
+        match *position {
+            TreeInsertPosition::First => Ok(0),
+            TreeInsertPosition::Last => Ok(siblings.len()),
+            TreeInsertPosition::Before(anchor) | TreeInsertPosition::After(anchor) => position
+                .index_in(siblings)
+                .ok_or(VecTreeError::MissingAnchor(anchor)),
+        }
+    }
+
+    /// Removes the node from its parent's children or from the roots, keeping its subtree.
+    fn unlink(&mut self, id: usize) {
+        let Some(node) = self.nodes[id].as_mut() else {
+            return;
+        };
+        let parent = node.parent.take();
+        match parent.and_then(|parent| self.nodes[parent].as_mut()) {
+            Some(parent) => parent.children.retain(|child| *child != id),
+            None => self.roots.retain(|root| *root != id),
+        }
+    }
+
+    /// Moves `ids` in order to `position`, checking every precondition before changing
+    /// anything.
+    fn move_nodes(
+        &mut self,
+        ids: &[usize],
+        parent: Option<usize>,
+        position: &TreeInsertPosition<usize>,
+    ) -> Result<(), VecTreeError> {
+        if let Some(&unknown) = ids.iter().find(|id| self.node(**id).is_none()) {
+            return Err(VecTreeError::UnknownNode(unknown));
+        }
+        self.resolve(parent, position)?;
+        if let TreeInsertPosition::Before(anchor) | TreeInsertPosition::After(anchor) = position
+            && ids.contains(anchor)
+        {
+            return Err(VecTreeError::MissingAnchor(*anchor));
+        }
+        if let Some(parent) = parent {
+            let mut ancestor = Some(parent);
+            while let Some(current) = ancestor {
+                if let Some(&node) = ids.iter().find(|id| **id == current) {
+                    return Err(VecTreeError::Cycle { node, parent });
+                }
+                ancestor = self.parent(current);
+            }
+        }
+
+        for &id in ids {
+            self.unlink(id);
+        }
+        let index = self.resolve(parent, position)?;
+        for (offset, &id) in ids.iter().enumerate() {
+            self.link(id, parent, index + offset);
+        }
+        self.revision.advance();
+        Ok(())
+    }
+
+    /// Returns `id` followed by its descendants in depth-first order.
+    fn subtree(&self, id: usize) -> SmallVec<[usize; 4]> {
+        let mut ids = SmallVec::new();
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            ids.push(current);
+            pending.extend(self.children_of(current).iter().rev());
+        }
+        ids
+    }
+}
+
+impl<T> Default for VecTreeModel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TreeModel for VecTreeModel<T> {
+    type Id = usize;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        self.roots.iter().copied()
+    }
+
+    fn children(&self, id: Self::Id) -> TreeChildren<'_, Self::Id> {
+        TreeChildren::loaded(self.children_of(id))
+    }
+
+    fn revision(&self) -> TreeRevision {
+        self.revision
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len
+    }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        self.node(id).is_some()
+    }
+}
+
+/// Creates children with `T::default()`. [`TreeEditCommand::Rename`] carries no value, so call
+/// [`VecTreeModel::rename`] with the new one first; the command then only checks the node.
+impl<T: Default> TreeEditor for VecTreeModel<T> {
+    type Error = VecTreeError;
+
+    fn apply(
+        &mut self,
+        command: TreeEditCommand<Self::Id>,
+    ) -> Result<TreeChangeSet<Self::Id>, Self::Error> {
+        match command {
+            TreeEditCommand::CreateChild { parent } => {
+                let child = self.insert(Some(parent), TreeInsertPosition::Last, T::default())?;
+                Ok(TreeChangeSet {
+                    inserted: smallvec![child],
+                    selection: TreeSelectionUpdate::Select(child),
+                    ..TreeChangeSet::default()
+                })
+            }
+            TreeEditCommand::Rename { node } => {
+                self.node(node).ok_or(VecTreeError::UnknownNode(node))?;
+                Ok(TreeChangeSet::default())
+            }
+            TreeEditCommand::Move {
+                nodes,
+                parent,
+                position,
+            } => {
+                self.move_nodes(&nodes, Some(parent), &position)?;
+                let selection = nodes.first().map_or(TreeSelectionUpdate::Keep, |node| {
+                    TreeSelectionUpdate::Select(*node)
+                });
+                Ok(TreeChangeSet {
+                    moved: nodes,
+                    selection,
+                    ..TreeChangeSet::default()
+                })
+            }
+            TreeEditCommand::Detach { nodes } => {
+                if let Some(&unknown) = nodes.iter().find(|id| self.node(**id).is_none()) {
+                    return Err(VecTreeError::UnknownNode(unknown));
+                }
+                let selection = self.parent_selection(&nodes);
+                for &node in &nodes {
+                    self.unlink(node);
+                }
+                self.revision.advance();
+                Ok(TreeChangeSet {
+                    moved: nodes,
+                    selection,
+                    ..TreeChangeSet::default()
+                })
+            }
+            TreeEditCommand::Delete { nodes } => {
+                if let Some(&unknown) = nodes.iter().find(|id| self.node(**id).is_none()) {
+                    return Err(VecTreeError::UnknownNode(unknown));
+                }
+                let selection = self.parent_selection(&nodes);
+                let mut removed = SmallVec::new();
+                for &node in &nodes {
+                    if self.node(node).is_some() {
+                        removed.extend(self.subtree(node));
+                        self.remove(node)?;
+                    }
+                }
+                Ok(TreeChangeSet {
+                    removed,
+                    selection,
+                    ..TreeChangeSet::default()
+                })
+            }
+        }
+    }
+}
+
+impl<T> VecTreeModel<T> {
+    /// Selects the parent of the first node, which survives detaching or deleting `nodes`
+    /// unless it is one of them.
+    fn parent_selection(&self, nodes: &[usize]) -> TreeSelectionUpdate<usize> {
+        nodes
+            .first()
+            .and_then(|node| self.parent(*node))
+            .filter(|parent| !nodes.contains(parent))
+            .map_or(TreeSelectionUpdate::Keep, TreeSelectionUpdate::Select)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec_tree_keeps_ids_stable_across_edits() {
+        let mut tree = VecTreeModel::new();
+        let root = tree
+            .insert(None, TreeInsertPosition::Last, "root")
+            .expect("roots need no parent");
+        let alpha = tree
+            .insert(Some(root), TreeInsertPosition::Last, "alpha")
+            .expect("root exists");
+        let beta = tree
+            .insert(Some(root), TreeInsertPosition::Before(alpha), "beta")
+            .expect("alpha is a child of root");
+        let gamma = tree
+            .insert(Some(alpha), TreeInsertPosition::First, "gamma")
+            .expect("alpha exists");
+        assert_eq!(tree.children(root).loaded_slice(), &[beta, alpha]);
+
+        assert_eq!(
+            tree.reparent(alpha, Some(gamma), TreeInsertPosition::Last),
+            Err(VecTreeError::Cycle {
+                node: alpha,
+                parent: gamma
+            })
+        );
+        tree.reparent(gamma, Some(beta), TreeInsertPosition::Last)
+            .expect("beta is outside gamma's subtree");
+        assert_eq!(tree.parent(gamma), Some(beta));
+        assert_eq!(tree.rename(gamma, "delta"), Ok("gamma"));
+
+        let revision = tree.revision();
+        assert_eq!(tree.remove(beta), Ok("beta"));
+        assert!(tree.revision() > revision);
+        assert_eq!(tree.len(), 2);
+        assert!(!tree.contains(gamma));
+        assert_eq!(tree.get(gamma), None);
+        let epsilon = tree
+            .insert(Some(root), TreeInsertPosition::Last, "epsilon")
+            .expect("root exists");
+        assert_eq!(epsilon, 4);
+        assert_eq!(tree.children(root).loaded_slice(), &[alpha, epsilon]);
+    }
+
+    #[test]
+    fn vec_tree_rejects_unknown_ids() {
+        let mut tree = VecTreeModel::new();
+        let root = tree
+            .insert(None, TreeInsertPosition::Last, "root")
+            .expect("roots need no parent");
+        assert_eq!(tree.remove(99), Err(VecTreeError::UnknownNode(99)));
+        assert_eq!(tree.rename(99, "other"), Err(VecTreeError::UnknownNode(99)));
+        assert_eq!(tree.remove(root), Ok("root"));
+        assert_eq!(tree.remove(root), Err(VecTreeError::UnknownNode(root)));
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn vec_tree_applies_edit_commands_atomically() {
+        let mut tree = VecTreeModel::<String>::new();
+        let root = tree
+            .insert(None, TreeInsertPosition::Last, "root".into())
+            .expect("roots need no parent");
+        let changes = tree
+            .apply(TreeEditCommand::CreateChild { parent: root })
+            .expect("root exists");
+        let child = changes.inserted[0];
+        assert_eq!(tree.get(child).map(String::as_str), Some(""));
+        let leaf = tree
+            .insert(Some(child), TreeInsertPosition::Last, "leaf".into())
+            .expect("child exists");
+
+        let revision = tree.revision();
+        assert_eq!(
+            tree.apply(TreeEditCommand::Move {
+                nodes: smallvec![child],
+                parent: root,
+                position: TreeInsertPosition::After(leaf),
+            }),
+            Err(VecTreeError::MissingAnchor(leaf))
+        );
+        assert_eq!(tree.revision(), revision);
+
+        let changes = tree
+            .apply(TreeEditCommand::Detach {
+                nodes: smallvec![leaf],
+            })
+            .expect("leaf exists");
+        assert_eq!(changes.selection, TreeSelectionUpdate::Select(child));
+        assert!(tree.children(child).loaded_slice().is_empty());
+        tree.apply(TreeEditCommand::Move {
+            nodes: smallvec![leaf],
+            parent: root,
+            position: TreeInsertPosition::First,
+        })
+        .expect("detached nodes can be moved back");
+        assert_eq!(tree.children(root).loaded_slice(), &[leaf, child]);
+
+        let changes = tree
+            .apply(TreeEditCommand::Delete {
+                nodes: smallvec![root],
+            })
+            .expect("root exists");
+        assert_eq!(changes.removed.as_slice(), &[root, leaf, child]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.roots().count(), 0);
+    }
+}
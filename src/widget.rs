@@ -1,23 +1,47 @@
 use ratatui::layout::{Constraint, Rect};
 use ratatui::prelude::Buffer;
 use ratatui::style::Style;
-use ratatui::text::Line;
+use ratatui::symbols::line;
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-    Block, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget,
-    Table, TableState, Widget,
+    Block, Borders, Cell, Clear, HighlightSpacing, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+    ScrollbarState, StatefulWidget, Table, TableState, Widget,
 };
 use smallvec::SmallVec;
 
-use crate::columns::TreeColumns;
+use crate::action::TreeEditRequest;
+use crate::buffer_text::tree_buffer_text;
+use crate::columns::{AdaptiveColumns, TreeColumns};
 use crate::context::{
-    TreeMarkState, TreeMatchState, TreeRowContext, TreeRowNodeState, TreeRowRenderState,
+    TreeMarkSource, TreeMarkState, TreeMatchState, TreeRowContext, TreeRowNodeState,
+    TreeRowRenderState,
 };
-use crate::glyphs::{TreeGlyphs, TreeLabelRenderer};
+use crate::details::TreeDetailProvider;
+use crate::glyphs::{TreeGlyphs, TreeLabelPrefix, TreeLabelRenderer, tree_label_line};
+use crate::legend::{TreeLegendEntry, legend_line};
 use crate::model::{TreeFilter, TreeModel, TreeQuery, TreeSort};
-use crate::projection::{ProjectedNode, TreeProjection};
+use crate::overlay::TreeOverlayRows;
+use crate::projection::{ProjectedNode, TreePlaceholder};
 use crate::state::TreeListViewState;
 use crate::state::hit::{ColumnHitBox, TreeHitMap};
-use crate::style::{TreeHorizontalScroll, TreeListViewStyle, TreeRowRendering};
+use crate::style::{
+    TreeHorizontalScroll, TreeLabelScrollbar, TreeListViewStyle, TreeModelCheck, TreeOverscroll,
+    TreePositionIndicator, TreeRowNumbers, TreeRowRendering,
+};
+
+/// The size a [`TreeListView`] needs, returned by [`TreeListView::measure`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeMeasure {
+    /// The number of rows in the projection.
+    pub rows: usize,
+    /// The height that shows every row, including the block, banner, header, and a horizontal
+    /// scrollbar when the columns do not fit the measured width.
+    pub height: u16,
+    /// The narrowest width that fits every column at its minimum width.
+    pub min_width: u16,
+    /// The width that fits every column at its ideal width.
+    pub ideal_width: u16,
+}
 
 /// A stateful tree table built around one projection shared by rendering and navigation.
 pub struct TreeListView<'a, T, F, S, L, C> {
@@ -27,6 +51,9 @@ pub struct TreeListView<'a, T, F, S, L, C> {
     columns: &'a C,
     style: TreeListViewStyle<'a>,
     glyphs: TreeGlyphs<'a>,
+    details: Option<&'a dyn TreeDetailProvider<T>>,
+    overlays: Option<&'a dyn TreeOverlayRows<T>>,
+    legend: &'a [TreeLegendEntry<'a>],
 }
 
 impl<'a, T, F, S, L, C> TreeListView<'a, T, F, S, L, C>
@@ -53,6 +80,9 @@ where
             columns,
             style,
             glyphs: TreeGlyphs::unicode(),
+            details: None,
+            overlays: None,
+            legend: &[],
         }
     }
 
@@ -63,83 +93,304 @@ where
         self
     }
 
+    /// Sets the provider of the popup opened by [`TreeViewAction::ShowDetails`].
+    ///
+    /// [`TreeViewAction::ShowDetails`]: crate::TreeViewAction::ShowDetails
+    #[must_use]
+    pub const fn details(mut self, provider: &'a dyn TreeDetailProvider<T>) -> Self {
+        self.details = Some(provider);
+        self
+    }
+
+    /// Sets the provider of the rows pinned below nodes by
+    /// [`TreeModel::overlay_rows`](crate::TreeModel::overlay_rows).
+    #[must_use]
+    pub const fn overlay_rows(mut self, provider: &'a dyn TreeOverlayRows<T>) -> Self {
+        self.overlays = Some(provider);
+        self
+    }
+
+    /// Explains glyphs and styles in the bottom border, such as the
+    /// [mark styles](TreeLegendEntry::marks), whenever no prompt is shown there.
+    #[must_use]
+    pub const fn legend(mut self, entries: &'a [TreeLegendEntry<'a>]) -> Self {
+        self.legend = entries;
+        self
+    }
+
+    /// Measures the space the tree needs without drawing it, so parent layouts can size the
+    /// pane to its content, such as a shrink-to-fit popup.
+    ///
+    /// `area` bounds the measurement: its width decides whether a horizontal scrollbar is needed.
+    /// A pane shorter than [`TreeMeasure::height`] also shows a vertical scrollbar, one column
+    /// wide.
+    pub fn measure(&self, area: Rect, state: &mut TreeListViewState<T::Id>) -> TreeMeasure {
+        state.ensure_projection(self.model, self.query);
+        let rows = state.projection().len();
+        let prompt = self.footer_prompt(state);
+        let probe = Rect::new(0, 0, 64, 64);
+        let inner = self.block(prompt).inner(probe);
+        let banner = self.banner().map_or(0, |banner| banner.height());
+        let chrome_width = probe.width.saturating_sub(inner.width);
+        let chrome_height = probe
+            .height
+            .saturating_sub(inner.height)
+            .saturating_add(u16::try_from(banner).unwrap_or(u16::MAX));
+
+        let gap_count =
+            u16::try_from(self.columns.column_count().saturating_sub(1)).unwrap_or(u16::MAX);
+        let fixed_width = chrome_width
+            .saturating_add(self.gutter_width(rows, u16::MAX))
+            .saturating_add(
+                u16::try_from(Line::from(self.style.highlight_symbol).width()).unwrap_or(u16::MAX),
+            )
+            .saturating_add(self.style.column_spacing.saturating_mul(gap_count));
+        let min_width = fixed_width.saturating_add(self.columns.minimum_width());
+        let ideal_width = fixed_width.saturating_add(self.columns.ideal_width());
+        let horizontal_scrollbar =
+            matches!(self.style.horizontal_scroll, TreeHorizontalScroll::Enabled)
+                && area.width < ideal_width;
+        let height = chrome_height
+            .saturating_add(self.columns.header_height())
+            .saturating_add(u16::try_from(rows).unwrap_or(u16::MAX))
+            .saturating_add(u16::from(horizontal_scrollbar));
+        TreeMeasure {
+            rows,
+            height,
+            min_width,
+            ideal_width,
+        }
+    }
+
+    /// Draws the view computed by the last stateful render without modifying `state`.
+    ///
+    /// Lets additional panes and overlays share one state immutably. The projection, marks, and
+    /// selection are used as last synchronized, offsets are clamped for this area only, and hit
+    /// testing keeps describing the primary view.
+    pub fn render_spectator(
+        self,
+        area: Rect,
+        buffer: &mut Buffer,
+        state: &TreeListViewState<T::Id>,
+    ) {
+        let Some((table_area, gutter)) = self.render_frame(area, buffer, state) else {
+            return;
+        };
+        let plan = self.prepare_spectator(table_area, state);
+        if let Some(gutter) = gutter {
+            self.render_row_numbers(buffer, gutter, state, &plan);
+        }
+        self.render_position_indicator(area, buffer, state, &plan);
+        let mut scratch = Buffer::empty(Rect::ZERO);
+        let (hit_map, widest_label) = self.render_projected_rows(buffer, state, &mut scratch, plan);
+        self.render_label_scrollbar(area, buffer, &hit_map, widest_label);
+        self.render_details(area, buffer, state, &hit_map);
+    }
+
     fn build_rows(
         &self,
-        projection: &TreeProjection<T::Id>,
+        state: &TreeListViewState<T::Id>,
         rendered: std::ops::Range<usize>,
-        selected: Option<usize>,
-        selected_column: Option<usize>,
-        draw_lines: bool,
-        marks: impl Fn(T::Id) -> TreeMarkState,
-    ) -> Vec<Row<'a>> {
-        let start_index = rendered.start;
-        let nodes = &projection.nodes()[rendered];
+        visible_columns: std::ops::Range<usize>,
+        column_widths: &[u16],
+        measured: std::ops::Range<usize>,
+    ) -> BuiltRows<'a> {
+        let projection = state.projection();
+        let selected = state.selected_index();
+        let nodes = projection.nodes();
+        let end = rendered.end.min(nodes.len());
+        let start_index = rendered.start.min(end);
+        let nodes = &nodes[start_index..end];
         let mut rows = Vec::with_capacity(nodes.len());
-        let mut tails = nodes.first().map_or_else(SmallVec::new, |node| {
-            Self::tail_stack_before(projection, *node)
-        });
+        let mut fills = Vec::with_capacity(nodes.len());
+        let mut tails = nodes
+            .first()
+            .map_or_else(SmallVec::new, |node| projection.tail_stack_before(*node));
+        let has_cell_flags = state.cell_flags().next().is_some();
+        let mut flagged_columns: SmallVec<[usize; 8]> = SmallVec::new();
+        let mut widest_label = 0;
 
         for (relative_index, node) in nodes.iter().enumerate() {
             Self::update_tail_stack(&mut tails, *node);
-            let is_selected = selected == Some(start_index.saturating_add(relative_index));
-            let mark = marks(node.id());
-            let context = TreeRowContext {
-                level: node.level(),
-                is_tail_stack: &tails,
-                node: TreeRowNodeState {
-                    expansion: node.expansion(),
-                    mark,
-                    match_state: node.match_state(),
-                },
-                render: TreeRowRenderState {
-                    draw_lines,
-                    is_selected,
-                    selected_column,
-                },
-                line_style: self.style.line_style,
+            flagged_columns.clear();
+            if has_cell_flags && !node.is_synthetic() {
+                flagged_columns.extend(
+                    (0..self.columns.column_count())
+                        .filter(|&column| state.is_cell_flagged(node.id(), column)),
+                );
+            }
+            let missing = !node.is_synthetic() && self.is_missing(node.id());
+            let index = start_index.saturating_add(relative_index);
+            let fill_fraction = if missing {
+                None
+            } else {
+                self.model.fill_fraction(node.id())
             };
+            fills.push(fill_fraction);
+            let render = TreeRowRenderState {
+                draw_lines: state.draw_lines() && (missing || self.model.draws_guides(node.id())),
+                is_selected: selected == Some(index),
+                selected_column: state.selected_column(),
+            };
+            let context = TreeRowContext::new(
+                node.level(),
+                &tails,
+                Self::node_state(state, *node),
+                render,
+                self.style.line_style,
+            )
+            .with_fill_fraction(fill_fraction)
+            .with_column_widths(column_widths)
+            .with_flagged_columns(&flagged_columns)
+            .with_leaf_alignment(self.style.leaf_alignment);
+            if let Some(overlay) = node.overlay() {
+                let line = self.overlays.map_or_else(Line::default, |overlays| {
+                    overlays.overlay_row(self.model, node.id(), overlay)
+                });
+                rows.push(self.overlay_row(line, &context));
+                continue;
+            }
+            if let Some(placeholder) = node.placeholder() {
+                let text = match placeholder {
+                    TreePlaceholder::Empty => self.style.empty_placeholder,
+                    TreePlaceholder::NoMatches => self.style.no_matches_placeholder,
+                    TreePlaceholder::Loading => self.style.loading_placeholder,
+                };
+                rows.push(self.message_row(text, self.style.placeholder_style, &context));
+                continue;
+            }
+            if missing {
+                rows.push(self.message_row(
+                    self.style.missing_node,
+                    self.style.error_style,
+                    &context,
+                ));
+                continue;
+            }
+            if measured.contains(&index) {
+                let width = self
+                    .label
+                    .width(self.model, node.id(), &context, &self.glyphs);
+                widest_label = widest_label.max(width.unwrap_or(0));
+            }
             let tree_cell = self
                 .label
                 .cell(self.model, node.id(), &context, &self.glyphs);
-            let cells = self
-                .columns
-                .cells(self.model, node.id(), &context, tree_cell);
-            rows.push(Row::new(cells).style(self.row_style(node.match_state(), mark)));
+            let cells = self.columns.visible_cells(
+                self.model,
+                node.id(),
+                &context,
+                tree_cell,
+                visible_columns.clone(),
+            );
+            let style = self.node_row_style(state, node.id(), &context.node);
+            rows.push(Row::new(cells).style(style));
+        }
+        BuiltRows {
+            rows,
+            fills,
+            widest_label,
         }
-        rows
     }
 
-    fn row_style(&self, match_state: TreeMatchState, mark: TreeMarkState) -> Style {
-        let match_style = match match_state {
-            TreeMatchState::Unfiltered => Style::default(),
-            TreeMatchState::Direct => self.style.direct_match_style,
-            TreeMatchState::Ancestor => self.style.ancestor_match_style,
+    fn node_state(
+        state: &TreeListViewState<T::Id>,
+        node: ProjectedNode<T::Id>,
+    ) -> TreeRowNodeState {
+        let mark = state.mark_state_cached(node.id());
+        let mark_source = if state.is_manually_marked(node.id()) {
+            Some(TreeMarkSource::Manual)
+        } else {
+            (mark != TreeMarkState::Unmarked).then_some(TreeMarkSource::Inherited)
         };
-        let mark_style = match mark {
-            TreeMarkState::Unmarked => Style::default(),
-            TreeMarkState::Partial => self.style.partial_mark_style,
-            TreeMarkState::Marked => self.style.marked_style,
+        TreeRowNodeState::new(node.expansion(), mark, node.match_state())
+            .with_mark_source(mark_source)
+            .with_matched_descendants(node.matched_descendants())
+    }
+
+    /// Returns `true` when [`TreeModelCheck::Verify`] finds that the model lost `id`.
+    fn is_missing(&self, id: T::Id) -> bool {
+        matches!(self.style.model_check, TreeModelCheck::Verify) && !self.model.contains(id)
+    }
+
+    /// Builds a synthetic row whose only cell is `text` in the tree column, such as a placeholder
+    /// or a missing node.
+    fn message_row(&self, text: &'a str, style: Style, context: &TreeRowContext<'_>) -> Row<'a> {
+        let glyphs = TreeGlyphs {
+            leaf: "",
+            ..self.glyphs
         };
-        match_style.patch(mark_style)
+        let mut label = Some(tree_label_line(
+            context,
+            TreeLabelPrefix::borrowed(text),
+            &glyphs,
+        ));
+        let tree_column = self.columns.tree_column_index();
+        let cells = (0..self.columns.column_count()).map(|index| {
+            label
+                .take_if(|_| index == tree_column)
+                .map_or_else(|| Cell::new(""), Cell::from)
+        });
+        Row::new(cells).style(style)
     }
 
-    fn tail_stack_before(
-        projection: &TreeProjection<T::Id>,
-        node: ProjectedNode<T::Id>,
-    ) -> SmallVec<[bool; 32]> {
-        let mut reversed = SmallVec::<[bool; 32]>::new();
-        let mut parent = node.parent_index();
-        while let Some(parent_index) = parent {
-            let Some(parent_node) = projection.nodes().get(parent_index).copied() else {
-                break;
+    /// Builds an overlay row whose tree cell continues the guides of the rows around it.
+    fn overlay_row(&self, line: Line<'a>, context: &TreeRowContext<'_>) -> Row<'a> {
+        let indent = context.is_tail_stack.iter().map(|&is_last| {
+            let glyph = match (context.render.draw_lines, is_last) {
+                (false, _) => self.glyphs.empty,
+                (true, true) => self.glyphs.indent,
+                (true, false) => self.glyphs.vert,
             };
-            if parent_node.level() > 0 {
-                reversed.push(parent_node.is_last_sibling());
-            }
-            parent = parent_node.parent_index();
+            Span::styled(glyph, context.line_style)
+        });
+        let spans: Vec<Span<'a>> = indent.chain(line.spans).collect();
+        let mut line = Some(Line::from(spans).style(line.style));
+        let tree_column = self.columns.tree_column_index();
+        let cells = (0..self.columns.column_count()).map(|index| {
+            line.take_if(|_| index == tree_column)
+                .map_or_else(|| Cell::new(""), Cell::from)
+        });
+        Row::new(cells).style(self.style.overlay_style)
+    }
+
+    /// Returns [`Self::row_style`] patched with the view state kept for `id`: the
+    /// multi-selection, the anchor, and flashes.
+    fn node_row_style(
+        &self,
+        state: &TreeListViewState<T::Id>,
+        id: T::Id,
+        node: &TreeRowNodeState,
+    ) -> Style {
+        let mut style = self.row_style(node);
+        if state.is_in_selection(id) {
+            style = style.patch(self.style.multi_selection_style);
+        }
+        if state.anchor() == Some(id) {
+            style = style.patch(self.style.anchor_style);
         }
-        reversed.reverse();
-        reversed
+        if state.flash_remaining(id).is_some() {
+            style = style.patch(self.style.flash_style);
+        }
+        style
+    }
+
+    fn row_style(&self, node: &TreeRowNodeState) -> Style {
+        let match_style = match node.match_state {
+            TreeMatchState::Unfiltered => Style::default(),
+            TreeMatchState::Direct => self.style.direct_match_style,
+            TreeMatchState::Ancestor => self.style.ancestor_match_style,
+        };
+        let mark_style = match (node.mark, node.mark_source()) {
+            (TreeMarkState::Unmarked, _) => Style::default(),
+            (TreeMarkState::Partial, _) => self.style.partial_mark_style,
+            (TreeMarkState::Marked, Some(TreeMarkSource::Inherited)) => self
+                .style
+                .marked_style
+                .patch(self.style.inherited_mark_style),
+            (TreeMarkState::Marked, _) => self.style.marked_style,
+        };
+        match_style.patch(mark_style)
     }
 
     fn update_tail_stack(tails: &mut SmallVec<[bool; 32]>, node: ProjectedNode<T::Id>) {
@@ -167,34 +418,199 @@ where
         table
     }
 
-    fn block(&self) -> Block<'_> {
-        let mut block = Block::default()
-            .borders(self.style.borders)
-            .style(self.style.block_style)
-            .border_style(self.style.border_style);
-        if let Some(title) = self.style.title.clone() {
-            block = block.title(title);
+    fn block(&self, prompt: Option<Line<'a>>) -> Block<'a> {
+        let mut block = self.style.block();
+        if let Some(prompt) = prompt {
+            block = block.title_bottom(prompt);
+        }
+        if self.style.position_indicator != TreePositionIndicator::Hidden {
+            // Reserves the bottom row, which `render_position_indicator` fills once the
+            // viewport is known.
+            block = block.title_bottom(Line::default().right_aligned());
         }
         block
     }
 
+    /// Draws the position indicator right-aligned on the block's bottom row.
+    fn render_position_indicator(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        state: &TreeListViewState<T::Id>,
+        plan: &RenderPlan,
+    ) {
+        let total = state.projection().len();
+        let text = match self.style.position_indicator {
+            TreePositionIndicator::Hidden => return,
+            TreePositionIndicator::Percent => {
+                let last_offset = total.saturating_sub(plan.viewport_height);
+                let percent = (plan.offset.min(last_offset) * 100)
+                    .checked_div(last_offset)
+                    .unwrap_or(100);
+                format!("{percent}%")
+            }
+            TreePositionIndicator::Position => {
+                let selected = state.selected_index().map_or(0, |index| index + 1);
+                format!("{selected}/{total}")
+            }
+        };
+        let borders = self.style.borders;
+        let left = area
+            .x
+            .saturating_add(u16::from(borders.contains(Borders::LEFT)));
+        let right = area
+            .right()
+            .saturating_sub(u16::from(borders.contains(Borders::RIGHT)));
+        let width = u16::try_from(text.len()).unwrap_or(u16::MAX);
+        let x = right.saturating_sub(width).max(left);
+        let row = Rect::new(
+            x,
+            area.bottom().saturating_sub(1),
+            right.saturating_sub(x),
+            1,
+        );
+        Line::raw(text).render(row, buffer);
+    }
+
+    /// Draws the detail popup below the node's row, or above it when the popup does not fit
+    /// inside `area` below.
+    fn render_details(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        state: &TreeListViewState<T::Id>,
+        hit_map: &TreeHitMap,
+    ) {
+        let (Some(provider), Some(id)) = (self.details, state.details_node()) else {
+            return;
+        };
+        if self.is_missing(id) {
+            return;
+        }
+        let index = state
+            .selected_index()
+            .filter(|_| state.selected_id() == Some(id))
+            .or_else(|| state.projection().index_of(id));
+        let Some(index) =
+            index.filter(|index| (hit_map.range_start..hit_map.range_end).contains(index))
+        else {
+            return;
+        };
+        let details = provider.details(self.model, id);
+        if details.is_empty() {
+            return;
+        }
+        let key_width = details
+            .iter()
+            .map(|(key, _)| Line::raw(key.as_ref()).width())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line<'_>> = details
+            .into_iter()
+            .map(|(key, value)| {
+                let padding = key_width.saturating_sub(Line::raw(key.as_ref()).width());
+                Line::from(vec![
+                    Span::styled(key, self.style.details_key_style),
+                    Span::raw(" ".repeat(padding.saturating_add(1))),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+        let content_width = lines.iter().map(Line::width).max().unwrap_or(0);
+        let width = u16::try_from(content_width)
+            .unwrap_or(u16::MAX)
+            .saturating_add(2)
+            .min(area.width);
+        let height = u16::try_from(lines.len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(2)
+            .min(area.height);
+
+        let row = hit_map
+            .rows
+            .y
+            .saturating_add(u16::try_from(index - hit_map.range_start).unwrap_or(u16::MAX));
+        let below = row.saturating_add(1);
+        let y = if below.saturating_add(height) <= area.bottom() {
+            below
+        } else if row.saturating_sub(area.y) >= height {
+            row - height
+        } else {
+            area.bottom().saturating_sub(height)
+        };
+        let x = hit_map
+            .rows
+            .x
+            .saturating_add(hit_map.selection_width)
+            .min(area.right().saturating_sub(width));
+        let popup = Rect::new(x, y, width, height);
+        Clear.render(popup, buffer);
+        Paragraph::new(lines)
+            .style(self.style.details_style)
+            .block(
+                Block::bordered()
+                    .border_type(self.style.border_type)
+                    .style(self.style.details_style),
+            )
+            .render(popup, buffer);
+    }
+
+    /// Returns the bottom-border prompt: an edit waiting for confirmation, the help of a focused
+    /// column header, or else the legend.
+    fn footer_prompt(&self, state: &TreeListViewState<T::Id>) -> Option<Line<'a>> {
+        if let Some(request) = state.pending_confirmation() {
+            return Some(
+                self.confirmation_prompt(request)
+                    .style(self.style.confirmation_style),
+            );
+        }
+        let help = state
+            .selected_column()
+            .filter(|_| state.is_header_selected())
+            .and_then(|column| self.columns.column_help(column));
+        if let Some(help) = help {
+            return Some(help.style(self.style.header_help_style));
+        }
+        (!self.legend.is_empty()).then(|| legend_line(self.legend, self.style.legend_style))
+    }
+
+    /// Builds the prompt for an edit waiting for confirmation, such as `Delete 'foo'? y/n`.
+    fn confirmation_prompt(&self, request: TreeEditRequest<T::Id>) -> Line<'a> {
+        let (verb, node) = match request {
+            TreeEditRequest::ReorderUp { node, .. } => ("Move up", node),
+            TreeEditRequest::ReorderDown { node, .. } => ("Move down", node),
+            TreeEditRequest::AddChild { parent } => ("Add a child to", parent),
+            TreeEditRequest::Rename { node } => ("Rename", node),
+            TreeEditRequest::Detach { node, .. } => ("Detach", node),
+            TreeEditRequest::Delete { node } => ("Delete", node),
+            TreeEditRequest::Yank { node } => ("Yank", node),
+            TreeEditRequest::Paste { parent } => ("Paste into", parent),
+        };
+        let name = self
+            .columns
+            .cell_text(self.model, node, self.columns.tree_column_index());
+        Line::from(name.map_or_else(
+            || format!(" {verb} the selected node? y/n "),
+            |name| format!(" {verb} '{name}'? y/n "),
+        ))
+    }
+
     fn prepare_render(&self, inner: Rect, state: &mut TreeListViewState<T::Id>) -> RenderPlan {
         state.ensure_projection(self.model, self.query);
         state.ensure_mark_states(self.model);
         state.select_column(state.selected_column(), self.columns.column_count());
 
-        let header_height = self.columns.header_height().min(inner.height);
-        let selection_width =
-            u16::try_from(Line::from(self.style.highlight_symbol).width()).unwrap_or(u16::MAX);
-        let layout = self.resolve_layout(
-            inner,
-            state.projection().len(),
-            header_height,
-            selection_width,
-        );
+        let (layout, header_height, selection_width) =
+            self.measure_layout(inner, state.projection().len(), state.column_widths());
         let viewport_height = usize::from(layout.table.height.saturating_sub(header_height));
-        state.ensure_selection_visible(viewport_height, self.style.scroll_policy);
-        state.clamp_offset_to_viewport(viewport_height);
+        state.pin_tail(viewport_height);
+        state.ensure_selection_visible(
+            viewport_height,
+            self.style.scroll_policy,
+            self.style.overscroll,
+        );
+        state.clamp_offset_to_viewport(viewport_height, self.style.overscroll);
+        state.record_viewport(viewport_height);
 
         let max_horizontal = layout.virtual_width.saturating_sub(layout.table.width);
         if matches!(self.style.horizontal_scroll, TreeHorizontalScroll::Disabled) {
@@ -215,15 +631,70 @@ where
             state.clamp_horizontal_offset(max_horizontal);
         }
 
-        let offset = state.offset().min(state.projection().len());
-        let visible_end = offset
-            .saturating_add(viewport_height)
-            .min(state.projection().len());
-        let rows = RowWindow::new(
-            self.style.row_rendering,
-            offset..visible_end,
+        self.plan(
+            layout,
+            header_height,
+            selection_width,
+            column_boxes,
+            (state.offset(), state.horizontal_offset()),
             state.projection().len(),
-        );
+        )
+    }
+
+    /// Plans a frame from the state as is, clamping offsets locally instead of storing them.
+    fn prepare_spectator(&self, inner: Rect, state: &TreeListViewState<T::Id>) -> RenderPlan {
+        let total_rows = state.projection().len();
+        let (layout, header_height, selection_width) =
+            self.measure_layout(inner, total_rows, state.column_widths());
+        let viewport_height = usize::from(layout.table.height.saturating_sub(header_height));
+        let offset = state
+            .offset()
+            .min(total_rows.saturating_sub(viewport_height));
+        let horizontal_offset = match self.style.horizontal_scroll {
+            TreeHorizontalScroll::Disabled => 0,
+            TreeHorizontalScroll::Enabled => state
+                .horizontal_offset()
+                .min(layout.virtual_width.saturating_sub(layout.table.width)),
+        };
+        let column_boxes =
+            column_hit_boxes(&layout.widths, selection_width, self.style.column_spacing);
+        self.plan(
+            layout,
+            header_height,
+            selection_width,
+            column_boxes,
+            (offset, horizontal_offset),
+            total_rows,
+        )
+    }
+
+    fn measure_layout(
+        &self,
+        inner: Rect,
+        total_rows: usize,
+        overrides: &AdaptiveColumns,
+    ) -> (RenderLayout, u16, u16) {
+        let header_height = self.columns.header_height().min(inner.height);
+        let selection_width =
+            u16::try_from(Line::from(self.style.highlight_symbol).width()).unwrap_or(u16::MAX);
+        let layout =
+            self.resolve_layout(inner, total_rows, header_height, selection_width, overrides);
+        (layout, header_height, selection_width)
+    }
+
+    fn plan(
+        &self,
+        layout: RenderLayout,
+        header_height: u16,
+        selection_width: u16,
+        column_boxes: SmallVec<[ColumnHitBox; 8]>,
+        (offset, horizontal_offset): (usize, u16),
+        total_rows: usize,
+    ) -> RenderPlan {
+        let viewport_height = usize::from(layout.table.height.saturating_sub(header_height));
+        let offset = offset.min(total_rows);
+        let visible_end = offset.saturating_add(viewport_height).min(total_rows);
+        let rows = RowWindow::new(self.style.row_rendering, offset..visible_end, total_rows);
         RenderPlan {
             layout,
             header_height,
@@ -231,15 +702,18 @@ where
             viewport_height,
             column_boxes,
             rows,
+            offset,
+            horizontal_offset,
         }
     }
 
     fn render_projected_rows(
         &self,
         buffer: &mut Buffer,
-        state: &mut TreeListViewState<T::Id>,
+        state: &TreeListViewState<T::Id>,
+        scratch: &mut Buffer,
         plan: RenderPlan,
-    ) {
+    ) -> (TreeHitMap, usize) {
         let RenderPlan {
             layout,
             header_height,
@@ -247,15 +721,29 @@ where
             viewport_height,
             column_boxes,
             rows: row_window,
+            offset,
+            horizontal_offset,
         } = plan;
-        let rows = self.build_rows(
-            state.projection(),
+        let visible_columns = visible_columns(
+            &column_boxes,
+            horizontal_offset.saturating_add(selection_width),
+            horizontal_offset.saturating_add(layout.table.width),
+        );
+        let measured = match self.style.label_scrollbar {
+            TreeLabelScrollbar::Hidden => 0..0,
+            TreeLabelScrollbar::Visible => row_window.visible.clone(),
+        };
+        let built = self.build_rows(
+            state,
             row_window.rendered.clone(),
-            state.selected_index(),
-            state.selected_column(),
-            state.draw_lines(),
-            |id| state.mark_state_cached(id),
+            visible_columns,
+            &layout.widths,
+            measured,
         );
+        let fills = built
+            .fills
+            .get(row_window.first_visible()..)
+            .unwrap_or_default();
         let selected = state
             .selected_index()
             .and_then(|selected| row_window.rendered_index(selected));
@@ -263,38 +751,47 @@ where
             .with_offset(row_window.table_offset)
             .with_selected(selected)
             .with_selected_column(state.selected_column());
-        let table = self.table(rows, &layout.widths, self.columns.header());
+        let table = self.table(built.rows, &layout.widths, self.columns.header());
 
+        let fill_area = Rect {
+            x: layout.table.x.saturating_add(selection_width),
+            y: layout.table.y.saturating_add(header_height),
+            width: layout.table.width.saturating_sub(selection_width),
+            height: layout.table.height.saturating_sub(header_height),
+        };
         if layout.virtual_width > layout.table.width {
             let virtual_area = Rect::new(0, 0, layout.virtual_width, layout.table.height);
-            state.render_buffer.resize(virtual_area);
-            state.render_buffer.reset();
-            StatefulWidget::render(
-                table,
-                virtual_area,
-                &mut state.render_buffer,
-                &mut table_state,
-            );
+            scratch.resize(virtual_area);
+            scratch.reset();
+            let fill_area = Rect {
+                x: selection_width.saturating_add(horizontal_offset),
+                y: header_height,
+                ..fill_area
+            };
+            self.paint_fills(scratch, fill_area, fills);
+            StatefulWidget::render(table, virtual_area, scratch, &mut table_state);
             blit_horizontal(
-                &state.render_buffer,
+                scratch,
                 buffer,
                 layout.table,
-                state.horizontal_offset(),
+                horizontal_offset,
                 selection_width,
             );
         } else {
+            self.paint_fills(buffer, fill_area, fills);
             StatefulWidget::render(table, layout.table, buffer, &mut table_state);
         }
 
         render_scrollbars(
             &layout,
             buffer,
-            state.offset(),
-            state.horizontal_offset(),
-            state.projection().len(),
+            offset,
+            horizontal_offset,
+            self.scrollable_rows(state.projection().len(), viewport_height),
             viewport_height,
+            self.style.scrollbar_style,
         );
-        state.hit_map = TreeHitMap {
+        let hit_map = TreeHitMap {
             table: layout.table,
             rows: Rect {
                 y: layout.table.y.saturating_add(header_height),
@@ -305,10 +802,173 @@ where
             horizontal_scrollbar: layout.horizontal_scrollbar,
             range_start: row_window.visible.start,
             range_end: row_window.visible.end,
-            horizontal_offset: state.horizontal_offset(),
+            horizontal_offset,
             selection_width,
             columns: column_boxes,
+            tree_column: self.columns.tree_column_index(),
+            indent_width: glyph_width(self.glyphs.indent),
+            expander_width: glyph_width(self.glyphs.collapsed),
+            panes: SmallVec::new(),
+        };
+        (hit_map, built.widest_label)
+    }
+
+    /// Draws a thumb in the bottom border under the on-screen part of the tree column, spanning the
+    /// share of the widest visible label that the column shows. Cells already taken by bottom
+    /// titles keep their text.
+    fn render_label_scrollbar(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        hit_map: &TreeHitMap,
+        widest_label: usize,
+    ) {
+        if self.style.label_scrollbar == TreeLabelScrollbar::Hidden
+            || !self.style.borders.contains(Borders::BOTTOM)
+            || area.height < 2
+        {
+            return;
+        }
+        let Some(column) = hit_map.columns.get(hit_map.tree_column) else {
+            return;
+        };
+        if widest_label <= usize::from(column.width) {
+            return;
+        }
+        let offset = hit_map.horizontal_offset;
+        let start = column
+            .start
+            .max(hit_map.selection_width.saturating_add(offset));
+        let end = column
+            .start
+            .saturating_add(column.width)
+            .min(offset.saturating_add(hit_map.table.width));
+        if end <= start {
+            return;
+        }
+        let track = usize::from(end - start);
+        let hidden = usize::from(start - column.start);
+        let thumb_start = hidden * track / widest_label;
+        let thumb_len = (track * track / widest_label)
+            .max(1)
+            .min(track - thumb_start);
+        let x = hit_map.table.x.saturating_add(start - offset);
+        let y = area.bottom() - 1;
+        let border = self.style.border_type.to_border_set().horizontal_bottom;
+        for dx in (thumb_start..thumb_start + thumb_len).filter_map(|dx| u16::try_from(dx).ok()) {
+            if let Some(cell) = buffer
+                .cell_mut((x.saturating_add(dx), y))
+                .filter(|cell| cell.symbol() == border)
+            {
+                cell.set_symbol(line::THICK_HORIZONTAL)
+                    .set_style(self.style.scrollbar_style);
+            }
+        }
+    }
+
+    /// Paints the fill style behind each visible row, one row per line of `area`, from the
+    /// fractions collected while building the rows.
+    fn paint_fills(&self, buffer: &mut Buffer, area: Rect, fills: &[Option<f32>]) {
+        for (y, fraction) in (area.y..area.bottom()).zip(fills) {
+            let Some(fraction) = *fraction else {
+                continue;
+            };
+            let width = fill_width(area.width, fraction);
+            if width > 0 {
+                buffer.set_style(Rect::new(area.x, y, width, 1), self.style.fill_style);
+            }
+        }
+    }
+
+    /// Draws the block and the root banner, then splits the remaining area into the table and the
+    /// row-number gutter.
+    fn render_frame(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        state: &TreeListViewState<T::Id>,
+    ) -> Option<(Rect, Option<Rect>)> {
+        if area.is_empty() {
+            return None;
+        }
+        let prompt = self.footer_prompt(state);
+        let block = self.block(prompt);
+        let mut inner = block.inner(area);
+        block.render(area, buffer);
+        if let Some(banner) = self.banner() {
+            let height = u16::try_from(banner.height())
+                .unwrap_or(u16::MAX)
+                .min(inner.height);
+            Paragraph::new(banner).render(Rect { height, ..inner }, buffer);
+            inner.y = inner.y.saturating_add(height);
+            inner.height = inner.height.saturating_sub(height);
+        }
+        if inner.is_empty() {
+            return None;
+        }
+        let gutter_width = self.gutter_width(state.projection().len(), inner.width);
+        let table = Rect {
+            x: inner.x.saturating_add(gutter_width),
+            width: inner.width.saturating_sub(gutter_width),
+            ..inner
         };
+        let gutter = (gutter_width > 0).then_some(Rect {
+            width: gutter_width,
+            ..inner
+        });
+        Some((table, gutter))
+    }
+
+    fn banner(&self) -> Option<Text<'a>> {
+        let root = self.model.roots().next()?;
+        self.label.root_banner(self.model, root)
+    }
+
+    /// Returns the width of the row-number gutter, including its trailing space.
+    fn gutter_width(&self, total_rows: usize, available: u16) -> u16 {
+        if matches!(self.style.row_numbers, TreeRowNumbers::Hidden) {
+            return 0;
+        }
+        let digits = total_rows.max(1).ilog10().saturating_add(2);
+        u16::try_from(digits).unwrap_or(u16::MAX).min(available)
+    }
+
+    fn render_row_numbers(
+        &self,
+        buffer: &mut Buffer,
+        gutter: Rect,
+        state: &TreeListViewState<T::Id>,
+        plan: &RenderPlan,
+    ) {
+        let digits = usize::from(gutter.width.saturating_sub(1));
+        let selected = state.selected_index();
+        let top = gutter.y.saturating_add(plan.header_height);
+        for (y, index) in (top..gutter.bottom()).zip(plan.rows.visible.clone()) {
+            let number = match (self.style.row_numbers, selected) {
+                (TreeRowNumbers::Relative, Some(selected)) if selected != index => {
+                    selected.abs_diff(index)
+                }
+                _ => index.saturating_add(1),
+            };
+            buffer.set_stringn(
+                gutter.x,
+                y,
+                format!("{number:>digits$}"),
+                digits,
+                self.style.row_number_style,
+            );
+        }
+    }
+
+    /// Returns the rows the vertical scrollbar spans, including blank rows below the last one
+    /// under [`TreeOverscroll::Enabled`].
+    const fn scrollable_rows(&self, total_rows: usize, viewport_height: usize) -> usize {
+        match self.style.overscroll {
+            TreeOverscroll::Enabled if total_rows > 0 => {
+                total_rows.saturating_add(viewport_height.saturating_sub(1))
+            }
+            TreeOverscroll::Enabled | TreeOverscroll::Disabled => total_rows,
+        }
     }
 
     fn resolve_layout(
@@ -317,6 +977,7 @@ where
         total_rows: usize,
         header_height: u16,
         selection_width: u16,
+        overrides: &AdaptiveColumns,
     ) -> RenderLayout {
         let gap_count =
             u16::try_from(self.columns.column_count().saturating_sub(1)).unwrap_or(u16::MAX);
@@ -339,6 +1000,7 @@ where
                 TreeHorizontalScroll::Disabled => column_viewport,
             };
             widths = self.columns.widths(target);
+            overrides.apply(&mut widths);
             let column_width = widths.iter().copied().fold(0_u16, u16::saturating_add);
             virtual_width = selection_width
                 .saturating_add(spacing)
@@ -393,20 +1055,64 @@ where
     type State = TreeListViewState<T::Id>;
 
     fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
-        if area.is_empty() {
-            state.hit_map = TreeHitMap::default();
-            return;
+        if !area.is_empty() {
+            state.ensure_projection(self.model, self.query);
         }
-
-        let block = self.block();
-        let inner = block.inner(area);
-        block.render(area, buffer);
-        if inner.is_empty() {
+        let Some((table_area, gutter)) = self.render_frame(area, buffer, state) else {
             state.hit_map = TreeHitMap::default();
             return;
+        };
+        let plan = self.prepare_render(table_area, state);
+        if matches!(self.style.model_check, TreeModelCheck::Verify) {
+            let nodes = state.projection().nodes();
+            let missing: SmallVec<[T::Id; 4]> = nodes
+                .get(plan.rows.visible.clone())
+                .unwrap_or_default()
+                .iter()
+                .filter(|node| !node.is_synthetic() && !self.model.contains(node.id()))
+                .map(|node| node.id())
+                .collect();
+            state.record_missing(missing);
+        }
+        if let Some(gutter) = gutter {
+            self.render_row_numbers(buffer, gutter, state, &plan);
         }
-        let plan = self.prepare_render(inner, state);
-        self.render_projected_rows(buffer, state, plan);
+        self.render_position_indicator(area, buffer, state, &plan);
+        let mut scratch = std::mem::replace(&mut state.render_buffer, Buffer::empty(Rect::ZERO));
+        let (hit_map, widest_label) = self.render_projected_rows(buffer, state, &mut scratch, plan);
+        state.hit_map = hit_map;
+        state.render_buffer = scratch;
+        self.render_label_scrollbar(area, buffer, &state.hit_map, widest_label);
+        self.render_details(area, buffer, state, &state.hit_map);
+        state.mark_rendered();
+        state.publish_scroll();
+    }
+}
+
+impl<T, F, S, L, C> TreeListView<'_, T, F, S, L, C>
+where
+    T: TreeModel,
+    F: TreeFilter<T>,
+    S: TreeSort<T>,
+    L: TreeLabelRenderer<T>,
+    C: TreeColumns<T>,
+{
+    /// Renders into a `width` by `height` buffer and returns its text, see
+    /// [`tree_buffer_text`](crate::tree_buffer_text).
+    ///
+    /// Rendering depends only on the model, query, style, and state, so applications can compare
+    /// the text with golden files to test their own labels and columns.
+    #[must_use]
+    pub fn render_to_string(
+        self,
+        width: u16,
+        height: u16,
+        state: &mut TreeListViewState<T::Id>,
+    ) -> String {
+        let area = Rect::new(0, 0, width, height);
+        let mut buffer = Buffer::empty(area);
+        self.render(area, &mut buffer, state);
+        tree_buffer_text(&buffer)
     }
 }
 
@@ -425,6 +1131,16 @@ struct RenderPlan {
     viewport_height: usize,
     column_boxes: SmallVec<[ColumnHitBox; 8]>,
     rows: RowWindow,
+    offset: usize,
+    horizontal_offset: u16,
+}
+
+/// The table rows of one frame and what the frame needs from them afterwards.
+struct BuiltRows<'a> {
+    rows: Vec<Row<'a>>,
+    /// The fill fraction of each row, by rendered index.
+    fills: Vec<Option<f32>>,
+    widest_label: usize,
 }
 
 struct RowWindow {
@@ -449,6 +1165,11 @@ impl RowWindow {
         }
     }
 
+    /// Returns the rendered index of the first visible row.
+    const fn first_visible(&self) -> usize {
+        self.visible.start.saturating_sub(self.rendered.start)
+    }
+
     fn rendered_index(&self, index: usize) -> Option<usize> {
         self.rendered
             .contains(&index)
@@ -456,6 +1177,22 @@ impl RowWindow {
     }
 }
 
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "the rounded value is clamped to 0..=width"
+)]
+fn fill_width(width: u16, fraction: f32) -> u16 {
+    if fraction.is_nan() {
+        return 0;
+    }
+    (f32::from(width) * fraction.clamp(0.0, 1.0)).round() as u16
+}
+
+fn glyph_width(glyph: &str) -> u16 {
+    u16::try_from(Line::from(glyph).width()).unwrap_or(u16::MAX)
+}
+
 fn column_hit_boxes(
     widths: &[u16],
     selection_width: u16,
@@ -473,6 +1210,19 @@ fn column_hit_boxes(
         .collect()
 }
 
+/// Returns the columns intersecting the virtual span `start..end`.
+fn visible_columns(columns: &[ColumnHitBox], start: u16, end: u16) -> std::ops::Range<usize> {
+    let first = columns
+        .iter()
+        .position(|column| column.start.saturating_add(column.width) > start)
+        .unwrap_or(columns.len());
+    let last = columns
+        .iter()
+        .rposition(|column| column.start < end)
+        .map_or(first, |last| last.saturating_add(1).max(first));
+    first..last
+}
+
 fn blit_horizontal(
     source: &Buffer,
     target: &mut Buffer,
@@ -506,6 +1256,7 @@ fn render_scrollbars(
     horizontal_offset: u16,
     total_rows: usize,
     viewport_height: usize,
+    style: Style,
 ) {
     if let Some(area) = layout.vertical_scrollbar {
         let mut scrollbar_state =
@@ -514,6 +1265,7 @@ fn render_scrollbars(
                 .viewport_content_length(viewport_height);
         Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
+            .style(style)
             .render(area, buffer, &mut scrollbar_state);
     }
     if let Some(area) = layout.horizontal_scrollbar {
@@ -526,6 +1278,7 @@ fn render_scrollbars(
         .viewport_content_length(viewport_width);
         Scrollbar::default()
             .orientation(ScrollbarOrientation::HorizontalBottom)
+            .style(style)
             .render(area, buffer, &mut scrollbar_state);
     }
 }
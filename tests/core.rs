@@ -1,12 +1,20 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::time::{Duration, Instant};
 
+use ratatui::widgets::TableState;
 use smallvec::smallvec;
 use tui_treelistview::{
-    ColumnDef, ColumnWidth, IndexedTree, IndexedTreeError, ProjectedNode, TreeAction,
-    TreeChangeSet, TreeChildren, TreeColumnSet, TreeEditCommand, TreeEditor, TreeEvent,
-    TreeExpansionState, TreeFilter, TreeFilterConfig, TreeIntent, TreeListViewSnapshot,
-    TreeListViewState, TreeMarkState, TreeModel, TreeModelRef, TreeQuery, TreeRevision,
-    TreeRootVisibility, TreeSelectionFallback, TreeSelectionUpdate, TreeSort, TreeViewAction,
+    ColumnDef, ColumnWidth, FnTreeModel, IndexedTree, IndexedTreeError, OwnedChildrenCache,
+    OwnedTreeModel, PathInterner, ProjectedNode, SubtreeAgg, TreeAcceleration, TreeAction,
+    TreeCellFlagging, TreeChangeSet, TreeChildren, TreeColumnSet, TreeEditAction, TreeEditCommand,
+    TreeEditError, TreeEditRequest, TreeEditor, TreeEvent, TreeExpandAllScope, TreeExpansionState,
+    TreeFilter, TreeFilterConfig, TreeInsertPosition, TreeIntent, TreeLabelPrefix,
+    TreeLabelProvider, TreeListViewSnapshot, TreeListViewState, TreeMarkState, TreeModel,
+    TreeModelOwned, TreeModelRef, TreePath, TreePlaceholder, TreePlaceholders, TreeQuery,
+    TreeReview, TreeRevision, TreeRootVisibility, TreeSelectionFallback, TreeSelectionUpdate,
+    TreeSharedState, TreeSnapshotV1, TreeSnapshotV2, TreeSort, TreeVersionedSnapshot,
+    TreeViewAction,
 };
 
 #[derive(Clone, Debug)]
@@ -54,6 +62,25 @@ impl TestTree {
         }
     }
 
+    /// Chains `hubs` diamonds: hub `3k` reaches hub `3k + 3` through both `3k + 1` and
+    /// `3k + 2`, so a walk that does not prune repeated nodes visits `2^hubs` paths.
+    fn diamonds(hubs: usize) -> Self {
+        let last = 3 * hubs;
+        let mut children: Vec<Children> = (0..last)
+            .map(|id| match id % 3 {
+                0 => Children::Loaded(vec![id + 1, id + 2]),
+                1 => Children::Loaded(vec![id + 2]),
+                _ => Children::Loaded(vec![id + 1]),
+            })
+            .collect();
+        children.push(Children::Leaf);
+        Self {
+            roots: vec![0],
+            children,
+            revision: TreeRevision::INITIAL,
+        }
+    }
+
     fn remove(&mut self, parent: usize, node: usize) {
         if let Children::Loaded(children) = &mut self.children[parent] {
             children.retain(|child| *child != node);
@@ -173,6 +200,17 @@ impl TreeEditor for EditableTree {
     }
 }
 
+struct NumberLabel;
+
+impl TreeLabelProvider<TestTree> for NumberLabel {
+    fn label_parts<'a>(&'a self, _model: &'a TestTree, id: usize) -> TreeLabelPrefix<'a> {
+        TreeLabelPrefix {
+            name: Cow::Owned(format!("node {id}")),
+            prefix: None,
+        }
+    }
+}
+
 fn columns() -> TreeColumnSet<'static, TestTree> {
     TreeColumnSet::new([ColumnDef::tree("Name", ColumnWidth::fixed(12))]).expect("one tree column")
 }
@@ -210,6 +248,22 @@ fn projection_supports_forests_and_hidden_roots() {
     assert_eq!(first.level(), 0);
 }
 
+#[test]
+fn selection_ancestors_follow_visible_parent_links() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.ancestors_of_selection().is_empty());
+    assert!(state.select_by_id(&model, &query, 3));
+    assert_eq!(state.ancestors_of_selection().as_slice(), [0, 1]);
+    assert!(state.select_by_id(&model, &query, 4));
+    assert!(state.ancestors_of_selection().is_empty());
+
+    let hidden = TreeQuery::new().with_root_visibility(TreeRootVisibility::Hidden);
+    assert!(state.select_by_id(&model, &hidden, 3));
+    assert_eq!(state.ancestors_of_selection().as_slice(), [0, 1]);
+}
+
 #[test]
 fn filtering_keeps_paths_and_can_force_expansion() {
     let model = TestTree::forest();
@@ -250,6 +304,43 @@ fn filtering_keeps_paths_and_can_force_expansion() {
     assert_eq!(collapsed.visible_ids().collect::<Vec<_>>(), [0]);
 }
 
+#[test]
+fn collapsed_filtered_branches_count_their_matched_descendants() {
+    let model = TestTree::forest();
+    let manual = TreeQuery::new().with_filter(
+        matches_two_or_three,
+        TreeFilterConfig::enabled_manual_expand(),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &manual));
+    assert_eq!(
+        state
+            .projection()
+            .get_by_id(0)
+            .map(ProjectedNode::matched_descendants),
+        Some(2)
+    );
+}
+
+#[test]
+fn manual_expand_filtering_keeps_expanded_branches_collapsed_below() {
+    let model = TestTree::forest();
+    let manual = TreeQuery::new().with_filter(
+        matches_two_or_three,
+        TreeFilterConfig::enabled_manual_expand(),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    assert!(state.set_expanded(0, None, true));
+    assert!(state.ensure_projection(&model, &manual));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 2]);
+    assert_eq!(
+        state.effective_expansion(1),
+        Some(TreeExpansionState::Collapsed)
+    );
+}
+
 #[test]
 fn filtering_can_be_disabled_without_replacing_its_policy() {
     let model = TestTree::forest();
@@ -318,6 +409,32 @@ fn replacing_the_same_policy_type_invalidates_its_projection_stamp() {
     assert_eq!(state.visible_ids().collect::<Vec<_>>(), [4, 5, 0, 2, 1, 3]);
 }
 
+#[test]
+fn committed_filter_expansion_survives_clearing_the_filter() {
+    let model = TestTree::forest();
+    let filtered = TreeQuery::new().with_filter(
+        ExactMatch(3),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &filtered));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3]);
+
+    assert!(!state.commit_filtered_expansion(
+        &model,
+        &ExactMatch(3),
+        TreeFilterConfig::enabled_manual_expand()
+    ));
+    assert!(state.commit_filtered_expansion(&model, &ExactMatch(3), TreeFilterConfig::enabled()));
+    assert!(!state.commit_filtered_expansion(&model, &ExactMatch(3), TreeFilterConfig::enabled()));
+
+    let cleared = TreeQuery::new();
+    assert!(state.ensure_projection(&model, &cleared));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4]);
+    assert!(!state.node_is_expanded(4, None));
+}
+
 #[test]
 fn selection_uses_stable_ids_across_sorting_and_model_changes() {
     let mut model = TestTree::forest();
@@ -343,6 +460,38 @@ fn selection_uses_stable_ids_across_sorting_and_model_changes() {
     assert_eq!(state.selected_id(), None);
 }
 
+#[test]
+fn sibling_fallbacks_move_a_deleted_selection_to_a_neighbour() {
+    let next = TreeQuery::new().with_selection_fallback(TreeSelectionFallback::NextSibling);
+    let mut model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &next, 1));
+    model.remove(0, 1);
+    assert!(state.ensure_projection(&model, &next));
+    assert_eq!(state.selected_id(), Some(2));
+
+    let mut model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &next, 2));
+    model.remove(0, 2);
+    assert!(state.ensure_projection(&model, &next));
+    assert_eq!(state.selected_id(), Some(1));
+
+    let previous = TreeQuery::new().with_selection_fallback(TreeSelectionFallback::PreviousSibling);
+    let mut model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &previous, 1));
+    model.remove(0, 1);
+    assert!(state.ensure_projection(&model, &previous));
+    assert_eq!(state.selected_id(), Some(2));
+    model.remove(0, 2);
+    assert!(state.ensure_projection(&model, &previous));
+    assert_eq!(state.selected_id(), Some(0));
+}
+
 #[test]
 fn selection_follows_a_stable_id_when_its_path_changes() {
     let mut model = TestTree::forest();
@@ -421,6 +570,28 @@ fn selection_distinguishes_occurrences_below_hidden_roots() {
     assert_eq!(state.selected_parent_id(), Some(1));
 }
 
+#[test]
+fn recursive_toggles_fall_back_to_one_level_above_the_budget() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let toggle = TreeAction::<()>::View(TreeViewAction::ToggleRecursive);
+    let mut state = TreeListViewState::new();
+    assert!(state.set_recursive_expansion_budget(Some(3)));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_first());
+
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+    let _ = state.ensure_projection(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 2, 4]);
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+
+    assert!(state.set_recursive_expansion_budget(Some(4)));
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+    let _ = state.ensure_projection(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4]);
+}
+
 #[test]
 fn selecting_an_invalid_index_clears_the_selection() {
     let model = TestTree::forest();
@@ -469,6 +640,47 @@ fn lazy_branches_emit_load_intents_and_loading_is_inert() {
     );
 }
 
+#[test]
+fn expanded_lazy_branches_show_a_loading_row_and_open_once_loaded() {
+    let mut model = TestTree {
+        roots: vec![0],
+        children: vec![Children::Unloaded, Children::Leaf],
+        revision: TreeRevision::INITIAL,
+    };
+    let query = TreeQuery::new().with_placeholders(TreePlaceholders::Visible);
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.select_by_id(&model, &query, 0));
+    let toggle = TreeAction::<()>::View(TreeViewAction::ToggleNode);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, toggle),
+        TreeEvent::Intent(TreeIntent::LoadChildren(0))
+    );
+    model.children[0] = Children::Loading;
+    model.revision.advance();
+    assert!(state.ensure_projection(&model, &query));
+    let placeholders: Vec<_> = state
+        .visible_nodes()
+        .iter()
+        .map(|node| node.placeholder())
+        .collect();
+    assert_eq!(placeholders, [None, Some(TreePlaceholder::Loading)]);
+
+    model.children[0] = Children::Loaded(vec![1]);
+    model.revision.advance();
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1]);
+
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, toggle),
+        TreeEvent::Changed
+    );
+    model.children[0] = Children::Loading;
+    model.revision.advance();
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_nodes().len(), 1);
+}
+
 #[test]
 fn right_and_left_follow_standard_tree_navigation() {
     let model = TestTree::forest();
@@ -509,6 +721,79 @@ fn right_and_left_follow_standard_tree_navigation() {
     assert_eq!(state.selected_id(), Some(0));
 }
 
+#[test]
+fn descending_returns_to_the_child_left_through_select_parent() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &query, 2));
+
+    let parent = TreeAction::<()>::View(TreeViewAction::SelectParent);
+    let child = TreeAction::<()>::View(TreeViewAction::SelectFirstChild);
+    let _ = state.handle_action(&model, &query, &columns, parent);
+    assert_eq!(state.selected_id(), Some(0));
+    let _ = state.handle_action(&model, &query, &columns, child);
+    assert_eq!(state.selected_id(), Some(2));
+
+    assert!(state.select_by_id(&model, &query, 4));
+    let _ = state.handle_action(&model, &query, &columns, child);
+    assert_eq!(state.selected_id(), Some(5));
+}
+
+#[test]
+fn approaching_the_last_row_reports_the_end_once() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let next = TreeAction::<()>::View(TreeViewAction::SelectNext);
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.set_end_threshold(Some(1)));
+    assert!(state.select_by_id(&model, &query, 2));
+    assert!(!state.take_end_reached());
+
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, next),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.selected_id(), Some(4));
+    assert!(state.take_end_reached());
+    let _ = state.handle_action(&model, &query, &columns, next);
+    assert!(!state.take_end_reached());
+
+    let first = TreeAction::<()>::View(TreeViewAction::SelectFirst);
+    let last = TreeAction::<()>::View(TreeViewAction::SelectLast);
+    let _ = state.handle_action(&model, &query, &columns, first);
+    assert!(!state.take_end_reached());
+    let _ = state.handle_action(&model, &query, &columns, last);
+    assert!(state.take_end_reached());
+}
+
+#[test]
+fn remembered_children_are_forgotten_with_hidden_parents() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let hidden = TreeQuery::new().with_filter(
+        ExactMatch(5),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &query, 2));
+
+    let parent = TreeAction::<()>::View(TreeViewAction::SelectParent);
+    let child = TreeAction::<()>::View(TreeViewAction::SelectFirstChild);
+    let _ = state.handle_action(&model, &query, &columns, parent);
+    assert!(state.ensure_projection(&model, &hidden));
+    assert!(state.select_by_id(&model, &query, 0));
+    let _ = state.handle_action(&model, &query, &columns, child);
+    assert_eq!(state.selected_id(), Some(1));
+}
+
 #[test]
 fn marks_are_aggregated_without_recursion() {
     let model = TestTree::forest();
@@ -523,6 +808,24 @@ fn marks_are_aggregated_without_recursion() {
     assert_eq!(state.mark_state(0), TreeMarkState::Marked);
 }
 
+#[test]
+fn bulk_marks_report_changes_and_aggregate() {
+    let model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.set_marks([1, 2], true));
+    state.ensure_mark_states(&model);
+    assert_eq!(state.mark_state(0), TreeMarkState::Marked);
+
+    assert!(state.set_marks([1, 2, 5], false));
+    assert!(!state.set_marks([1, 2], false));
+    assert!(state.set_marks([4, 5], true));
+    state.ensure_mark_states(&model);
+    assert_eq!(state.mark_state(0), TreeMarkState::Unmarked);
+    assert_eq!(state.mark_state(4), TreeMarkState::Marked);
+    assert!(state.clear_marks());
+    assert_eq!(state.manual_marked_ids().count(), 0);
+}
+
 #[test]
 fn projection_handles_a_very_deep_tree_iteratively() {
     const DEPTH: usize = 20_000;
@@ -563,6 +866,175 @@ fn adapters_parse_invariants_once() {
     assert_eq!(model.revision(), TreeRevision::new(7));
 }
 
+#[test]
+fn closure_models_collect_reachable_children_once() {
+    let model = FnTreeModel::new(
+        || [0, 4],
+        |id| match id {
+            0 => smallvec::smallvec![1, 2],
+            1 => smallvec::smallvec![0],
+            _ => smallvec::SmallVec::new(),
+        },
+    )
+    .with_revision(TreeRevision::new(3));
+    assert_eq!(model.roots().collect::<Vec<_>>(), [0, 4]);
+    assert!(matches!(model.children(0), TreeChildren::Loaded([1, 2])));
+    assert!(matches!(model.children(2), TreeChildren::Leaf));
+    assert!(matches!(model.children(9), TreeChildren::Leaf));
+    assert_eq!(model.size_hint(), 4);
+
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let _ = state.set_expanded(0, None, true);
+    let _ = state.ensure_projection(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 2, 4]);
+}
+
+struct Computed {
+    fetches: std::cell::Cell<usize>,
+}
+
+impl TreeModelOwned for Computed {
+    type Id = usize;
+
+    fn roots(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        std::iter::once(0)
+    }
+
+    fn children(&self, id: Self::Id) -> smallvec::SmallVec<[Self::Id; 8]> {
+        self.fetches.set(self.fetches.get() + 1);
+        if id < 7 {
+            smallvec::smallvec![2 * id + 1, 2 * id + 2]
+        } else {
+            smallvec::SmallVec::new()
+        }
+    }
+
+    fn revision(&self) -> TreeRevision {
+        TreeRevision::INITIAL
+    }
+}
+
+#[test]
+fn owned_models_fetch_children_of_visible_rows_only() {
+    let model = Computed {
+        fetches: std::cell::Cell::new(0),
+    };
+    let query = TreeQuery::new();
+    let mut cache = OwnedChildrenCache::new();
+    let mut state = TreeListViewState::new();
+
+    let adapter = OwnedTreeModel::new(&model, &mut cache, &state);
+    assert_eq!(
+        model.fetches.get(),
+        3,
+        "root children learn whether they are branches"
+    );
+    assert!(matches!(adapter.children(0), TreeChildren::Loaded([1, 2])));
+    assert!(matches!(adapter.children(3), TreeChildren::Unloaded));
+
+    assert!(state.set_expanded(0, None, true));
+    let adapter = OwnedTreeModel::new(&model, &mut cache, &state);
+    assert_eq!(model.fetches.get(), 3);
+    let _ = state.ensure_projection(&adapter, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 2]);
+
+    assert!(state.set_expanded(2, Some(0), true));
+    let adapter = OwnedTreeModel::new(&model, &mut cache, &state);
+    let _ = state.ensure_projection(&adapter, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 2, 5, 6]);
+    assert_eq!(model.fetches.get(), 5);
+    assert!(matches!(
+        adapter.children(5),
+        TreeChildren::Loaded([11, 12])
+    ));
+    assert!(matches!(adapter.children(11), TreeChildren::Unloaded));
+}
+
+#[test]
+fn added_nodes_are_revealed_and_removed_nodes_hand_over_the_selection() {
+    let mut model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let _ = state.ensure_projection(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 4]);
+
+    model.children.push(Children::Leaf);
+    model.children[3] = Children::Loaded(vec![6]);
+    model.revision.advance();
+    assert!(state.on_node_added(&model, &query, Some(3), 6));
+    assert_eq!(state.selected_id(), Some(6));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 6, 2, 4]);
+
+    model.children[3] = Children::Leaf;
+    model.revision.advance();
+    assert!(state.on_node_removed(&model, &query, 6));
+    assert_eq!(state.selected_id(), Some(3));
+
+    let hidden = TreeQuery::new().with_filter(
+        ExactMatch(5),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    model.children.push(Children::Leaf);
+    model.children[5] = Children::Loaded(vec![7]);
+    model.revision.advance();
+    assert!(!state.on_node_added(&model, &hidden, Some(5), 7));
+    assert_eq!(
+        state.selected_id(),
+        Some(5),
+        "the filter hides the new node"
+    );
+}
+
+#[test]
+fn subtree_aggregates_are_memoized_per_revision() {
+    let mut model = TestTree::forest();
+    let mut sizes = SubtreeAgg::new();
+    let mut calls = 0;
+    let mut size = |_: &TestTree, id: usize| {
+        calls += 1;
+        id as u64
+    };
+    assert!(sizes.refresh(&model, &mut size));
+    assert_eq!(sizes.total(0), Some(6));
+    assert_eq!(sizes.descendants(0), Some(3));
+    assert_eq!(sizes.total(4), Some(9));
+    assert_eq!(sizes.descendants(3), Some(0));
+    assert_eq!(sizes.total(9), None);
+
+    assert!(!sizes.refresh(&model, &mut size));
+    sizes.invalidate();
+    assert!(sizes.refresh(&model, &mut size));
+    model.remove(0, 1);
+    assert!(sizes.refresh(&model, &mut size));
+    assert_eq!(sizes.total(0), Some(2));
+    assert_eq!(sizes.descendants(1), None);
+    assert_eq!(calls, 6 + 6 + 4);
+}
+
+#[test]
+fn builder_expands_marks_and_selects_in_order() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let state = TreeListViewState::builder()
+        .expand_depth(1)
+        .select_id(3)
+        .marks([5])
+        .draw_lines(false)
+        .build(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4, 5]);
+    assert_eq!(state.selected_id(), Some(3));
+    assert_eq!(state.mark_state(4), TreeMarkState::Marked);
+    assert!(!state.draw_lines());
+
+    let state = TreeListViewState::builder()
+        .select_root()
+        .build(&model, &query);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 4]);
+    assert_eq!(state.selected_id(), Some(0));
+}
+
 #[test]
 fn edit_changes_reconcile_selection_marks_and_expansion() {
     let mut model = EditableTree(TestTree::forest());
@@ -617,6 +1089,8 @@ fn snapshots_preserve_ids_and_both_scroll_offsets() {
         offset: 9,
         horizontal_offset: 13,
         draw_lines: false,
+        column_widths: vec![None, Some(20)],
+        cell_flags: vec![(2, 1)],
     };
     let state = TreeListViewState::from_snapshot(snapshot.clone());
     assert_eq!(state.snapshot(), snapshot);
@@ -631,22 +1105,1351 @@ fn snapshots_preserve_ids_and_both_scroll_offsets() {
 }
 
 #[test]
-fn column_navigation_recovers_from_a_stale_snapshot_index() {
-    let snapshot = TreeListViewSnapshot {
-        expanded: vec![],
-        manual_marked: vec![],
-        selected: None,
-        selected_column: Some(usize::MAX),
-        offset: 0,
+fn versioned_snapshots_migrate_legacy_formats() {
+    let legacy = TreeSnapshotV1 {
+        expanded: vec![(None, 0)],
+        manual_marked: vec![2],
+        selected: Some(2),
+        selected_column: None,
+        offset: 1,
         horizontal_offset: 0,
         draw_lines: true,
     };
-    let mut state = TreeListViewState::<usize>::from_snapshot(snapshot.clone());
-
-    assert!(state.select_column_left(2));
-    assert_eq!(state.selected_column(), Some(1));
+    let current = TreeListViewSnapshot::from_legacy(legacy.clone());
+    assert_eq!(current.expanded, legacy.expanded);
+    assert!(current.column_widths.is_empty());
+    assert_eq!(TreeVersionedSnapshot::V1(legacy).into_current(), current);
+    assert_eq!(
+        TreeVersionedSnapshot::from(current.clone()).into_current(),
+        current
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let json = r#"{"version":"1","expanded":[[null,0]],"manual_marked":[2],"selected":2,
+            "selected_column":null,"offset":1,"horizontal_offset":0,"draw_lines":true}"#;
+        let stored: TreeVersionedSnapshot<usize> =
+            serde_json::from_str(json).expect("deserialize a first-format snapshot");
+        assert_eq!(stored.into_current(), current);
+
+        let json = serde_json::to_string(&TreeVersionedSnapshot::from(current.clone()))
+            .expect("serialize snapshot");
+        assert!(json.starts_with(r#"{"version":"3""#), "{json}");
+        let decoded: TreeVersionedSnapshot<usize> =
+            serde_json::from_str(&json).expect("deserialize snapshot");
+        assert_eq!(decoded.into_current(), current);
+    }
+}
+
+#[test]
+fn versioned_snapshots_migrate_the_second_format() {
+    let stored = TreeSnapshotV2 {
+        expanded: vec![(None, 0)],
+        manual_marked: vec![2],
+        selected: Some(2),
+        selected_column: Some(1),
+        offset: 0,
+        horizontal_offset: 3,
+        draw_lines: false,
+        column_widths: vec![None, Some(8)],
+    };
+    let current = TreeVersionedSnapshot::V2(stored.clone()).into_current();
+    assert_eq!(current.column_widths, stored.column_widths);
+    assert_eq!(current.horizontal_offset, 3);
+    assert!(current.cell_flags.is_empty());
+
+    #[cfg(feature = "serde")]
+    {
+        let json = r#"{"version":"2","expanded":[[null,0]],"manual_marked":[2],"selected":2,
+            "selected_column":1,"offset":0,"horizontal_offset":3,"draw_lines":false,
+            "column_widths":[null,8]}"#;
+        let decoded: TreeVersionedSnapshot<usize> =
+            serde_json::from_str(json).expect("deserialize a second-format snapshot");
+        assert_eq!(decoded, TreeVersionedSnapshot::V2(stored));
+        assert_eq!(decoded.into_current(), current);
+    }
+}
+
+#[test]
+fn versioned_snapshots_keep_flagged_cells_in_the_third_format() {
+    let mut current = TreeListViewSnapshot::from_legacy(TreeSnapshotV1 {
+        expanded: vec![(None, 0)],
+        manual_marked: vec![],
+        selected: Some(1),
+        selected_column: Some(1),
+        offset: 0,
+        horizontal_offset: 0,
+        draw_lines: true,
+    });
+    current.cell_flags = vec![(1, 1), (3, 0)];
+    let versioned = TreeVersionedSnapshot::from(current.clone());
+    assert!(
+        matches!(&versioned, TreeVersionedSnapshot::V3(stored) if stored.cell_flags.len() == 2)
+    );
+    assert_eq!(versioned.into_current(), current);
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&TreeVersionedSnapshot::from(current.clone()))
+            .expect("serialize snapshot");
+        let decoded: TreeVersionedSnapshot<usize> =
+            serde_json::from_str(&json).expect("deserialize a third-format snapshot");
+        assert_eq!(decoded.into_current(), current);
+
+        let json = r#"{"version":"3","expanded":[],"manual_marked":[],"selected":null,
+            "selected_column":null,"offset":0,"horizontal_offset":0,"draw_lines":true}"#;
+        let decoded: TreeVersionedSnapshot<usize> =
+            serde_json::from_str(json).expect("deserialize a third-format snapshot");
+        let decoded = decoded.into_current();
+        assert!(decoded.column_widths.is_empty());
+        assert!(decoded.cell_flags.is_empty());
+    }
+}
+
+#[test]
+fn column_navigation_recovers_from_a_stale_snapshot_index() {
+    let snapshot = TreeListViewSnapshot {
+        expanded: vec![],
+        manual_marked: vec![],
+        selected: None,
+        selected_column: Some(usize::MAX),
+        offset: 0,
+        horizontal_offset: 0,
+        draw_lines: true,
+        column_widths: Vec::new(),
+        cell_flags: Vec::new(),
+    };
+    let mut state = TreeListViewState::<usize>::from_snapshot(snapshot.clone());
+
+    assert!(state.select_column_left(2));
+    assert_eq!(state.selected_column(), Some(1));
 
     state.restore(snapshot);
     assert!(state.select_column_right(2));
     assert_eq!(state.selected_column(), Some(0));
 }
+
+#[test]
+fn repeated_navigation_accelerates_within_the_interval() {
+    let model = TestTree::dag_with_shared_leaf();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_first());
+    state.set_acceleration(Some(TreeAcceleration {
+        interval: Duration::from_millis(100),
+        steps: [1, 2, 5],
+        repeats_per_stage: 2,
+    }));
+
+    let start = Instant::now();
+    let press = |state: &mut TreeListViewState<usize>, millis| {
+        state.handle_action_at(
+            &model,
+            &query,
+            &columns,
+            TreeAction::<()>::View(TreeViewAction::SelectNext),
+            start + Duration::from_millis(millis),
+        )
+    };
+    let _ = press(&mut state, 0);
+    let _ = press(&mut state, 50);
+    assert_eq!(state.selected_index(), Some(2));
+    let _ = press(&mut state, 100);
+    assert_eq!(state.selected_index(), Some(4));
+
+    assert!(state.select_first());
+    let _ = press(&mut state, 500);
+    assert_eq!(state.selected_index(), Some(1));
+}
+
+#[test]
+fn guarded_edits_wait_for_confirmation() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.set_requires_confirmation(TreeEditAction::Delete, true));
+    assert!(!state.set_requires_confirmation(TreeEditAction::Delete, true));
+    assert!(state.select_by_id(&model, &query, 4));
+    let delete = TreeAction::<()>::Edit(TreeEditAction::Delete);
+
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, delete),
+        TreeEvent::Changed
+    );
+    assert_eq!(
+        state.pending_confirmation(),
+        Some(TreeEditRequest::Delete { node: 4 })
+    );
+    assert_eq!(
+        state.confirm_pending::<()>(),
+        TreeEvent::Intent(TreeIntent::Edit(TreeEditRequest::Delete { node: 4 }))
+    );
+    assert_eq!(state.pending_confirmation(), None);
+
+    let _ = state.handle_action(&model, &query, &columns, delete);
+    assert_eq!(
+        state.handle_action(
+            &model,
+            &query,
+            &columns,
+            TreeAction::<()>::View(TreeViewAction::SelectNext)
+        ),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.pending_confirmation(), None);
+    assert_eq!(state.selected_id(), Some(4));
+
+    let rename = TreeAction::<()>::Edit(TreeEditAction::Rename);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, rename),
+        TreeEvent::Intent(TreeIntent::Edit(TreeEditRequest::Rename { node: 4 }))
+    );
+}
+
+#[test]
+fn counted_actions_repeat_movement_and_target_the_nth_branch() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_first());
+
+    let select_next = TreeAction::<()>::View(TreeViewAction::SelectNext);
+    let _ = state.handle_action_counted(&model, &query, &columns, select_next, 3);
+    assert_eq!(state.selected_id(), Some(2));
+
+    assert!(state.select_first());
+    let toggle = TreeAction::<()>::View(TreeViewAction::ToggleNode);
+    assert_eq!(
+        state.handle_action_counted(&model, &query, &columns, toggle, 2),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.selected_id(), Some(4));
+    assert!(!state.node_is_expanded(4, None));
+    assert_eq!(
+        state.handle_action_counted(&model, &query, &columns, toggle, 1),
+        TreeEvent::Unchanged
+    );
+    assert_eq!(state.selected_id(), Some(4));
+}
+
+#[test]
+fn counted_reorder_requests_carry_the_count() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+
+    assert!(state.select_id(Some(1)));
+    let reorder = TreeAction::<()>::Edit(TreeEditAction::ReorderDown);
+    assert_eq!(
+        state.handle_action_counted(&model, &query, &columns, reorder, 4),
+        TreeEvent::Intent(TreeIntent::Edit(TreeEditRequest::ReorderDown {
+            node: 1,
+            parent: 0,
+            count: 4,
+        }))
+    );
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, reorder),
+        TreeEvent::Intent(TreeIntent::Edit(TreeEditRequest::ReorderDown {
+            node: 1,
+            parent: 0,
+            count: 1,
+        }))
+    );
+}
+
+#[test]
+fn render_dirty_flag_tracks_view_changes() {
+    let mut model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.take_render_dirty());
+    assert!(!state.take_render_dirty());
+
+    assert!(state.select_next());
+    assert!(state.take_render_dirty());
+    assert!(state.set_marked(0, true));
+    assert!(state.take_render_dirty());
+    assert!(state.set_expanded(0, None, true));
+    assert!(state.take_render_dirty());
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.take_render_dirty());
+
+    model.remove(0, 2);
+    assert!(
+        !state.take_render_dirty(),
+        "model changes wait for synchronization"
+    );
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.take_render_dirty());
+}
+
+#[test]
+fn visible_nodes_expose_structure_for_custom_rendering() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+
+    let nodes = state.visible_nodes();
+    assert_eq!(
+        nodes.iter().map(|node| node.id()).collect::<Vec<_>>(),
+        [0, 1, 3, 2, 4, 5]
+    );
+    assert!(nodes[1].has_children());
+    assert!(!nodes[2].has_children());
+    let projection = state.projection();
+    assert_eq!(projection.tail_stack(0).as_deref(), Some(&[][..]));
+    assert_eq!(
+        projection.tail_stack(2).as_deref(),
+        Some(&[false, true][..])
+    );
+    assert_eq!(projection.tail_stack(3).as_deref(), Some(&[true][..]));
+    assert_eq!(projection.tail_stack(6), None);
+}
+
+#[test]
+fn edits_resolve_against_the_filtered_projection() {
+    let mut model = EditableTree(TestTree::forest());
+    let query = TreeQuery::new().with_filter(
+        |_: &EditableTree, id: usize| matches!(id, 2 | 3),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    let columns = TreeColumnSet::new([ColumnDef::tree("Name", ColumnWidth::fixed(12))])
+        .expect("one tree column");
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_last());
+    assert_eq!(state.selected_id(), Some(2));
+
+    let event = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::Edit(TreeEditAction::Delete),
+    );
+    assert_eq!(
+        event,
+        TreeEvent::Intent(TreeIntent::Edit(TreeEditRequest::Delete { node: 2 }))
+    );
+    let _ = state
+        .apply_edit(
+            &mut model,
+            &query,
+            TreeEditCommand::Delete {
+                nodes: smallvec![2],
+            },
+        )
+        .expect("valid delete");
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3]);
+    assert_eq!(state.selected_index(), Some(0));
+}
+
+#[test]
+fn moves_into_their_own_subtree_are_rejected_before_the_model() {
+    let mut model = EditableTree(TestTree::forest());
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let mut move_to = |nodes, parent| {
+        state.apply_edit(
+            &mut model,
+            &query,
+            TreeEditCommand::Move {
+                nodes,
+                parent,
+                position: TreeInsertPosition::Last,
+            },
+        )
+    };
+
+    assert_eq!(
+        move_to(smallvec![0], 3),
+        Err(TreeEditError::Cycle { node: 0, parent: 3 })
+    );
+    assert_eq!(
+        move_to(smallvec![4, 1], 1),
+        Err(TreeEditError::Cycle { node: 1, parent: 1 })
+    );
+    assert_eq!(
+        move_to(smallvec![2], 4),
+        Err(TreeEditError::Model("unsupported test command"))
+    );
+}
+
+#[test]
+fn structural_edit_errors_are_reported_distinctly() {
+    let mut model = EditableTree(TestTree::forest());
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+
+    assert_eq!(
+        state.apply_edit(
+            &mut model,
+            &query,
+            TreeEditCommand::Detach {
+                nodes: smallvec![4],
+            },
+        ),
+        Err(TreeEditError::RootProtected(4))
+    );
+    assert_eq!(
+        state.apply_edit(&mut model, &query, TreeEditCommand::Rename { node: 99 }),
+        Err(TreeEditError::InvalidTarget(99))
+    );
+    let error = state
+        .apply_edit(&mut model, &query, TreeEditCommand::Rename { node: 2 })
+        .expect_err("the test model refuses renames");
+    assert_eq!(error.to_string(), "unsupported test command");
+}
+
+#[test]
+fn edits_validate_moved_nodes_and_walk_shared_subtrees_once() {
+    let mut model = EditableTree(TestTree::forest());
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert_eq!(
+        state.apply_edit(
+            &mut model,
+            &query,
+            TreeEditCommand::Move {
+                nodes: smallvec![99],
+                parent: 4,
+                position: TreeInsertPosition::Last,
+            },
+        ),
+        Err(TreeEditError::InvalidTarget(99))
+    );
+
+    let last = 3 * 64;
+    let mut diamonds = EditableTree(TestTree::diamonds(64));
+    assert_eq!(
+        state.apply_edit(
+            &mut diamonds,
+            &query,
+            TreeEditCommand::Move {
+                nodes: smallvec![1],
+                parent: last,
+                position: TreeInsertPosition::Last,
+            },
+        ),
+        Err(TreeEditError::Cycle {
+            node: 1,
+            parent: last,
+        })
+    );
+    assert_eq!(
+        state.apply_edit(
+            &mut diamonds,
+            &query,
+            TreeEditCommand::Rename { node: last + 1 }
+        ),
+        Err(TreeEditError::InvalidTarget(last + 1))
+    );
+}
+
+struct FruitLabel;
+
+impl TreeLabelProvider<TestTree> for FruitLabel {
+    fn label_parts<'a>(&'a self, _model: &'a TestTree, id: usize) -> TreeLabelPrefix<'a> {
+        let names = [
+            "Apple",
+            "apricot",
+            "banana",
+            "avocado",
+            "blueberry",
+            "cherry",
+        ];
+        TreeLabelPrefix::borrowed(names[id])
+    }
+}
+
+#[test]
+fn type_ahead_jumps_to_the_next_visible_label_with_the_typed_prefix() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let start = Instant::now();
+    let type_at = |state: &mut TreeListViewState<usize>, character, millis| {
+        let now = start + Duration::from_millis(millis);
+        state.type_ahead(&model, &query, &FruitLabel, character, now)
+    };
+
+    assert!(type_at(&mut state, 'a', 0));
+    assert_eq!(state.selected_id(), Some(0));
+    assert!(!type_at(&mut state, 'P', 100));
+    assert!(type_at(&mut state, 'r', 200));
+    assert_eq!(state.selected_id(), Some(1));
+    assert_eq!(state.type_ahead_prefix(), Some("apr"));
+
+    assert!(type_at(&mut state, 'b', 2_000));
+    assert_eq!(state.selected_id(), Some(2));
+    assert!(type_at(&mut state, 'b', 2_100));
+    assert_eq!(state.selected_id(), Some(4));
+    assert!(type_at(&mut state, 'b', 2_200));
+    assert_eq!(state.selected_id(), Some(2));
+    assert!(!type_at(&mut state, 'z', 2_300));
+    assert_eq!(state.selected_id(), Some(2));
+
+    assert!(state.clear_type_ahead());
+    assert!(!state.clear_type_ahead());
+    assert_eq!(state.type_ahead_prefix(), None);
+}
+
+#[test]
+fn find_by_label_selects_the_first_displayed_match() {
+    let model = TestTree::forest();
+    let query =
+        TreeQuery::new().with_sort(NumericOrder { descending: true }, TreeRevision::INITIAL);
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+
+    let matches = state.select_first_matching(&model, &query, &NumberLabel, |name| {
+        name.ends_with(['3', '5'])
+    });
+    assert_eq!(matches, 2);
+    assert_eq!(state.selected_id(), Some(5), "4 and its child 5 sort first");
+
+    let filtered = query.clone().with_filter(
+        ExactMatch(3),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    assert_eq!(
+        state.select_first_matching(&model, &filtered, &NumberLabel, |name| {
+            name.ends_with(['3', '5'])
+        }),
+        1
+    );
+    assert_eq!(state.selected_id(), Some(3));
+
+    assert!(state.collapse_all());
+    assert_eq!(
+        state.select_first_matching(&model, &query, &NumberLabel, |name| name == "node 3"),
+        1,
+        "nodes inside collapsed branches are searched"
+    );
+    assert_eq!(state.selected_id(), Some(3));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [4, 0, 2, 1, 3]);
+    assert!(state.node_is_expanded(1, Some(0)));
+    assert!(!state.node_is_expanded(4, None));
+}
+
+#[test]
+fn label_paths_round_trip_through_loaded_children() {
+    let model = TestTree::forest();
+    let paths = TreePath::new("/");
+
+    let path = paths.path_of(&model, &NumberLabel, 3);
+    assert_eq!(path.as_deref(), Some("node 0/node 1/node 3"));
+    assert_eq!(
+        paths.resolve(&model, &NumberLabel, "node 0/node 1/node 3"),
+        Some(3)
+    );
+    assert_eq!(paths.resolve(&model, &NumberLabel, "node 4"), Some(4));
+    assert_eq!(paths.resolve(&model, &NumberLabel, "node 0/node 5"), None);
+    assert_eq!(paths.path_of(&model, &NumberLabel, 9), None);
+}
+
+#[test]
+fn label_paths_walk_shared_subtrees_once() {
+    let last = 3 * 64;
+    let model = TestTree::diamonds(64);
+    let paths = TreePath::new("/");
+
+    let path = paths
+        .path_of(&model, &NumberLabel, last)
+        .expect("the last hub is loaded");
+    assert!(path.starts_with("node 0/node 1/node 3/node 4/"), "{path}");
+    assert_eq!(path.split('/').count(), 2 * 64 + 1);
+    assert_eq!(paths.resolve(&model, &NumberLabel, &path), Some(last));
+
+    let mut state = TreeListViewState::new();
+    assert!(state.set_marks([last], true));
+    let snapshot = state.snapshot_paths(&model, &NumberLabel, paths);
+    assert_eq!(snapshot.manual_marked, [path]);
+}
+
+#[test]
+fn interned_paths_are_shared_and_rebased_after_renames() {
+    let mut paths = PathInterner::new();
+    let file = paths
+        .intern(["src", "state", "marks.rs"])
+        .expect("non-empty path");
+    let state = paths.get(&["src", "state"]).expect("interned prefix");
+    assert_eq!(paths.intern(["src", "state", "marks.rs"]), Some(file));
+    assert_eq!(paths.intern(Vec::<&str>::new()), None);
+    assert_eq!(paths.parent(file), Some(state));
+    assert_eq!(paths.segments(file), [&"src", &"state", &"marks.rs"]);
+    assert_eq!(paths.len(), 3);
+
+    let renamed = paths.rename(state, "view");
+    let moved = paths.rebase(file, state, renamed);
+    assert_eq!(paths.segments(moved), [&"src", &"view", &"marks.rs"]);
+    assert!(paths.starts_with(moved, renamed));
+    assert_eq!(paths.rebase(renamed, state, renamed), renamed);
+
+    let mut view = TreeListViewState::new();
+    let _ = view.set_marks([paths.get(&["src"]).expect("interned root"), file], true);
+    let snapshot = view
+        .snapshot()
+        .map_ids(|id| Some(paths.rebase(id, state, renamed)));
+    assert!(snapshot.manual_marked.contains(&moved));
+    assert!(!snapshot.manual_marked.contains(&file));
+    assert_eq!(snapshot.manual_marked.len(), 2);
+}
+
+#[test]
+fn model_revisions_rebuild_at_most_once_per_interval() {
+    let mut model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let start = Instant::now();
+    let _ = state.set_min_rebuild_interval(Duration::from_millis(100));
+    let _ = state.expand_all(&model);
+    state.tick(start);
+    assert!(state.ensure_projection(&model, &query));
+
+    model.remove(0, 2);
+    state.tick(start + Duration::from_millis(10));
+    assert!(!state.ensure_projection(&model, &query));
+    assert!(
+        state.visible_ids().any(|id| id == 2),
+        "the last view is kept"
+    );
+
+    assert!(state.collapse_all());
+    assert!(
+        state.ensure_projection(&model, &query),
+        "expansion changes are not throttled"
+    );
+    let _ = state.expand_all(&model);
+    assert!(state.ensure_projection(&model, &query));
+    model.remove(0, 1);
+    state.tick(start + Duration::from_millis(50));
+    assert!(!state.ensure_projection(&model, &query));
+    state.tick(start + Duration::from_millis(200));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 4, 5]);
+}
+
+#[test]
+fn flashes_expire_after_their_ticks() {
+    let mut state = TreeListViewState::<usize>::new();
+    let start = Instant::now();
+    let _ = state.take_render_dirty();
+    assert!(state.flash(3, 2));
+    assert!(state.flash(4, 1));
+    assert!(state.take_render_dirty());
+    assert_eq!(state.flash_remaining(3), Some(2));
+
+    assert!(state.tick(start), "the flash on 4 expires");
+    assert_eq!(state.flash_remaining(3), Some(1));
+    assert_eq!(state.flash_remaining(4), None);
+    assert!(state.take_render_dirty());
+
+    assert!(state.flash(3, 5), "flashing again restarts the countdown");
+    assert!(!state.tick(start));
+    assert_eq!(state.flash_remaining(3), Some(4));
+    assert!(state.flash(3, 0));
+    assert!(!state.clear_flashes());
+}
+
+#[test]
+fn views_share_expansion_and_marks_but_not_selection() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut shared = TreeSharedState::new();
+    let mut overview = TreeListViewState::new();
+    let mut detail = TreeListViewState::new();
+
+    let _ = overview.expand_all(&model);
+    assert!(!overview.sync_shared(&mut shared));
+    assert!(detail.sync_shared(&mut shared));
+    assert!(detail.node_is_expanded(1, Some(0)));
+
+    assert!(detail.set_marked(3, true));
+    assert!(detail.select_by_id(&model, &query, 3));
+    assert!(!detail.sync_shared(&mut shared));
+    assert!(overview.sync_shared(&mut shared));
+    assert!(overview.is_manually_marked(3));
+    assert_eq!(overview.selected_id(), None);
+    assert!(!overview.sync_shared(&mut shared));
+}
+
+#[test]
+fn filter_results_can_be_marked_in_one_call() {
+    let model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.mark_all_filtered(&model, &|_: &TestTree, id: usize| id != 3, true));
+    assert_eq!(
+        {
+            let mut marked = state.manual_marked_ids().collect::<Vec<_>>();
+            marked.sort_unstable();
+            marked
+        },
+        [2, 5]
+    );
+    assert!(state.mark_all_filtered(&model, &matches_two_or_three, false));
+    assert!(state.is_manually_marked(3));
+    assert!(!state.is_manually_marked(1));
+}
+
+#[test]
+fn collapse_to_selection_keeps_only_the_ancestor_chain() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 3));
+
+    assert_eq!(
+        state.handle_action(
+            &model,
+            &query,
+            &columns(),
+            TreeAction::<()>::View(TreeViewAction::CollapseToSelection),
+        ),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4]);
+    assert_eq!(state.selected_id(), Some(3));
+    assert!(!state.node_is_expanded(4, None));
+}
+
+#[test]
+fn collapse_to_selection_reports_unchanged_expansion() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(!state.collapse_to_selection());
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 3));
+
+    assert!(state.collapse_to_selection());
+    assert!(state.ensure_projection(&model, &query));
+    assert!(!state.collapse_to_selection());
+}
+
+#[test]
+fn cancelled_rebuilds_keep_the_previous_projection() {
+    let mut children = vec![(1..5000).collect::<Vec<usize>>()];
+    children.resize_with(5000, Vec::new);
+    let mut revision = TreeRevision::INITIAL;
+    let model = IndexedTree::new([0], &children, revision).expect("flat tree is valid");
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_first());
+
+    let _ = state.set_expanded(0, None, true);
+    let polls = std::cell::Cell::new(0);
+    let cancel = || {
+        polls.set(polls.get() + 1);
+        true
+    };
+    assert!(!state.ensure_projection_cancellable(&model, &query, &cancel));
+    assert_eq!(polls.get(), 1);
+    assert_eq!(state.projection().len(), 1);
+    assert_eq!(state.selected_id(), Some(0));
+
+    revision.advance();
+    let model = IndexedTree::new([0], &children, revision).expect("flat tree is valid");
+    assert!(state.ensure_projection_cancellable(&model, &query, &|| false));
+    assert_eq!(state.projection().len(), 5000);
+}
+
+#[test]
+fn table_states_move_in_and_out_of_the_view_state() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let table = TableState::new()
+        .with_offset(1)
+        .with_selected(Some(2))
+        .with_selected_column(Some(1));
+    let mut state = TreeListViewState::from_table_state(table);
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.selected_id(), Some(3));
+    assert_eq!(state.selected_column(), Some(1));
+
+    let (table, snapshot) = state.into_parts();
+    assert_eq!(table.offset(), 1);
+    assert_eq!(table.selected(), Some(2));
+    assert_eq!(table.selected_column(), Some(1));
+    assert_eq!(snapshot.selected, Some(3));
+}
+
+#[test]
+fn path_snapshots_restore_by_label_instead_of_id() {
+    let model = TestTree::forest();
+    let paths = TreePath::new("/");
+    let state = TreeListViewState::from_snapshot(TreeListViewSnapshot {
+        expanded: vec![(None, 0), (Some(0), 1), (None, 9)],
+        manual_marked: vec![3, 9],
+        selected: Some(3),
+        selected_column: None,
+        offset: 2,
+        horizontal_offset: 0,
+        draw_lines: true,
+        column_widths: Vec::new(),
+        cell_flags: Vec::new(),
+    });
+
+    let snapshot = state.snapshot_paths(&model, &NumberLabel, paths);
+    assert_eq!(
+        snapshot.expanded,
+        [
+            (None, "node 0".to_owned()),
+            (Some("node 0".to_owned()), "node 0/node 1".to_owned()),
+        ]
+    );
+    assert_eq!(snapshot.manual_marked, ["node 0/node 1/node 3"]);
+    assert_eq!(snapshot.selected.as_deref(), Some("node 0/node 1/node 3"));
+
+    let mut restored = TreeListViewState::new();
+    restored.restore_paths(&model, &NumberLabel, paths, snapshot);
+    assert!(restored.node_is_expanded(1, Some(0)));
+    assert_eq!(restored.selected_id(), Some(3));
+    assert_eq!(restored.snapshot().manual_marked, [3]);
+    assert_eq!(restored.snapshot().offset, 2);
+}
+
+struct ModuloLabel;
+
+impl TreeLabelProvider<TestTree> for ModuloLabel {
+    fn label_parts<'a>(&'a self, _model: &'a TestTree, id: usize) -> TreeLabelPrefix<'a> {
+        TreeLabelPrefix {
+            name: Cow::Owned(format!("node {}", id % 6)),
+            prefix: None,
+        }
+    }
+}
+
+#[test]
+fn rebuilds_carry_state_to_regenerated_ids_by_parent_and_label() {
+    let old = TestTree::forest();
+    let mut children = vec![Children::Leaf; 6];
+    children.extend([
+        Children::Loaded(vec![7, 8]),
+        Children::Leaf,
+        Children::Leaf,
+        Children::Leaf,
+        Children::Loaded(vec![11]),
+        Children::Leaf,
+    ]);
+    let new = TestTree {
+        roots: vec![6, 10],
+        children,
+        revision: TreeRevision::INITIAL,
+    };
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&old);
+    let _ = state.set_marks([3, 5], true);
+    assert!(state.select_by_id(&old, &TreeQuery::new(), 3));
+
+    let report = state.rebuild_preserving(&old, &new, &ModuloLabel);
+    assert_eq!(report.remapped, 4);
+    assert_eq!(report.dropped, 2);
+    assert!(state.node_is_expanded(6, None));
+    assert!(state.node_is_expanded(7, Some(6)));
+    assert!(state.node_is_expanded(10, None));
+    assert_eq!(state.snapshot().manual_marked, [11]);
+    assert_eq!(state.selected_id(), None);
+}
+
+#[test]
+fn reveal_marked_expands_the_paths_to_restored_marks() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(!state.reveal_marked(&model));
+    assert!(state.set_marks([3, 4], true));
+    assert!(state.reveal_marked(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4]);
+    assert!(!state.reveal_marked(&model));
+
+    assert!(state.set_marked(5, true));
+    assert!(state.reveal_marked(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4, 5]);
+}
+
+#[test]
+fn expand_all_under_a_filter_opens_only_the_found_paths_unless_scoped_to_the_model() {
+    let model = TestTree::forest();
+    let columns = columns();
+    let expand_all = TreeAction::<()>::View(TreeViewAction::ExpandAll);
+
+    let filtered = TreeQuery::new().with_filter(
+        ExactMatch(3),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    let _ = state.handle_action(&model, &filtered, &columns, expand_all);
+    assert!(state.node_is_expanded(0, None));
+    assert!(state.node_is_expanded(1, Some(0)));
+    assert!(!state.node_is_expanded(4, None));
+
+    let whole = TreeQuery::new().with_filter(
+        ExactMatch(3),
+        TreeFilterConfig::enabled().with_expand_all(TreeExpandAllScope::Model),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    let _ = state.handle_action(&model, &whole, &columns, expand_all);
+    assert!(state.node_is_expanded(4, None));
+    assert_eq!(
+        TreeFilterConfig::Disabled.with_expand_all(TreeExpandAllScope::Filtered),
+        TreeFilterConfig::Disabled
+    );
+}
+
+#[test]
+fn retain_state_prunes_expansions_and_marks_by_predicate() {
+    let model = TestTree::forest();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.set_marks([2, 3, 5], true));
+
+    assert!(state.retain_state(|id| id != 1 && id != 5));
+    assert!(state.node_is_expanded(0, None));
+    assert!(!state.node_is_expanded(1, Some(0)));
+    assert!(state.node_is_expanded(4, None));
+    assert_eq!(
+        {
+            let mut marks = state.manual_marked_ids().collect::<Vec<_>>();
+            marks.sort_unstable();
+            marks
+        },
+        [2, 3]
+    );
+    assert!(!state.retain_state(|id| id != 1 && id != 5));
+}
+
+#[test]
+fn review_mode_steps_between_marked_or_matching_rows() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.set_marks([2, 5], true));
+    assert!(state.select_first());
+
+    let next = TreeAction::<()>::View(TreeViewAction::SelectNext);
+    let prev = TreeAction::<()>::View(TreeViewAction::SelectPrev);
+    let toggle = TreeAction::<()>::View(TreeViewAction::ToggleReview);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, toggle),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.review(), TreeReview::Marked);
+    let _ = state.handle_action(&model, &query, &columns, next);
+    assert_eq!(state.selected_id(), Some(2));
+    let _ = state.handle_action(&model, &query, &columns, next);
+    assert_eq!(state.selected_id(), Some(4));
+    let _ = state.handle_action(&model, &query, &columns, prev);
+    assert_eq!(state.selected_id(), Some(2));
+    let _ = state.handle_action_counted(&model, &query, &columns, next, 5);
+    assert_eq!(state.selected_id(), Some(5));
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, next),
+        TreeEvent::Unchanged
+    );
+
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+    assert_eq!(state.review(), TreeReview::Disabled);
+    let _ = state.handle_action(&model, &query, &columns, prev);
+    assert_eq!(state.selected_id(), Some(4));
+
+    let filtered = TreeQuery::new().with_filter(
+        matches_two_or_three,
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    assert!(state.set_review(TreeReview::Matched));
+    assert!(state.ensure_projection(&model, &filtered));
+    assert!(state.select_first());
+    let _ = state.handle_action(&model, &filtered, &columns, next);
+    assert_eq!(state.selected_id(), Some(3));
+    let _ = state.handle_action(&model, &filtered, &columns, next);
+    assert_eq!(state.selected_id(), Some(2));
+    assert!(state.set_review(TreeReview::Disabled));
+    let _ = state.handle_action(&model, &filtered, &columns, toggle);
+    assert_eq!(state.review(), TreeReview::Matched);
+}
+
+#[test]
+fn set_anchor_pins_a_baseline_row_and_reports_the_distance_to_it() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    let set_anchor = TreeAction::<()>::View(TreeViewAction::SetAnchor);
+    assert_eq!(state.anchor_distance(), None);
+
+    assert!(state.select_by_id(&model, &query, 1));
+    let _ = state.handle_action(&model, &query, &columns, set_anchor);
+    assert_eq!(state.anchor(), Some(1));
+    assert_eq!(state.anchor_distance(), Some(0));
+    assert!(state.select_by_id(&model, &query, 5));
+    assert_eq!(state.anchor_distance(), Some(4));
+    assert!(state.select_by_id(&model, &query, 0));
+    assert_eq!(state.anchor_distance(), Some(-1));
+
+    assert!(state.set_expanded(0, None, false));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.anchor_distance(), None);
+    assert!(state.retain_state(|id| id != 1));
+    assert_eq!(state.anchor(), None);
+
+    assert!(state.set_anchor(Some(0)));
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, set_anchor),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.anchor(), None);
+}
+
+#[test]
+fn multi_selection_extends_from_its_anchor_without_touching_marks() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &query, 1));
+    let sorted = |state: &TreeListViewState<usize>| {
+        let mut ids = state.selection_ids().collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    };
+    assert_eq!(sorted(&state), [1]);
+
+    let down = TreeAction::<()>::View(TreeViewAction::ExtendSelectionDown);
+    let _ = state.handle_action(&model, &query, &columns, down);
+    let _ = state.handle_action(&model, &query, &columns, down);
+    assert_eq!(state.selected_id(), Some(2));
+    assert_eq!(state.selection_anchor(), Some(1));
+    assert_eq!(sorted(&state), [1, 2, 3]);
+    assert_eq!(state.mark_state(0), TreeMarkState::Unmarked);
+
+    assert!(state.toggle_in_selection(5));
+    assert!(state.is_in_selection(5));
+    let up = TreeAction::<()>::View(TreeViewAction::ExtendSelectionUp);
+    let _ = state.handle_action(&model, &query, &columns, up);
+    assert_eq!(state.selected_id(), Some(3));
+    assert_eq!(sorted(&state), [2, 3, 4, 5]);
+
+    assert!(state.retain_state(|id| id != 4));
+    assert_eq!(sorted(&state), [2, 3, 5]);
+    assert!(state.clear_selection_set());
+    assert_eq!(sorted(&state), [3]);
+    assert!(!state.select_range(1, 42));
+}
+
+#[test]
+fn toggle_mark_flags_the_selected_data_cell_when_cell_flagging_is_enabled() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+        ColumnDef::data_owned("Target", ColumnWidth::fixed(3), |_: &TestTree, _, _| {
+            ratatui::widgets::Cell::from("")
+        }),
+    ])
+    .expect("one tree column");
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.select_by_id(&model, &query, 2));
+    let toggle = TreeAction::<()>::View(TreeViewAction::ToggleMark);
+    let right = TreeAction::<()>::View(TreeViewAction::SelectColumnRight);
+    let left = TreeAction::<()>::View(TreeViewAction::SelectColumnLeft);
+
+    let _ = state.handle_action(&model, &query, &columns, right);
+    let _ = state.handle_action(&model, &query, &columns, right);
+    assert_eq!(state.selected_column(), Some(1));
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+    assert!(state.is_manually_marked(2));
+    assert!(!state.is_cell_flagged(2, 1));
+    assert!(state.toggle_marked(2));
+
+    assert!(state.set_cell_flagging(TreeCellFlagging::Enabled));
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, toggle),
+        TreeEvent::Changed
+    );
+    assert!(state.is_cell_flagged(2, 1));
+    assert!(!state.is_manually_marked(2));
+    assert!(state.set_cell_flag(4, 1, true));
+    assert_eq!(state.snapshot().cell_flags.len(), 2);
+
+    assert!(state.retain_state(|id| id != 4));
+    assert_eq!(state.cell_flags().collect::<Vec<_>>(), [(2, 1)]);
+    let restored = TreeListViewState::from_snapshot(state.snapshot());
+    assert!(restored.is_cell_flagged(2, 1));
+
+    let _ = state.handle_action(&model, &query, &columns, left);
+    assert_eq!(state.selected_column(), Some(0));
+    let _ = state.handle_action(&model, &query, &columns, toggle);
+    assert!(state.is_manually_marked(2));
+    assert!(state.clear_cell_flags());
+    assert!(!state.is_cell_flagged(2, 1));
+}
+
+#[cfg(feature = "keys")]
+#[test]
+fn unbound_keys_fall_through_to_the_application() {
+    use tui_treelistview::{TreeKey, TreeKeyCode, TreeKeyDispatch, TreeKeyModifiers};
+
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    let quit = TreeKey::new(TreeKeyCode::Char('q'), TreeKeyModifiers::NONE);
+    assert_eq!(
+        state.dispatch_key(&model, &query, &columns, quit),
+        TreeKeyDispatch::Unhandled(quit)
+    );
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, quit),
+        TreeEvent::Unchanged
+    );
+    assert_eq!(
+        state.dispatch_key(&model, &query, &columns, TreeKeyCode::Char('3')),
+        TreeKeyDispatch::Handled(TreeEvent::Changed)
+    );
+    assert_eq!(
+        state.dispatch_key(&model, &query, &columns, TreeKeyCode::Esc),
+        TreeKeyDispatch::Handled(TreeEvent::Changed)
+    );
+    assert_eq!(
+        state.dispatch_key(&model, &query, &columns, TreeKeyCode::Esc),
+        TreeKeyDispatch::Unhandled(TreeKeyCode::Esc.into())
+    );
+    assert_eq!(
+        state.dispatch_key(&model, &query, &columns, TreeKeyCode::Down),
+        TreeKeyDispatch::Handled(TreeEvent::Changed)
+    );
+}
+
+#[cfg(feature = "keys")]
+#[test]
+fn vim_chords_run_once_their_last_key_arrives() {
+    use tui_treelistview::{KeymapProfile, TreeKey, TreeKeyCode, TreeKeyModifiers};
+
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    state.keymap_mut().set_profile(KeymapProfile::Vim);
+    state.keymap_mut().bind_vim_chords();
+    assert!(state.ensure_projection(&model, &query));
+    let press = |character| TreeKey::new(TreeKeyCode::Char(character), TreeKeyModifiers::NONE);
+
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, press('z')),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.pending_chord(), [press('z')]);
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, press('R')),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.visible_ids().count(), 6);
+
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, TreeKeyCode::End),
+        TreeEvent::Changed
+    );
+    for character in ['g', 'g'] {
+        assert_eq!(
+            state.handle_key(&model, &query, &columns, press(character)),
+            TreeEvent::Changed
+        );
+    }
+    assert_eq!(state.selected_id(), Some(0));
+
+    state.take_render_dirty();
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, press('g')),
+        TreeEvent::Changed
+    );
+    assert!(state.take_render_dirty());
+    assert_eq!(
+        state.handle_key(&model, &query, &columns, press('q')),
+        TreeEvent::Changed,
+        "the unbound key discarded the pending chord"
+    );
+    assert!(state.pending_chord().is_empty());
+}
+
+#[test]
+fn batched_actions_report_one_event_each_and_sync_once_at_the_end() {
+    let model = TestTree::forest();
+    let query = TreeQuery::new();
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    let generation = state.projection().generation();
+
+    let events = state.apply_actions(
+        &model,
+        &query,
+        &columns,
+        [
+            TreeAction::View(TreeViewAction::ExpandAll),
+            TreeAction::View(TreeViewAction::SelectFirst),
+            TreeAction::View(TreeViewAction::SelectNext),
+            TreeAction::View(TreeViewAction::SelectNext),
+            TreeAction::View(TreeViewAction::ToggleMark),
+            TreeAction::View(TreeViewAction::CollapseAll),
+            TreeAction::Custom(7),
+        ],
+    );
+    assert_eq!(
+        events,
+        [
+            TreeEvent::Changed,
+            TreeEvent::Changed,
+            TreeEvent::Changed,
+            TreeEvent::Changed,
+            TreeEvent::Changed,
+            TreeEvent::Changed,
+            TreeEvent::Intent(TreeIntent::Custom(7)),
+        ]
+    );
+    assert!(state.is_manually_marked(3));
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 4]);
+    assert_eq!(state.selected_id(), Some(0));
+    assert_eq!(
+        state.projection().generation(),
+        generation.next().next(),
+        "SelectFirst rebuilds after ExpandAll, and the end of the batch after CollapseAll"
+    );
+}
+
+#[test]
+fn placeholders_stand_in_for_empty_and_filtered_out_children() {
+    let mut model = TestTree::forest();
+    model.children[2] = Children::Loaded(Vec::new());
+    let query = TreeQuery::new().with_placeholders(TreePlaceholders::Visible);
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.set_expanded(2, Some(0), true));
+    assert!(state.ensure_projection(&model, &query));
+
+    let placeholder = state.projection().nodes()[4];
+    assert_eq!(placeholder.placeholder(), Some(TreePlaceholder::Empty));
+    assert_eq!((placeholder.id(), placeholder.level()), (2, 2));
+    assert_eq!(
+        state.projection().nodes()[3].expansion(),
+        TreeExpansionState::Expanded
+    );
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1, 3, 2, 4, 5]);
+
+    let filtered = query.with_filter(
+        ExactMatch(4),
+        TreeFilterConfig::enabled_manual_expand(),
+        TreeRevision::INITIAL,
+    );
+    assert!(state.ensure_projection(&model, &filtered));
+    let placeholders = state
+        .projection()
+        .nodes()
+        .iter()
+        .filter_map(|node| node.placeholder())
+        .collect::<Vec<_>>();
+    assert_eq!(placeholders, [TreePlaceholder::NoMatches]);
+
+    assert!(state.ensure_projection(&model, &TreeQuery::new()));
+    assert_eq!(state.projection().len(), 6);
+}
+
+#[test]
+fn selection_steps_over_placeholder_rows() {
+    let mut model = TestTree::forest();
+    model.children[2] = Children::Loaded(Vec::new());
+    let query = TreeQuery::new().with_placeholders(TreePlaceholders::Visible);
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.set_expanded(2, Some(0), true));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.projection().nodes()[4].placeholder().is_some());
+
+    assert!(state.select_index(Some(3)));
+    assert!(state.select_next());
+    assert_eq!(
+        (state.selected_index(), state.selected_id()),
+        (Some(5), Some(4))
+    );
+    assert!(state.select_prev());
+    assert_eq!(
+        (state.selected_index(), state.selected_id()),
+        (Some(3), Some(2))
+    );
+    assert!(state.select_index(Some(4)));
+    assert_eq!(state.selected_index(), Some(5));
+}
+
+#[test]
+fn review_mode_steps_over_placeholders_of_reviewed_nodes() {
+    let mut model = TestTree::forest();
+    model.children[2] = Children::Loaded(Vec::new());
+    let query = TreeQuery::new().with_placeholders(TreePlaceholders::Visible);
+    let columns = columns();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.set_expanded(2, Some(0), true));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.set_marks([2], true));
+    assert!(state.set_review(TreeReview::Marked));
+    assert!(state.select_first());
+
+    let next = TreeAction::<()>::View(TreeViewAction::SelectNext);
+    let _ = state.handle_action(&model, &query, &columns, next);
+    assert_eq!(state.selected_index(), Some(3));
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, next),
+        TreeEvent::Unchanged
+    );
+    assert_eq!(state.selected_index(), Some(3));
+}
+
+#[test]
+fn action_names_round_trip() {
+    for action in TreeViewAction::ALL {
+        assert_eq!(TreeViewAction::from_name(action.name()), Some(action));
+        assert_eq!(
+            TreeAction::<()>::from_name(action.name()),
+            Some(TreeAction::View(action))
+        );
+    }
+    for action in TreeEditAction::ALL {
+        assert_eq!(TreeEditAction::from_name(action.name()), Some(action));
+        assert_eq!(
+            TreeAction::<()>::from_name(action.name()),
+            Some(TreeAction::Edit(action))
+        );
+    }
+    assert_eq!(TreeAction::<()>::from_name("select_nothing"), None);
+}
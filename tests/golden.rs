@@ -0,0 +1,163 @@
+//! Golden renderings of representative trees, compared with the files in `tests/snapshots`.
+//!
+//! After an intended rendering change, regenerate them with `INSTA_UPDATE=always cargo test` or
+//! `cargo insta review` and commit the updated files.
+
+use std::borrow::Cow;
+
+use insta::assert_snapshot;
+use ratatui::widgets::Cell;
+use tui_treelistview::{
+    ColumnDef, ColumnWidth, TextFilter, TreeColumnSet, TreeFilterConfig, TreeInsertPosition,
+    TreeLabelPrefix, TreeLabelProvider, TreeListView, TreeListViewState, TreeListViewStyle,
+    TreeMarkState, TreeModel, TreeQuery, TreeRevision, TreeRowContext, VecTreeModel,
+};
+
+type Model = VecTreeModel<String>;
+
+struct Label;
+
+impl TreeLabelProvider<Model> for Label {
+    fn label_parts<'a>(&'a self, model: &'a Model, id: usize) -> TreeLabelPrefix<'a> {
+        TreeLabelPrefix::borrowed(model.get(id).map_or("", String::as_str))
+    }
+}
+
+fn add(model: &mut Model, parent: Option<usize>, name: &str) -> usize {
+    model
+        .insert(parent, TreeInsertPosition::Last, name.to_owned())
+        .expect("parent exists")
+}
+
+/// Two roots with branches nested five levels deep.
+fn deep() -> Model {
+    let mut model = Model::new();
+    let projects = add(&mut model, None, "projects");
+    let mut parent = projects;
+    for name in ["widgets", "src", "layout", "table", "cells"] {
+        add(&mut model, Some(parent), &format!("{name}.md"));
+        parent = add(&mut model, Some(parent), name);
+    }
+    add(&mut model, Some(parent), "span.rs");
+    add(&mut model, Some(projects), "archive");
+    let notes = add(&mut model, None, "notes");
+    add(&mut model, Some(notes), "today");
+    model
+}
+
+fn name_column() -> TreeColumnSet<'static, Model> {
+    TreeColumnSet::new([ColumnDef::tree("Name", ColumnWidth::fixed(32))
+        .with_text(|model: &Model, id| Cow::Borrowed(model.get(id).map_or("", String::as_str)))])
+    .expect("one tree column")
+}
+
+fn render<F, C>(
+    model: &Model,
+    query: &TreeQuery<F>,
+    columns: &C,
+    state: &mut TreeListViewState<usize>,
+    size: (u16, u16),
+) -> String
+where
+    F: tui_treelistview::TreeFilter<Model>,
+    C: tui_treelistview::TreeColumns<Model>,
+{
+    TreeListView::new(model, query, &Label, columns, TreeListViewStyle::default())
+        .render_to_string(size.0, size.1, state)
+}
+
+#[test]
+fn deep_tree() {
+    let model = deep();
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_index(Some(11)));
+    assert_snapshot!(render(&model, &query, &name_column(), &mut state, (40, 18)));
+}
+
+#[test]
+fn wide_columns() {
+    let model = deep();
+    let query = TreeQuery::new();
+    let data = |header: &'static str, value: fn(usize) -> String| {
+        ColumnDef::data_owned(
+            header,
+            ColumnWidth::fixed(8),
+            move |_: &Model, id: usize, _: &TreeRowContext<'_>| Cell::from(value(id)),
+        )
+    };
+    let columns = TreeColumnSet::new([
+        ColumnDef::tree("Name", ColumnWidth::fixed(16))
+            .with_text(|model: &Model, id| Cow::Borrowed(model.get(id).map_or("", String::as_str))),
+        data("Id", |id| id.to_string()),
+        data("Size", |id| format!("{} KiB", id * 3)),
+        data("Owner", |id| ["ana", "bo", "cy"][id % 3].to_owned()),
+        data("Mode", |id| {
+            if id % 2 == 0 { "rw-" } else { "r--" }.to_owned()
+        }),
+    ])
+    .expect("one tree column");
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert!(state.select_index(Some(2)));
+    assert_snapshot!(render(&model, &query, &columns, &mut state, (44, 9)));
+}
+
+#[test]
+fn filtered_tree() {
+    let model = deep();
+    let query = TreeQuery::new().with_filter(
+        TextFilter::new(Label, "ta"),
+        TreeFilterConfig::enabled(),
+        TreeRevision::INITIAL,
+    );
+    let mut state = TreeListViewState::new();
+    assert!(state.ensure_projection(&model, &query));
+    assert_snapshot!(render(&model, &query, &name_column(), &mut state, (40, 10)));
+}
+
+#[test]
+fn marked_tree() {
+    let model = deep();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::data_owned(
+            "Mark",
+            ColumnWidth::fixed(4),
+            |_: &Model, _: usize, context: &TreeRowContext<'_>| {
+                Cell::from(match context.node.mark {
+                    TreeMarkState::Unmarked => "[ ]",
+                    TreeMarkState::Partial => "[~]",
+                    TreeMarkState::Marked => "[x]",
+                })
+            },
+        ),
+        ColumnDef::tree("Name", ColumnWidth::fixed(32))
+            .with_text(|model: &Model, id| Cow::Borrowed(model.get(id).map_or("", String::as_str))),
+    ])
+    .expect("one tree column");
+    let mut state = TreeListViewState::new();
+    assert!(state.set_marks([4, 14], true));
+    assert!(state.reveal_marked(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert_snapshot!(render(&model, &query, &columns, &mut state, (40, 12)));
+}
+
+#[test]
+fn virtualized_rows() {
+    let mut model = Model::new();
+    let root = add(&mut model, None, "log");
+    for index in 0..10_000 {
+        add(&mut model, Some(root), &format!("entry {index:05}"));
+    }
+    let query = TreeQuery::new();
+    let mut state = TreeListViewState::new();
+    assert!(state.expand_all(&model));
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.visible_len(), model.size_hint());
+    assert!(state.select_index(Some(5_000)));
+    assert_snapshot!(render(&model, &query, &name_column(), &mut state, (32, 8)));
+}
@@ -1,15 +1,28 @@
+use std::borrow::Cow;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Position, Rect};
-use ratatui::widgets::{Cell, StatefulWidget};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Borders, Cell, StatefulWidget, Widget};
 use tui_treelistview::{
-    ColumnDef, ColumnWidth, TreeChildren, TreeColumnSet, TreeHit, TreeHorizontalScroll,
-    TreeLabelPrefix, TreeLabelProvider, TreeListView, TreeListViewState, TreeListViewStyle,
-    TreeModel, TreeQuery, TreeRevision, TreeRowContext, TreeRowRendering,
+    ColumnDef, ColumnWidth, TreeAction, TreeCellFlagging, TreeCellOverflow, TreeChildren,
+    TreeClickKind, TreeColumnSet, TreeColumnsView, TreeDetailProvider, TreeEditAction, TreeEvent,
+    TreeFilterConfig, TreeFollowTail, TreeGlyphs, TreeHit, TreeHitPart, TreeHorizontalScroll,
+    TreeLabelPrefix, TreeLabelProvider, TreeLabelRenderer, TreeLabelScrollbar, TreeLegendEntry,
+    TreeListView, TreeListViewState, TreeListViewStyle, TreeMeasure, TreeModel, TreeModelCheck,
+    TreeOverlayRows, TreeOverlaySelection, TreeOverscroll, TreePlaceholders, TreePositionIndicator,
+    TreeQuery, TreeRevision, TreeRowContext, TreeRowNumbers, TreeRowRendering, TreeScrollPolicy,
+    TreeSearchBar, TreeSelectionFallback, TreeTheme, TreeViewAction, tree_buffer_text,
 };
 
 struct Model {
     children: Vec<Vec<usize>>,
     names: Vec<String>,
+    unguided: Vec<usize>,
+    fills: Vec<(usize, f32)>,
+    diagnostics: Vec<(usize, &'static str)>,
+    revision: TreeRevision,
 }
 
 impl Model {
@@ -19,8 +32,20 @@ impl Model {
             names: ["root", "alpha", "beta", "gamma", "delta", "epsilon"]
                 .map(str::to_owned)
                 .into(),
+            unguided: Vec::new(),
+            fills: Vec::new(),
+            diagnostics: Vec::new(),
+            revision: TreeRevision::INITIAL,
         }
     }
+
+    fn append(&mut self, name: &str) {
+        let id = self.children.len();
+        self.children[0].push(id);
+        self.children.push(vec![]);
+        self.names.push(name.to_owned());
+        self.revision.advance();
+    }
 }
 
 impl TreeModel for Model {
@@ -35,12 +60,47 @@ impl TreeModel for Model {
     }
 
     fn revision(&self) -> TreeRevision {
-        TreeRevision::INITIAL
+        self.revision
     }
 
     fn size_hint(&self) -> usize {
         self.children.len()
     }
+
+    fn contains(&self, id: Self::Id) -> bool {
+        id < self.names.len()
+    }
+
+    fn draws_guides(&self, id: Self::Id) -> bool {
+        !self.unguided.contains(&id)
+    }
+
+    fn fill_fraction(&self, id: Self::Id) -> Option<f32> {
+        self.fills
+            .iter()
+            .find_map(|(filled, fraction)| (*filled == id).then_some(*fraction))
+    }
+
+    fn overlay_rows(&self, id: Self::Id) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|(node, _)| *node == id)
+            .count()
+    }
+}
+
+struct Diagnostics;
+
+impl TreeOverlayRows<Model> for Diagnostics {
+    fn overlay_row<'a>(&'a self, model: &'a Model, id: usize, index: usize) -> Line<'a> {
+        let (_, message) = model
+            .diagnostics
+            .iter()
+            .filter(|(node, _)| *node == id)
+            .nth(index)
+            .expect("counted by overlay_rows");
+        Line::from(format!("! {message}"))
+    }
 }
 
 struct Label;
@@ -61,7 +121,8 @@ fn columns(show_header: bool) -> TreeColumnSet<'static, Model> {
                 Cell::from(format!("{}{separator}{}", context.level, model.names[id]))
             },
         ),
-        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+        ColumnDef::tree("Name", ColumnWidth::fixed(12))
+            .with_text(|model: &Model, id| Cow::Borrowed(model.names[id].as_str())),
     ])
     .expect("one tree column");
     if show_header {
@@ -96,6 +157,259 @@ fn rendered(rendering: TreeRowRendering, offset: usize) -> Buffer {
     buffer
 }
 
+#[test]
+fn children_changed_without_a_revision_render_the_last_projection() {
+    let mut model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 5));
+    let area = Rect::new(0, 0, 30, 8);
+    let render = |model: &Model, state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+        let mut spectator = Buffer::empty(area);
+        TreeListView::new(
+            model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render_spectator(area, &mut spectator, state);
+        buffer
+    };
+    let before = render(&model, &mut state);
+
+    model.children[0].truncate(1);
+    assert_eq!(render(&model, &mut state), before);
+    assert_eq!(state.projection().len(), 6);
+
+    model.revision.advance();
+    let _ = render(&model, &mut state);
+    assert_eq!(state.visible_ids().collect::<Vec<_>>(), [0, 1]);
+    assert_eq!(state.selected_id(), Some(0));
+}
+
+#[test]
+fn verified_rendering_replaces_missing_nodes_with_error_rows() {
+    let mut model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 30, 6);
+    let render = |model: &Model, state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        let style = TreeListViewStyle {
+            model_check: TreeModelCheck::Verify,
+            error_style: Style::new().fg(Color::Red),
+            ..TreeListViewStyle::borderless()
+        };
+        TreeListView::new(model, &query, &label, &columns, style).render(area, &mut buffer, state);
+        buffer
+    };
+    let _ = render(&model, &mut state);
+    assert_eq!(state.take_model_event::<()>(), None);
+
+    model.names.truncate(5);
+    let buffer = render(&model, &mut state);
+    let row = (0..area.width)
+        .map(|x| buffer[(x, 5)].symbol())
+        .collect::<String>();
+    assert!(row.contains("└── <missing"), "{row:?}");
+    assert_eq!(buffer[(20, 5)].fg, Color::Red);
+    assert_eq!(
+        state.take_model_event::<()>(),
+        Some(TreeEvent::ModelInconsistent(5))
+    );
+    assert_eq!(state.take_model_event::<()>(), None);
+
+    let _ = render(&model, &mut state);
+    assert_eq!(
+        state.take_model_event::<()>(),
+        None,
+        "reported once per projection"
+    );
+}
+
+#[test]
+fn ellipsis_columns_shorten_wide_text_and_keep_the_cell_style() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let label = Label;
+    let columns = TreeColumnSet::new([
+        ColumnDef::tree("Name", ColumnWidth::fixed(4)),
+        ColumnDef::data_owned(
+            "Desc",
+            ColumnWidth::fixed(6),
+            |model: &Model, id: usize, _: &TreeRowContext<'_>| {
+                Cell::from(model.names[id].clone()).style(Style::new().fg(Color::Red))
+            },
+        )
+        .with_text(|model: &Model, id| Cow::Borrowed(model.names[id].as_str()))
+        .with_overflow(TreeCellOverflow::Ellipsis),
+    ])
+    .expect("one tree column")
+    .without_header();
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 14, 6);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle {
+            highlight_symbol: "",
+            ..TreeListViewStyle::borderless()
+        },
+    )
+    .render(area, &mut buffer, &mut state);
+    let cells = |y: u16| (5..11).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+
+    assert_eq!(cells(1), "alpha ");
+    assert_eq!(cells(5), "epsil…");
+    assert_eq!(buffer[(10, 5)].fg, Color::Red);
+}
+
+#[test]
+fn overflow_modes_fit_the_rendered_cell() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let label = Label;
+    let render = |overflow| {
+        let columns = TreeColumnSet::new([
+            ColumnDef::tree("Name", ColumnWidth::fixed(4)),
+            ColumnDef::data_owned(
+                "Desc",
+                ColumnWidth::fixed(8),
+                |_: &Model, _: usize, _: &TreeRowContext<'_>| {
+                    Cell::from(Line::from(vec![
+                        Span::raw("alpha "),
+                        Span::styled("beta", Style::new().fg(Color::Red)),
+                    ]))
+                },
+            )
+            .with_overflow(overflow),
+        ])
+        .expect("one tree column")
+        .without_header();
+        let mut state = TreeListViewState::new();
+        let area = Rect::new(0, 0, 13, 1);
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle {
+                highlight_symbol: "",
+                ..TreeListViewStyle::borderless()
+            },
+        )
+        .render(area, &mut buffer, &mut state);
+        let cells = (5..13).map(|x| buffer[(x, 0)].symbol()).collect::<String>();
+        (cells, buffer[(12, 0)].fg)
+    };
+
+    assert_eq!(
+        render(TreeCellOverflow::Clip),
+        ("alpha be".to_owned(), Color::Red)
+    );
+    assert_eq!(
+        render(TreeCellOverflow::Ellipsis),
+        ("alpha b…".to_owned(), Color::Red)
+    );
+    assert_eq!(render(TreeCellOverflow::Wrap).0, "alpha   ");
+}
+
+#[test]
+fn overscroll_brings_the_last_row_to_the_top() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let area = Rect::new(0, 0, 30, 3);
+    let render = |state: &mut TreeListViewState<usize>, overscroll| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle {
+                overscroll,
+                ..TreeListViewStyle::borderless()
+            },
+        )
+        .render(area, &mut buffer, state);
+        buffer
+    };
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let _ = render(&mut state, TreeOverscroll::Disabled);
+
+    assert!(state.set_offset(5));
+    let _ = render(&mut state, TreeOverscroll::Disabled);
+    assert_eq!(state.offset(), 3);
+
+    assert!(state.set_offset(5));
+    let buffer = render(&mut state, TreeOverscroll::Enabled);
+    assert_eq!(state.offset(), 5);
+    let row = |y| (0..29).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+    assert!(row(0).contains("epsilon"));
+    assert_eq!(row(1).trim(), "");
+    assert_eq!(buffer[(29, 2)].symbol(), "▼");
+}
+
+#[test]
+fn page_jump_scrolls_by_whole_viewports() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 0));
+    let area = Rect::new(0, 0, 30, 2);
+    let step = |state: &mut TreeListViewState<usize>, action| {
+        let _ = state.handle_action(&model, &query, &columns, TreeAction::<()>::View(action));
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle {
+                scroll_policy: TreeScrollPolicy::PageJump,
+                ..TreeListViewStyle::borderless()
+            },
+        )
+        .render(area, &mut buffer, state);
+        state.offset()
+    };
+
+    let offsets: Vec<usize> = (0..4)
+        .map(|_| step(&mut state, TreeViewAction::SelectNext))
+        .collect();
+    assert_eq!(offsets, [0, 2, 2, 4]);
+    assert_eq!(step(&mut state, TreeViewAction::SelectPrev), 2);
+    assert_eq!(step(&mut state, TreeViewAction::SelectPrev), 2);
+    assert_eq!(step(&mut state, TreeViewAction::SelectPrev), 0);
+}
+
 #[test]
 fn virtualized_and_full_rendering_are_identical_at_every_viewport_position() {
     for offset in [0, 2, 5] {
@@ -147,6 +461,165 @@ fn hit_testing_reports_headers_rows_columns_and_scrollbars() {
         Some(TreeHit::HorizontalScrollbar)
     );
     assert_eq!(state.hit_test(Position::new(3, 2)), None);
+    assert_eq!(
+        state.hit_test_part(Position::new(20, 3)),
+        Some(TreeHitPart::Expander)
+    );
+    assert_eq!(
+        state.hit_test_part(Position::new(22, 3)),
+        Some(TreeHitPart::Label)
+    );
+    assert_eq!(
+        state.hit_test_part(Position::new(21, 4)),
+        Some(TreeHitPart::Cell)
+    );
+    assert_eq!(
+        state.hit_test_part(Position::new(24, 4)),
+        Some(TreeHitPart::Label)
+    );
+    assert_eq!(
+        state.hit_test_part(Position::new(8, 4)),
+        Some(TreeHitPart::Cell)
+    );
+    assert_eq!(state.hit_test_part(Position::new(7, 2)), None);
+
+    assert_eq!(
+        state.handle_click::<()>(Position::new(20, 4), TreeClickKind::Double),
+        TreeEvent::CellClicked {
+            id: 1,
+            column: 1,
+            kind: TreeClickKind::Double,
+        }
+    );
+    assert_eq!(state.selected_id(), Some(1));
+    assert_eq!(
+        state.handle_click::<()>(Position::new(7, 2), TreeClickKind::Single),
+        TreeEvent::HeaderClicked {
+            column: 0,
+            kind: TreeClickKind::Single,
+        }
+    );
+    assert_eq!(state.selected_id(), Some(1));
+}
+
+#[cfg(feature = "keymap")]
+#[test]
+fn mouse_clicks_toggle_expanders_and_the_wheel_scrolls() {
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    let mouse = |kind, column, row| MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(true);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let area = Rect::new(4, 2, 22, 4);
+    let render = |state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+    };
+    render(&mut state);
+
+    let click = MouseEventKind::Down(MouseButton::Left);
+    assert_eq!(
+        state.handle_mouse::<()>(mouse(click, 20, 3)),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.selected_id(), Some(0));
+    render(&mut state);
+    assert_eq!(state.projection().len(), 6);
+
+    assert_eq!(
+        state.handle_mouse::<()>(mouse(click, 22, 4)),
+        TreeEvent::CellClicked {
+            id: 1,
+            column: 1,
+            kind: TreeClickKind::Single,
+        }
+    );
+    assert_eq!(
+        state.handle_mouse::<()>(mouse(MouseEventKind::ScrollDown, 22, 4)),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.offset(), 1);
+    render(&mut state);
+    assert_eq!(
+        state.handle_mouse::<()>(mouse(click, 20, 3)),
+        TreeEvent::CellClicked {
+            id: 1,
+            column: 1,
+            kind: TreeClickKind::Single,
+        }
+    );
+}
+
+#[test]
+fn overlay_rows_are_drawn_below_their_node_and_skipped_by_selection() {
+    let mut model = Model::sample();
+    model.diagnostics = vec![(2, "fail"), (2, "warn")];
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 30, 6);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .overlay_rows(&Diagnostics)
+    .render(area, &mut buffer, &mut state);
+
+    let line = |y: u16| -> String { (0..area.width).map(|x| buffer[(x, y)].symbol()).collect() };
+    assert!(line(2).contains("beta"), "{:?}", line(2));
+    assert!(line(3).contains("│     ! fail"), "{:?}", line(3));
+    assert!(line(4).contains("│     ! warn"), "{:?}", line(4));
+    assert!(line(5).contains("gamma"), "{:?}", line(5));
+    assert_eq!(state.visible_ids().count(), 6);
+    assert_eq!(state.visible_nodes()[3].overlay(), Some(0));
+
+    assert!(state.select_by_id(&model, &query, 2));
+    assert!(state.select_next());
+    assert_eq!(state.selected_id(), Some(3));
+    assert!(state.select_prev());
+    assert_eq!(state.selected_id(), Some(2));
+
+    let _ = state.set_overlay_selection(TreeOverlaySelection::Selectable);
+    assert!(state.select_next());
+    assert_eq!(state.selected_index(), Some(3));
+}
+
+#[test]
+fn sibling_fallback_skips_the_overlay_rows_of_the_parent() {
+    let mut model = Model::sample();
+    model.diagnostics = vec![(0, "fail")];
+    let query = TreeQuery::new().with_selection_fallback(TreeSelectionFallback::PreviousSibling);
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 1));
+    assert_eq!(state.visible_nodes()[1].overlay(), Some(0));
+
+    model.children[0].retain(|&child| child != 1);
+    model.revision.advance();
+    assert!(state.ensure_projection(&model, &query));
+    assert_eq!(state.selected_id(), Some(2));
+    assert_eq!(state.selected_index(), Some(2));
 }
 
 #[test]
@@ -159,6 +632,7 @@ fn rendering_clamps_the_offset_to_the_last_full_viewport() {
     let _ = state.expand_all(&model);
     let _ = state.ensure_projection(&model, &query);
     let _ = state.set_offset(usize::MAX);
+    let link = state.scroll_link();
     let area = Rect::new(0, 0, 20, 4);
     let mut buffer = Buffer::empty(area);
     TreeListView::new(
@@ -171,6 +645,10 @@ fn rendering_clamps_the_offset_to_the_last_full_viewport() {
     .render(area, &mut buffer, &mut state);
 
     assert_eq!(state.offset(), 3);
+    let metrics = link.metrics();
+    assert_eq!(metrics, state.scroll_metrics());
+    assert_eq!(metrics.visible_rows(), 3..6);
+    assert_eq!(metrics.rows_area, Rect::new(0, 0, 19, 3));
     assert_eq!(
         buffer.cell((3, 0)).map(ratatui::buffer::Cell::symbol),
         Some("1")
@@ -245,3 +723,1156 @@ fn horizontal_scrollbar_reaches_the_end_at_the_maximum_offset() {
         Some("►")
     );
 }
+
+#[test]
+fn pending_confirmation_is_prompted_in_the_bottom_border() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let _ = state.set_requires_confirmation(TreeEditAction::Delete, true);
+    assert!(state.select_by_id(&model, &query, 2));
+    let _ = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::Edit(TreeEditAction::Delete),
+    );
+
+    let area = Rect::new(0, 0, 40, 6);
+    let mut buffer = Buffer::empty(area);
+    let widget = TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::default(),
+    );
+    StatefulWidget::render(widget, area, &mut buffer, &mut state);
+    let bottom: String = (0..area.width)
+        .map(|x| buffer[(x, area.height - 1)].symbol())
+        .collect();
+    assert!(bottom.contains(" Delete 'beta'? y/n "), "{bottom:?}");
+}
+
+#[test]
+fn focused_headers_explain_their_column_in_the_bottom_border() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+        ColumnDef::data_owned(
+            "Sz",
+            ColumnWidth::fixed(4),
+            |_: &Model, _, _: &TreeRowContext<'_>| Cell::from("1k"),
+        )
+        .with_description("size on disk"),
+    ])
+    .expect("one tree column");
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.ensure_projection(&model, &query);
+    let select_header = TreeAction::<()>::View(TreeViewAction::SelectHeaderRow);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, select_header),
+        TreeEvent::Unchanged
+    );
+    assert!(state.select_column(Some(1), 2));
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, select_header),
+        TreeEvent::HeaderSelected { column: 1 }
+    );
+
+    let render = |state: &mut TreeListViewState<usize>| {
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buffer = Buffer::empty(area);
+        let widget = TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::default(),
+        );
+        StatefulWidget::render(widget, area, &mut buffer, state);
+        (0..area.width)
+            .map(|x| buffer[(x, area.height - 1)].symbol())
+            .collect::<String>()
+    };
+    let bottom = render(&mut state);
+    assert!(bottom.contains(" Sz: size on disk "), "{bottom:?}");
+
+    let _ = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::View(TreeViewAction::SelectNext),
+    );
+    assert!(!state.is_header_selected());
+    assert!(!render(&mut state).contains("size on disk"));
+}
+
+#[test]
+fn wide_tables_only_render_visible_columns_and_scroll_by_column() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let label = Label;
+    let calls: [std::cell::Cell<usize>; 6] = Default::default();
+    let data = calls.iter().enumerate().map(|(index, calls)| {
+        ColumnDef::data_owned(
+            format!("C{index}"),
+            ColumnWidth::fixed(8),
+            move |_: &Model, _, _: &TreeRowContext<'_>| {
+                calls.set(calls.get() + 1);
+                Cell::from(format!("c{index}"))
+            },
+        )
+    });
+    let columns = TreeColumnSet::new(
+        std::iter::once(ColumnDef::tree("Name", ColumnWidth::fixed(10))).chain(data),
+    )
+    .expect("one tree column")
+    .without_header();
+    let mut state = TreeListViewState::new();
+    let area = Rect::new(0, 0, 20, 3);
+    let render = |state: &mut TreeListViewState<usize>| {
+        for calls in &calls {
+            calls.set(0);
+        }
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+        let counts: Vec<usize> = calls.iter().map(std::cell::Cell::get).collect();
+        (buffer, counts)
+    };
+
+    let (_, counts) = render(&mut state);
+    assert_eq!(counts, [1, 0, 0, 0, 0, 0]);
+    assert_eq!(state.first_visible_column(), Some(0));
+
+    let right = TreeAction::<()>::View(TreeViewAction::ScrollColumnsRight);
+    let _ = state.handle_action(&model, &query, &columns, right);
+    assert_eq!(state.horizontal_offset(), 11);
+    let (buffer, counts) = render(&mut state);
+    assert_eq!(counts, [1, 1, 0, 0, 0, 0]);
+    assert_eq!(state.first_visible_column(), Some(1));
+    assert_eq!(buffer[(3, 0)].symbol(), "c");
+    assert_eq!(buffer[(4, 0)].symbol(), "0");
+
+    let left = TreeAction::<()>::View(TreeViewAction::ScrollColumnsLeft);
+    let _ = state.handle_action(&model, &query, &columns, left);
+    assert_eq!(state.horizontal_offset(), 0);
+}
+
+#[test]
+fn resized_columns_shift_later_columns_and_survive_snapshots() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let area = Rect::new(0, 0, 40, 1);
+    let render = |state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+        (0..area.width)
+            .map(|x| buffer[(x, 0)].symbol())
+            .collect::<String>()
+    };
+    let mut state = TreeListViewState::new();
+    assert!(state.select_by_id(&model, &query, 0));
+    let _ = render(&mut state);
+
+    let shrink = TreeAction::<()>::View(TreeViewAction::ShrinkColumn);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, shrink),
+        TreeEvent::Unchanged,
+        "no column is selected"
+    );
+    let _ = state.select_column(Some(0), 2);
+    let _ = state.handle_action_counted(&model, &query, &columns, shrink, 4);
+    assert_eq!(state.column_widths().widths(), [Some(8)]);
+    let grow = TreeAction::<()>::View(TreeViewAction::GrowColumn);
+    let _ = state.handle_action(&model, &query, &columns, grow);
+    assert_eq!(state.column_widths().width(0), Some(9));
+
+    let row = render(&mut state);
+    assert!(row.starts_with(">> 0:root    ▶ root"), "{row:?}");
+
+    let mut restored = TreeListViewState::from_snapshot(state.snapshot());
+    assert!(restored.select_by_id(&model, &query, 0));
+    assert_eq!(render(&mut restored), row);
+    assert!(restored.set_column_width(0, None));
+    assert!(restored.column_widths().is_empty());
+}
+
+#[test]
+fn row_number_gutter_precedes_the_highlight_symbol() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 2));
+    let area = Rect::new(0, 0, 40, 4);
+
+    let mut render = |row_numbers| {
+        let mut buffer = Buffer::empty(area);
+        let style = TreeListViewStyle {
+            row_numbers,
+            ..TreeListViewStyle::borderless()
+        };
+        TreeListView::new(&model, &query, &label, &columns, style).render(
+            area,
+            &mut buffer,
+            &mut state,
+        );
+        (0..area.height)
+            .map(|y| (0..5).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        render(TreeRowNumbers::Absolute),
+        ["1 ", "2 ", "3 >>", "4 "].map(|line| format!("{line:<5}"))
+    );
+    assert_eq!(
+        render(TreeRowNumbers::Relative),
+        ["2 ", "1 ", "3 >>", "1 "].map(|line| format!("{line:<5}"))
+    );
+    assert_eq!(
+        state.hit_test(Position::new(0, 2)),
+        None,
+        "the gutter is outside the rows"
+    );
+}
+
+#[test]
+fn follow_tail_pins_appended_rows_until_the_user_scrolls_up() {
+    let mut model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    state.set_follow_tail(TreeFollowTail::Enabled);
+    let area = Rect::new(0, 0, 40, 3);
+    let render = |model: &Model, state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+    };
+
+    render(&model, &mut state);
+    assert_eq!(state.offset(), 3);
+    model.append("zeta");
+    render(&model, &mut state);
+    assert_eq!(state.offset(), 4);
+
+    assert!(state.scroll_view_by(-1));
+    model.append("eta");
+    render(&model, &mut state);
+    assert_eq!(state.offset(), 3, "scrolling up suspends following");
+
+    assert!(state.scroll_to_tail());
+    render(&model, &mut state);
+    model.append("theta");
+    render(&model, &mut state);
+    assert_eq!(state.offset(), 6);
+}
+
+#[test]
+fn spectators_draw_the_primary_view_without_touching_the_state() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(true);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 5));
+    let widget = || {
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+    };
+    let area = Rect::new(0, 0, 30, 4);
+    let mut primary = Buffer::empty(area);
+    widget().render(area, &mut primary, &mut state);
+    let offset = state.offset();
+    let hit = state.hit_test(Position::new(20, 1));
+
+    let state = &state;
+    let mut spectator = Buffer::empty(area);
+    widget().render_spectator(area, &mut spectator, state);
+    assert_eq!(spectator, primary);
+
+    let overlay = Rect::new(0, 0, 30, 8);
+    let mut tall = Buffer::empty(overlay);
+    widget().render_spectator(overlay, &mut tall, state);
+    assert_eq!(state.offset(), offset);
+    assert_eq!(state.hit_test(Position::new(20, 1)), hit);
+    assert_eq!(
+        tall.cell((3, 1)).map(ratatui::buffer::Cell::symbol),
+        Some("0"),
+        "the taller pane clamps its own offset and shows the root"
+    );
+}
+
+#[test]
+fn models_can_opt_rows_out_of_tree_guides() {
+    let mut model = Model::sample();
+    model.unguided.push(2);
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 40, 4);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .render(area, &mut buffer, &mut state);
+
+    let guide = |y| buffer.cell((16, y)).map(ratatui::buffer::Cell::symbol);
+    assert_eq!(guide(1), Some("├"));
+    assert_eq!(guide(2), Some(" "), "beta is drawn without guides");
+    assert_eq!(guide(3), Some("├"));
+}
+
+#[test]
+fn columns_view_lays_out_parents_siblings_and_children() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 2));
+    let area = Rect::new(0, 0, 42, 5);
+    let mut buffer = Buffer::empty(area);
+    TreeColumnsView::new(&model, &query, &label, TreeListViewStyle::borderless()).render(
+        area,
+        &mut buffer,
+        &mut state,
+    );
+
+    let lines: Vec<String> = (0..area.height)
+        .map(|y| (0..area.width).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect();
+    assert_eq!(lines[0].trim_end(), "root ▶      alpha");
+    assert_eq!(lines[1].trim_end(), "         >> beta");
+
+    assert!(state.select_by_id(&model, &query, 0));
+    let mut buffer = Buffer::empty(area);
+    TreeColumnsView::new(&model, &query, &label, TreeListViewStyle::borderless()).render(
+        area,
+        &mut buffer,
+        &mut state,
+    );
+    let first: String = (0..area.width).map(|x| buffer[(x, 0)].symbol()).collect();
+    assert_eq!(first.trim_end(), "         >> root ▶        alpha");
+}
+
+#[test]
+fn columns_view_clicks_select_the_listed_rows() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.select_by_id(&model, &query, 2));
+    let area = Rect::new(0, 0, 42, 5);
+    let mut buffer = Buffer::empty(area);
+    TreeColumnsView::new(&model, &query, &label, TreeListViewStyle::borderless()).render(
+        area,
+        &mut buffer,
+        &mut state,
+    );
+
+    assert_eq!(
+        state.hit_test(Position::new(12, 3)),
+        Some(TreeHit::Row {
+            id: 4,
+            index: 4,
+            column: None,
+        })
+    );
+    assert_eq!(
+        state.hit_test(Position::new(0, 1)),
+        None,
+        "the parent pane lists one row"
+    );
+    assert_eq!(
+        state.handle_click::<()>(Position::new(0, 0), TreeClickKind::Single),
+        TreeEvent::Changed
+    );
+    assert_eq!(state.selected_id(), Some(0));
+}
+
+#[test]
+fn border_type_and_title_placement_come_from_the_style() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let area = Rect::new(0, 0, 20, 4);
+    let mut buffer = Buffer::empty(area);
+    let style = TreeListViewStyle {
+        title: Some("Tree".into()),
+        bottom_title: Some("1/6".into()),
+        title_alignment: ratatui::layout::Alignment::Right,
+        border_type: ratatui::widgets::BorderType::Rounded,
+        ..TreeListViewStyle::default()
+    };
+    TreeListView::new(&model, &query, &label, &columns, style).render(
+        area,
+        &mut buffer,
+        &mut state,
+    );
+
+    let line = |y| -> String { (0..area.width).map(|x| buffer[(x, y)].symbol()).collect() };
+    assert_eq!(line(0), "╭──────────────Tree╮");
+    assert_eq!(line(3), "╰───────────────1/6╯");
+}
+
+#[test]
+fn fill_fractions_paint_the_row_background_behind_the_cells() {
+    let mut model = Model::sample();
+    model.fills = vec![(1, 0.5), (2, 1.0), (3, f32::NAN)];
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 20, 8);
+    let mut buffer = Buffer::empty(area);
+    let style = TreeListViewStyle {
+        fill_style: Style::default().bg(Color::Blue),
+        ..TreeListViewStyle::borderless()
+    };
+    TreeListView::new(&model, &query, &label, &columns, style).render(
+        area,
+        &mut buffer,
+        &mut state,
+    );
+
+    let filled = |y: u16| {
+        (0..area.width)
+            .filter(|x| buffer[(*x, y)].bg == Color::Blue)
+            .collect::<Vec<_>>()
+    };
+    assert!(filled(0).is_empty());
+    assert_eq!(filled(1), (3..12).collect::<Vec<_>>());
+    assert_eq!(filled(2), (3..20).collect::<Vec<_>>());
+    assert!(filled(3).is_empty());
+    assert_eq!(buffer[(3, 1)].symbol(), "1");
+}
+
+#[test]
+fn collapsed_branches_render_aggregated_column_values() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::data_owned(
+            "Detail",
+            ColumnWidth::fixed(8),
+            |model: &Model, id: usize, _: &TreeRowContext<'_>| Cell::from(model.names[id].clone()),
+        )
+        .with_aggregate_owned(|model: &Model, id: usize, _: &TreeRowContext<'_>| {
+            Cell::from(format!("sum {}", model.children[id].len()))
+        }),
+        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+    ])
+    .expect("one tree column")
+    .without_header();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let area = Rect::new(0, 0, 30, 7);
+    let render = |state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+        (3..8).map(|x| buffer[(x, 0)].symbol()).collect::<String>()
+    };
+
+    assert_eq!(render(&mut state), "sum 5");
+    let _ = state.expand_all(&model);
+    assert_eq!(render(&mut state), "root ");
+}
+
+struct BannerLabel;
+
+impl TreeLabelRenderer<Model> for BannerLabel {
+    fn cell<'a>(
+        &'a self,
+        model: &'a Model,
+        id: usize,
+        context: &TreeRowContext<'_>,
+        glyphs: &TreeGlyphs<'a>,
+    ) -> Cell<'a> {
+        Label.cell(model, id, context, glyphs)
+    }
+
+    fn root_banner<'a>(&'a self, model: &'a Model, root: usize) -> Option<Text<'a>> {
+        Some(Text::from(vec![
+            Line::from(model.names[root].as_str()),
+            Line::from(format!("{} children", model.children[root].len())),
+        ]))
+    }
+}
+
+#[test]
+fn root_banners_render_above_the_rows_and_shift_hit_testing() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = BannerLabel;
+    let mut state = TreeListViewState::new();
+    let area = Rect::new(0, 0, 40, 5);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .render(area, &mut buffer, &mut state);
+
+    let line = |y: u16| {
+        (0..area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    };
+    assert_eq!(line(0).trim_end(), "root");
+    assert_eq!(line(1).trim_end(), "5 children");
+    assert!(line(2).contains("0:root"));
+    assert!(matches!(
+        state.hit_test(Position::new(4, 2)),
+        Some(TreeHit::Row { id: 0, .. })
+    ));
+}
+
+#[test]
+fn labels_spill_into_columns_that_absorb_overflow() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::tree("Name", ColumnWidth::fixed(4)),
+        ColumnDef::data_owned(
+            "Size",
+            ColumnWidth::fixed(6),
+            |_: &Model, _, _: &TreeRowContext<'_>| Cell::from("size"),
+        )
+        .absorb_label_overflow(|model: &Model, id| !model.children[id].is_empty()),
+    ])
+    .expect("one tree column")
+    .without_header();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 20, 3);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .render(area, &mut buffer, &mut state);
+
+    let line = |y: u16| {
+        (0..area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    };
+    assert!(line(0).contains("root"), "{:?}", line(0));
+    assert!(!line(0).contains("size"));
+    assert!(line(1).contains("size"), "{:?}", line(1));
+}
+
+#[test]
+fn search_bar_scrolls_the_query_to_keep_the_cursor_visible() {
+    let mut bar = TreeSearchBar::new().with_cursor_style(Style::default().bg(Color::White));
+    let _ = bar.set_query("abcdefghij");
+    let area = Rect::new(2, 1, 6, 1);
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 2));
+    (&bar).render(area, &mut buffer);
+
+    let line = (area.x..area.right())
+        .map(|x| buffer[(x, 1)].symbol())
+        .collect::<String>();
+    assert_eq!(line, "/ghij ");
+    assert_eq!(bar.cursor_position(area), Position::new(7, 1));
+    assert_eq!(buffer[(7, 1)].bg, Color::White);
+
+    assert!(bar.move_cursor_to_start());
+    assert_eq!(bar.cursor_position(area), Position::new(3, 1));
+}
+
+#[test]
+fn position_indicator_reports_scroll_progress_on_the_bottom_row() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 20, 4);
+
+    let render = |state: &mut TreeListViewState<usize>, position_indicator| {
+        let mut buffer = Buffer::empty(area);
+        let style = TreeListViewStyle {
+            position_indicator,
+            ..TreeListViewStyle::borderless()
+        };
+        TreeListView::new(&model, &query, &label, &columns, style).render(area, &mut buffer, state);
+        (0..area.width)
+            .map(|x| buffer[(x, area.bottom() - 1)].symbol())
+            .collect::<String>()
+    };
+
+    assert_eq!(
+        render(&mut state, TreePositionIndicator::Percent).trim(),
+        "0%"
+    );
+    assert_eq!(
+        render(&mut state, TreePositionIndicator::Position).trim(),
+        "0/6",
+        "nothing is selected yet"
+    );
+    let _ = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::View(TreeViewAction::SelectLast),
+    );
+    assert_eq!(
+        render(&mut state, TreePositionIndicator::Percent),
+        format!("{:>20}", "100%")
+    );
+    assert_eq!(
+        render(&mut state, TreePositionIndicator::Position).trim(),
+        "6/6"
+    );
+}
+
+#[test]
+fn measure_sizes_the_pane_to_fit_every_row() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(true);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let view = || {
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::default(),
+        )
+    };
+
+    let measure = view().measure(Rect::new(0, 0, 80, 40), &mut state);
+    assert_eq!(
+        measure,
+        TreeMeasure {
+            rows: 6,
+            height: 9,
+            min_width: 30,
+            ideal_width: 30,
+        }
+    );
+    assert_eq!(
+        view().measure(Rect::new(0, 0, 20, 40), &mut state).height,
+        10,
+        "a narrow pane needs a horizontal scrollbar"
+    );
+
+    let area = Rect::new(0, 0, measure.ideal_width, measure.height);
+    let mut buffer = Buffer::empty(area);
+    view().render(area, &mut buffer, &mut state);
+    assert_eq!(state.scroll_metrics().viewport_height, 6);
+    assert!(
+        (0..area.width)
+            .map(|x| buffer[(x, 7)].symbol())
+            .collect::<String>()
+            .contains("epsilon")
+    );
+}
+
+struct Details;
+
+impl TreeDetailProvider<Model> for Details {
+    fn details<'a>(&'a self, model: &'a Model, id: usize) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+        vec![
+            (Cow::Borrowed("id"), Cow::Owned(id.to_string())),
+            (
+                Cow::Borrowed("name"),
+                Cow::Borrowed(model.names[id].as_str()),
+            ),
+        ]
+    }
+}
+
+#[test]
+fn details_popup_overlays_the_rows_below_the_selection() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let _ = state.ensure_projection(&model, &query);
+    let _ = state.select_first();
+    let event = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::View(TreeViewAction::ShowDetails),
+    );
+    assert_eq!(event, TreeEvent::Changed);
+    assert_eq!(state.details_node(), Some(0));
+
+    let area = Rect::new(0, 0, 30, 9);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::default(),
+    )
+    .details(&Details)
+    .render(area, &mut buffer, &mut state);
+    let row = |y: u16| {
+        (0..area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    };
+    assert!(row(1).contains("root"), "the selected row stays visible");
+    assert!(row(3).starts_with("│   │id   0"), "{}", row(3));
+    assert!(row(4).starts_with("│   │name root"), "{}", row(4));
+
+    assert!(state.hide_details());
+    assert!(!state.hide_details());
+}
+
+#[test]
+fn placeholder_rows_render_under_branches_whose_children_are_filtered_out() {
+    let model = Model::sample();
+    let query = TreeQuery::new()
+        .with_filter(
+            |_: &Model, id: usize| id == 0,
+            TreeFilterConfig::enabled_manual_expand(),
+            TreeRevision::INITIAL,
+        )
+        .with_placeholders(TreePlaceholders::Visible);
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+
+    let area = Rect::new(0, 0, 30, 6);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::default(),
+    )
+    .render(area, &mut buffer, &mut state);
+    let row = |y: u16| {
+        (0..area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    };
+    assert!(row(1).contains("▼ root"), "{}", row(1));
+    assert!(row(2).ends_with("└── no match│"), "{}", row(2));
+    assert_eq!(state.projection().len(), 2);
+}
+
+#[test]
+fn themes_restyle_rows_guides_and_scrollbars_together() {
+    assert_eq!(
+        TreeListViewStyle::from_theme(TreeTheme::default()),
+        TreeListViewStyle::default()
+    );
+
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let _ = state.ensure_projection(&model, &query);
+    let _ = state.select_first();
+    let theme = TreeTheme::dark();
+    let area = Rect::new(0, 0, 30, 4);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle {
+            borders: Borders::NONE,
+            ..TreeListViewStyle::from_theme(theme)
+        },
+    )
+    .render(area, &mut buffer, &mut state);
+
+    assert_eq!(buffer[(4, 0)].bg, theme.selection.bg.expect("background"));
+    assert_eq!(buffer[(16, 1)].fg, theme.guides.fg.expect("foreground"));
+    assert_eq!(buffer[(29, 0)].fg, theme.scrollbar.fg.expect("foreground"));
+}
+
+#[test]
+fn inherited_marks_use_their_own_style() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.set_marks(1..6, true));
+    let area = Rect::new(0, 0, 30, 6);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle {
+            marked_style: Style::new().fg(Color::Yellow),
+            inherited_mark_style: Style::new().fg(Color::DarkGray),
+            ..TreeListViewStyle::borderless()
+        },
+    )
+    .render(area, &mut buffer, &mut state);
+
+    assert_eq!(buffer[(20, 0)].fg, Color::DarkGray);
+    assert_eq!(buffer[(20, 1)].fg, Color::Yellow);
+}
+
+#[test]
+fn flagged_cells_reach_data_column_renderers() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::data_owned(
+            "Target",
+            ColumnWidth::fixed(3),
+            |_: &Model, _, context: &TreeRowContext<'_>| {
+                Cell::from(if context.is_cell_flagged(0) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                })
+            },
+        ),
+        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+    ])
+    .expect("one tree column")
+    .without_header();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    assert!(state.set_cell_flagging(TreeCellFlagging::Enabled));
+    assert!(state.select_by_id(&model, &query, 2));
+    let _ = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::View(TreeViewAction::SelectColumnRight),
+    );
+    let _ = state.handle_action(
+        &model,
+        &query,
+        &columns,
+        TreeAction::<()>::View(TreeViewAction::ToggleMark),
+    );
+    assert!(state.is_cell_flagged(2, 0));
+    let area = Rect::new(0, 0, 16, 4);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .render(area, &mut buffer, &mut state);
+
+    let cell = |row: u16| {
+        (3..6)
+            .map(|x| buffer[(x, row)].symbol())
+            .collect::<String>()
+    };
+    assert_eq!(cell(1), "[ ]");
+    assert_eq!(cell(2), "[x]");
+}
+
+#[test]
+fn style_rules_color_data_cells_by_value() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([
+        ColumnDef::data_owned(
+            "Size",
+            ColumnWidth::fixed(4),
+            |model: &Model, id: usize, _: &TreeRowContext<'_>| {
+                Cell::from(model.names[id].len().to_string()).style(Style::new().bg(Color::Blue))
+            },
+        )
+        .with_style_rule(|model: &Model, id| {
+            (model.names[id].len() > 4).then(|| Style::new().fg(Color::Red))
+        }),
+        ColumnDef::tree("Name", ColumnWidth::fixed(12)),
+    ])
+    .expect("one tree column")
+    .without_header();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 20, 3);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(
+        &model,
+        &query,
+        &label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .render(area, &mut buffer, &mut state);
+
+    let root = &buffer[(3, 0)];
+    assert_eq!((root.fg, root.bg), (Color::Reset, Color::Blue));
+    let alpha = &buffer[(3, 1)];
+    assert_eq!((alpha.fg, alpha.bg), (Color::Red, Color::Blue));
+}
+
+#[test]
+fn legend_explains_mark_styles_in_the_bottom_border() {
+    let model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let style = TreeListViewStyle {
+        marked_style: Style::new().fg(Color::Yellow),
+        ..TreeListViewStyle::default()
+    };
+    let mut legend = TreeLegendEntry::marks(&style).to_vec();
+    legend.push(TreeLegendEntry::new("*", "pinned"));
+    let area = Rect::new(0, 0, 48, 5);
+    let mut buffer = Buffer::empty(area);
+    TreeListView::new(&model, &query, &label, &columns, style)
+        .legend(&legend)
+        .render(area, &mut buffer, &mut state);
+
+    let footer = (0..area.width)
+        .map(|x| buffer[(x, 4)].symbol())
+        .collect::<String>();
+    assert!(
+        footer.contains(" ■ marked  ■ partial  ■ inherited  * pinned "),
+        "{footer}"
+    );
+    let swatch = footer
+        .find('■')
+        .map(|index| footer[..index].chars().count());
+    let swatch = u16::try_from(swatch.expect("legend is drawn")).expect("fits the area");
+    assert_eq!(buffer[(swatch, 4)].fg, Color::Yellow);
+}
+
+#[test]
+fn buffer_text_skips_cells_covered_by_wide_characters() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 2));
+    buffer.set_string(0, 0, "日本 x", Style::default());
+    assert_eq!(tree_buffer_text(&buffer), "日本 x\n");
+}
+
+#[test]
+fn page_actions_move_by_the_rendered_viewport_height() {
+    let mut model = Model::sample();
+    for index in 0..14 {
+        model.append(&format!("extra {index}"));
+    }
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 30, 6);
+    let render = |state: &mut TreeListViewState<usize>| {
+        let mut buffer = Buffer::empty(area);
+        TreeListView::new(
+            &model,
+            &query,
+            &label,
+            &columns,
+            TreeListViewStyle::borderless(),
+        )
+        .render(area, &mut buffer, state);
+    };
+    render(&mut state);
+    assert_eq!(state.scroll_metrics().viewport_height, 6);
+    assert!(state.select_index(Some(1)));
+
+    let page_down = TreeAction::<()>::View(TreeViewAction::PageDown);
+    let page_up = TreeAction::<()>::View(TreeViewAction::PageUp);
+    assert_eq!(
+        state.handle_action(&model, &query, &columns, page_down),
+        TreeEvent::Changed
+    );
+    assert_eq!((state.selected_index(), state.offset()), (Some(7), 6));
+    render(&mut state);
+    assert_eq!(state.offset(), 6);
+
+    let _ = state.handle_action_counted(&model, &query, &columns, page_down, 2);
+    render(&mut state);
+    assert_eq!((state.selected_index(), state.offset()), (Some(19), 14));
+
+    let _ = state.handle_action(&model, &query, &columns, page_up);
+    render(&mut state);
+    assert_eq!((state.selected_index(), state.offset()), (Some(13), 8));
+}
+
+#[test]
+fn label_scrollbar_shows_how_much_of_the_widest_label_fits() {
+    let mut model = Model::sample();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([ColumnDef::tree("Name", ColumnWidth::fixed(14))])
+        .expect("one tree column")
+        .without_header();
+    let label = Label;
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let area = Rect::new(0, 0, 20, 5);
+    let mut render = |model: &Model, label_scrollbar| {
+        let style = TreeListViewStyle {
+            label_scrollbar,
+            ..TreeListViewStyle::default()
+        };
+        let text = TreeListView::new(model, &query, &label, &columns, style).render_to_string(
+            area.width,
+            area.height,
+            &mut state,
+        );
+        text.lines().last().unwrap_or_default().to_owned()
+    };
+
+    assert!(!render(&model, TreeLabelScrollbar::Visible).contains('━'));
+    model.names[2] = "a label twice as wide".to_owned();
+    assert_eq!(
+        render(&model, TreeLabelScrollbar::Hidden),
+        format!("└{}┘", "─".repeat(18))
+    );
+    assert_eq!(
+        render(&model, TreeLabelScrollbar::Visible),
+        format!("└───{}{}┘", "━".repeat(7), "─".repeat(8)),
+        "the column shows half of the widest label"
+    );
+}
+
+#[test]
+fn label_scrollbar_keeps_the_legend_and_position_indicator() {
+    let mut model = Model::sample();
+    model.names[2] = "a label twice as wide".to_owned();
+    let query = TreeQuery::new();
+    let columns = TreeColumnSet::new([ColumnDef::tree("Name", ColumnWidth::fixed(14))])
+        .expect("one tree column")
+        .without_header();
+    let label = Label;
+    let legend = [TreeLegendEntry::new("*", "x")];
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let style = TreeListViewStyle {
+        label_scrollbar: TreeLabelScrollbar::Visible,
+        position_indicator: TreePositionIndicator::Percent,
+        ..TreeListViewStyle::default()
+    };
+    let text = TreeListView::new(&model, &query, &label, &columns, style)
+        .legend(&legend)
+        .render_to_string(20, 5, &mut state);
+
+    assert_eq!(
+        text.lines().last().unwrap_or_default(),
+        "└ * x ━━━━━──────0%┘",
+        "the thumb fills only the border cells the titles leave free"
+    );
+}
+
+#[test]
+fn selected_overlay_rows_do_not_target_their_node() {
+    let mut model = Model::sample();
+    model.diagnostics = vec![(2, "fail")];
+    let query = TreeQuery::new();
+    let columns = columns(false);
+    let mut state = TreeListViewState::new();
+    let _ = state.expand_all(&model);
+    let _ = state.set_overlay_selection(TreeOverlaySelection::Selectable);
+    let area = Rect::new(0, 0, 30, 6);
+    TreeListView::new(
+        &model,
+        &query,
+        &Label,
+        &columns,
+        TreeListViewStyle::borderless(),
+    )
+    .overlay_rows(&Diagnostics)
+    .render(area, &mut Buffer::empty(area), &mut state);
+    assert!(state.select_index(Some(3)));
+    assert_eq!(state.visible_nodes()[3].overlay(), Some(0));
+
+    for action in [
+        TreeAction::<()>::View(TreeViewAction::ToggleMark),
+        TreeAction::View(TreeViewAction::SetAnchor),
+        TreeAction::Edit(TreeEditAction::Delete),
+    ] {
+        assert_eq!(
+            state.handle_action(&model, &query, &columns, action),
+            TreeEvent::Unchanged
+        );
+    }
+    assert!(!state.is_manually_marked(2));
+}